@@ -44,6 +44,7 @@ use objc2_foundation::{
 use tracing::{trace, warn};
 use winit_common::core_foundation::MainRunLoop;
 use winit_core::cursor::Cursor;
+use winit_core::drag::DragActions;
 use winit_core::error::{NotSupportedError, RequestError};
 use winit_core::event::{SurfaceSizeWriter, WindowEvent};
 use winit_core::icon::Icon;
@@ -386,7 +387,12 @@ define_class!(
             let position =
                 LogicalPosition::<f64>::from((dl.x, dl.y)).to_physical(self.scale_factor());
 
-            self.queue_event(WindowEvent::DragEntered { paths, position });
+            self.queue_event(WindowEvent::DragEntered {
+                paths,
+                mime_types: Vec::new(),
+                actions: DragActions::COPY,
+                position,
+            });
 
             true
         }
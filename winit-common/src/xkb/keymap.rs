@@ -1,6 +1,6 @@
 //! XKB keymap.
 
-use std::ffi::c_char;
+use std::ffi::{CStr, c_char};
 use std::ops::Deref;
 use std::ptr::{self, NonNull};
 
@@ -19,7 +19,7 @@ use {memmap2::MmapOptions, std::os::unix::io::OwnedFd};
 
 #[cfg(feature = "x11")]
 use super::XKBXH;
-use super::{XKBH, XkbContext};
+use super::{XKBH, XKBLH, XkbContext};
 
 /// Map the raw X11-style keycode to the `KeyCode` enum.
 ///
@@ -977,6 +977,7 @@ pub fn keysym_location(keysym: u32) -> KeyLocation {
 pub struct XkbKeymap {
     keymap: NonNull<xkb_keymap>,
     _mods_indices: ModsIndices,
+    layout_names: Vec<String>,
     pub _core_keyboard_id: i32,
 }
 
@@ -1028,7 +1029,14 @@ impl XkbKeymap {
             mod5: mod_index_for_name(keymap, b"Mod5\0"),
         };
 
-        Self { keymap, _mods_indices: mods_indices, _core_keyboard_id }
+        let layout_names = layout_names(keymap);
+
+        Self { keymap, _mods_indices: mods_indices, layout_names, _core_keyboard_id }
+    }
+
+    /// The names of the layouts, by layout index.
+    pub fn layout_names(&self) -> &[String] {
+        &self.layout_names
     }
 
     #[cfg(feature = "x11")]
@@ -1092,6 +1100,22 @@ pub struct ModsIndices {
     pub mod5: Option<xkb_mod_index_t>,
 }
 
+/// Read the names of the layouts of `keymap`, by layout index.
+fn layout_names(keymap: NonNull<xkb_keymap>) -> Vec<String> {
+    let num_layouts = unsafe { (XKBH.xkb_keymap_num_layouts)(keymap.as_ptr()) };
+    (0..num_layouts)
+        .map(|layout| {
+            let name = XKBLH.as_ref().map_or(ptr::null(), |xkblh| unsafe {
+                (xkblh.xkb_keymap_layout_get_name)(keymap.as_ptr(), layout)
+            });
+            if name.is_null() {
+                return String::new();
+            }
+            unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned()
+        })
+        .collect()
+}
+
 fn mod_index_for_name(keymap: NonNull<xkb_keymap>, name: &[u8]) -> Option<xkb_mod_index_t> {
     unsafe {
         let mod_index =
@@ -1099,3 +1123,36 @@ fn mod_index_for_name(keymap: NonNull<xkb_keymap>, name: &[u8]) -> Option<xkb_mo
         if mod_index == XKB_MOD_INVALID { None } else { Some(mod_index) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_names_by_index() {
+        if xkb::xkbcommon_option().is_none() {
+            return;
+        }
+        let context = XkbContext::new().unwrap();
+
+        let names = xkb::xkb_rule_names {
+            rules: ptr::null(),
+            model: ptr::null(),
+            layout: c"us,de".as_ptr(),
+            variant: ptr::null(),
+            options: ptr::null(),
+        };
+        let keymap = unsafe {
+            (XKBH.xkb_keymap_new_from_names)(
+                context.as_ptr(),
+                &names,
+                xkb_keymap_compile_flags::XKB_KEYMAP_COMPILE_NO_FLAGS,
+            )
+        };
+        // The keyboard configuration data may not be installed.
+        let Some(keymap) = NonNull::new(keymap) else { return };
+        let keymap = XkbKeymap::new_inner(keymap, 0);
+
+        assert_eq!(keymap.layout_names(), ["English (US)", "German"]);
+    }
+}
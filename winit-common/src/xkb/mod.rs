@@ -8,7 +8,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 
 use smol_str::SmolStr;
 use winit_core::event::{ElementState, KeyEvent};
-use winit_core::keyboard::{Key, KeyLocation};
+use winit_core::keyboard::{Key, KeyLocation, KeyboardLayouts};
 use xkbcommon_dl::{
     self as xkb, XkbCommon, XkbCommonCompose, xkb_compose_status, xkb_context, xkb_context_flags,
     xkbcommon_compose_handle, xkbcommon_handle,
@@ -34,6 +34,17 @@ static XKBH: LazyLock<&'static XkbCommon> = LazyLock::new(xkbcommon_handle);
 static XKBCH: LazyLock<&'static XkbCommonCompose> = LazyLock::new(xkbcommon_compose_handle);
 #[cfg(feature = "x11")]
 static XKBXH: LazyLock<&'static xkb::x11::XkbCommonX11> = LazyLock::new(xkbcommon_x11_handle);
+static XKBLH: LazyLock<Option<XkbCommonLayouts>> = LazyLock::new(|| {
+    ["libxkbcommon.so.0", "libxkbcommon.so"]
+        .into_iter()
+        .find_map(|name| unsafe { XkbCommonLayouts::open(name) }.ok())
+});
+
+// The functions `xkbcommon-dl` doesn't bind.
+dlib::dlopen_external_library!(XkbCommonLayouts,
+functions:
+    fn xkb_keymap_layout_get_name(*mut xkb::xkb_keymap, xkb::xkb_layout_index_t) -> *const c_char,
+);
 
 #[inline(always)]
 pub fn reset_dead_keys() {
@@ -124,6 +135,13 @@ impl Context {
         self.keymap.as_mut()
     }
 
+    /// The layouts of the current keymap, with the active one.
+    pub fn keyboard_layouts(&mut self) -> Option<KeyboardLayouts> {
+        let names = self.keymap.as_ref()?.layout_names().to_vec();
+        let active = self.state.as_mut()?.active_layout() as usize;
+        Some(KeyboardLayouts { names, active })
+    }
+
     #[cfg(feature = "wayland")]
     pub fn set_keymap_from_fd(&mut self, fd: OwnedFd, size: usize) {
         let keymap = XkbKeymap::from_fd(&self.context, fd, size);
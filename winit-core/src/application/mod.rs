@@ -1,7 +1,9 @@
 //! End user application handling.
 
+use crate::clipboard::ClipboardEvent;
 use crate::event::{DeviceEvent, DeviceId, StartCause, WindowEvent};
 use crate::event_loop::ActiveEventLoop;
+use crate::monitor::MonitorEvent;
 use crate::window::WindowId;
 
 pub mod macos;
@@ -211,6 +213,25 @@ pub trait ApplicationHandler {
         let _ = (event_loop, device_id, event);
     }
 
+    /// Emitted when a clipboard request completes or the clipboard changes owner.
+    ///
+    /// See [`Clipboard`][crate::clipboard::Clipboard].
+    fn clipboard_event(&mut self, event_loop: &dyn ActiveEventLoop, event: ClipboardEvent) {
+        let _ = (event_loop, event);
+    }
+
+    /// Emitted when a monitor is connected, disconnected, or its configuration changes.
+    ///
+    /// The monitors available when the event loop starts aren't reported, query them with
+    /// [`ActiveEventLoop::available_monitors()`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// Only emitted on X11 and Wayland.
+    fn monitor_event(&mut self, event_loop: &dyn ActiveEventLoop, event: MonitorEvent) {
+        let _ = (event_loop, event);
+    }
+
     /// Emitted when the event loop is about to block and wait for new events.
     ///
     /// Most applications shouldn't need to hook into this event since there is no real relationship
@@ -387,6 +408,16 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for &mut A {
         (**self).device_event(event_loop, device_id, event);
     }
 
+    #[inline]
+    fn clipboard_event(&mut self, event_loop: &dyn ActiveEventLoop, event: ClipboardEvent) {
+        (**self).clipboard_event(event_loop, event);
+    }
+
+    #[inline]
+    fn monitor_event(&mut self, event_loop: &dyn ActiveEventLoop, event: MonitorEvent) {
+        (**self).monitor_event(event_loop, event);
+    }
+
     #[inline]
     fn about_to_wait(&mut self, event_loop: &dyn ActiveEventLoop) {
         (**self).about_to_wait(event_loop);
@@ -455,6 +486,16 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for Box<A> {
         (**self).device_event(event_loop, device_id, event);
     }
 
+    #[inline]
+    fn clipboard_event(&mut self, event_loop: &dyn ActiveEventLoop, event: ClipboardEvent) {
+        (**self).clipboard_event(event_loop, event);
+    }
+
+    #[inline]
+    fn monitor_event(&mut self, event_loop: &dyn ActiveEventLoop, event: MonitorEvent) {
+        (**self).monitor_event(event_loop, event);
+    }
+
     #[inline]
     fn about_to_wait(&mut self, event_loop: &dyn ActiveEventLoop) {
         (**self).about_to_wait(event_loop);
@@ -0,0 +1,289 @@
+//! Clipboard access.
+//!
+//! The clipboard is reached through [`ActiveEventLoop::clipboard()`]. Writing to it is
+//! synchronous, since the contents are kept by winit and handed out to other clients on demand,
+//! while reading is asynchronous: requests return an [`AsyncRequestSerial`] and the result is
+//! delivered later through [`ApplicationHandler::clipboard_event()`].
+//!
+//! [`ActiveEventLoop::clipboard()`]: crate::event_loop::ActiveEventLoop::clipboard
+//! [`ApplicationHandler::clipboard_event()`]: crate::application::ApplicationHandler::clipboard_event
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::as_any::AsAny;
+use crate::error::RequestError;
+use crate::event_loop::AsyncRequestSerial;
+
+/// The MIME type used for UTF-8 encoded plain text.
+pub const TEXT_MIME_TYPE: &str = "text/plain;charset=utf-8";
+
+/// The MIME type used for lists of URIs, such as dragged files.
+pub const URI_LIST_MIME_TYPE: &str = "text/uri-list";
+
+/// A single representation of the clipboard contents.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClipboardData {
+    /// The MIME type describing [`data`][Self::data].
+    pub mime_type: String,
+    /// The raw payload.
+    pub data: Vec<u8>,
+}
+
+impl ClipboardData {
+    /// Create a new payload with the given MIME type.
+    pub fn new(mime_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self { mime_type: mime_type.into(), data: data.into() }
+    }
+
+    /// Create a UTF-8 plain text payload.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::new(TEXT_MIME_TYPE, text.into())
+    }
+
+    /// Whether the payload is UTF-8 plain text.
+    pub fn is_text(&self) -> bool {
+        is_text_mime_type(&self.mime_type)
+    }
+
+    /// Interpret the payload as text, if it is valid UTF-8.
+    pub fn to_text(&self) -> Option<&str> {
+        std::str::from_utf8(&self.data).ok()
+    }
+
+    /// Create a `text/uri-list` payload referencing local files.
+    pub fn files<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> Self {
+        let mut uri_list = String::new();
+        for path in paths {
+            uri_list.push_str("file://");
+            for byte in path_to_bytes(path.as_ref()) {
+                if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+                    uri_list.push(byte as char);
+                } else {
+                    let _ = write!(uri_list, "%{byte:02X}");
+                }
+            }
+            uri_list.push_str("\r\n");
+        }
+
+        Self::new(URI_LIST_MIME_TYPE, uri_list)
+    }
+
+    /// Interpret a `text/uri-list` payload as local file paths.
+    ///
+    /// Comments and URIs that don't refer to local files are skipped.
+    pub fn to_paths(&self) -> Vec<PathBuf> {
+        self.data
+            .split(|&byte| byte == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .filter_map(|uri| uri.strip_prefix(b"file://"))
+            // Skip the host, which must be empty or `localhost` for local files.
+            .map(|uri| uri.strip_prefix(b"localhost").unwrap_or(uri))
+            .filter(|path| path.first() == Some(&b'/'))
+            .filter_map(|path| Some(bytes_to_path(percent_decode(path)?)))
+            .collect()
+    }
+}
+
+fn percent_decode(data: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(data.len());
+    let mut bytes = data.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte == b'%' {
+            let hex = [*bytes.next()?, *bytes.next()?];
+            decoded.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            decoded.push(byte);
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(unix)]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().replace('\\', "/").into_bytes()
+}
+
+#[cfg(unix)]
+fn bytes_to_path(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    OsString::from_vec(bytes).into()
+}
+
+#[cfg(not(unix))]
+fn bytes_to_path(bytes: Vec<u8>) -> PathBuf {
+    OsString::from(String::from_utf8_lossy(&bytes).into_owned()).into()
+}
+
+/// Returns `true` if `mime_type` is one of the commonly used names for UTF-8 plain text.
+///
+/// Backends use this to treat the various legacy text targets as aliases of
+/// [`TEXT_MIME_TYPE`].
+pub fn is_text_mime_type(mime_type: &str) -> bool {
+    matches!(
+        mime_type,
+        TEXT_MIME_TYPE
+            | "text/plain;charset=UTF-8"
+            | "text/plain"
+            | "UTF8_STRING"
+            | "STRING"
+            | "TEXT"
+    )
+}
+
+/// The reason a clipboard request failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum ClipboardError {
+    /// Nobody owns the clipboard.
+    Empty,
+    /// The clipboard owner doesn't provide the requested MIME type.
+    MimeTypeUnavailable,
+    /// The transfer was interrupted or the owner sent malformed data.
+    TransferFailed(String),
+}
+
+impl fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("the clipboard is empty"),
+            Self::MimeTypeUnavailable => {
+                f.write_str("the clipboard contents are not available in the requested MIME type")
+            },
+            Self::TransferFailed(reason) => write!(f, "clipboard transfer failed: {reason}"),
+        }
+    }
+}
+
+impl Error for ClipboardError {}
+
+/// Describes an update from the clipboard.
+///
+/// See [`ApplicationHandler::clipboard_event()`].
+///
+/// On X11 and Wayland, results of primary selection requests made through the platform extension
+/// traits are delivered through this event too, identified by their serial.
+///
+/// [`ApplicationHandler::clipboard_event()`]: crate::application::ApplicationHandler::clipboard_event
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ClipboardEvent {
+    /// The MIME types requested with [`Clipboard::request_mime_types()`] are available.
+    MimeTypes {
+        /// The serial returned by [`Clipboard::request_mime_types()`].
+        serial: AsyncRequestSerial,
+        /// The MIME types offered by the current owner of the clipboard.
+        result: Result<Vec<String>, ClipboardError>,
+    },
+
+    /// The data requested with [`Clipboard::request_data()`] has been received.
+    Data {
+        /// The serial returned by [`Clipboard::request_data()`].
+        serial: AsyncRequestSerial,
+        /// The received payload.
+        result: Result<ClipboardData, ClipboardError>,
+    },
+
+    /// Another client has taken over the clipboard.
+    ///
+    /// The contents previously passed to [`Clipboard::set_contents()`] have been dropped.
+    OwnershipLost,
+
+    /// Another client has taken over the primary selection.
+    ///
+    /// ## Platform-specific
+    ///
+    /// Only emitted on X11 and Wayland, which are the only platforms with a primary selection.
+    PrimarySelectionOwnershipLost,
+}
+
+/// Access to the system clipboard.
+///
+/// Obtained from [`ActiveEventLoop::clipboard()`].
+///
+/// [`ActiveEventLoop::clipboard()`]: crate::event_loop::ActiveEventLoop::clipboard
+pub trait Clipboard: AsAny + fmt::Debug {
+    /// Take ownership of the clipboard and offer `contents` to other clients.
+    ///
+    /// Each entry is a different representation of the same data, the reading client picks the
+    /// one it prefers. Text entries are additionally offered under the legacy plain text names.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The compositor only allows setting the clipboard in response to user input,
+    ///   so this fails with [`RequestError::Ignored`] until one of the application's windows got a
+    ///   keyboard or pointer event.
+    fn set_contents(&self, contents: Vec<ClipboardData>) -> Result<(), RequestError>;
+
+    /// Drop the contents previously set with [`set_contents()`][Self::set_contents].
+    ///
+    /// Does nothing if the application doesn't own the clipboard.
+    fn clear(&self) -> Result<(), RequestError>;
+
+    /// Request the list of MIME types in which the clipboard contents are available.
+    ///
+    /// The result is delivered as [`ClipboardEvent::MimeTypes`].
+    fn request_mime_types(&self) -> Result<AsyncRequestSerial, RequestError>;
+
+    /// Request the clipboard contents in the given MIME type.
+    ///
+    /// The result is delivered as [`ClipboardEvent::Data`].
+    fn request_data(&self, mime_type: &str) -> Result<AsyncRequestSerial, RequestError>;
+}
+
+impl_dyn_casting!(Clipboard);
+
+impl dyn Clipboard + '_ {
+    /// Put UTF-8 text on the clipboard.
+    pub fn set_text(&self, text: impl Into<String>) -> Result<(), RequestError> {
+        self.set_contents(vec![ClipboardData::text(text)])
+    }
+
+    /// Request the clipboard contents as UTF-8 text.
+    ///
+    /// The result is delivered as [`ClipboardEvent::Data`].
+    pub fn request_text(&self) -> Result<AsyncRequestSerial, RequestError> {
+        self.request_data(TEXT_MIME_TYPE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_aliases() {
+        assert!(ClipboardData::text("foo").is_text());
+        assert!(is_text_mime_type("UTF8_STRING"));
+        assert!(is_text_mime_type("text/plain"));
+        assert!(!is_text_mime_type("text/html"));
+        assert_eq!(ClipboardData::text("foo").to_text(), Some("foo"));
+    }
+
+    #[test]
+    fn uri_list() {
+        let data = ClipboardData::files(["/tmp/a b.txt", "/tmp/ü"]);
+        assert_eq!(data.mime_type, URI_LIST_MIME_TYPE);
+        assert_eq!(data.to_text(), Some("file:///tmp/a%20b.txt\r\nfile:///tmp/%C3%BC\r\n"));
+        assert_eq!(data.to_paths(), [PathBuf::from("/tmp/a b.txt"), PathBuf::from("/tmp/ü")]);
+
+        let data = ClipboardData::new(
+            URI_LIST_MIME_TYPE,
+            "# comment\r\nhttps://example.com\r\nfile://localhost/etc/hosts\nfile://host/x",
+        );
+        assert_eq!(data.to_paths(), [PathBuf::from("/etc/hosts")]);
+    }
+}
@@ -0,0 +1,64 @@
+//! Drag and drop.
+//!
+//! A drag carrying data out of a window is started with [`Window::start_drag()`], its payload
+//! is a list of [`ClipboardData`], one entry per representation of the dragged data.
+//!
+//! [`Window::start_drag()`]: crate::window::Window::start_drag
+//! [`ClipboardData`]: crate::clipboard::ClipboardData
+use bitflags::bitflags;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// What happens to dragged data when it is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DragAction {
+    /// The data is copied to the drop target.
+    Copy,
+    /// The data is moved to the drop target, the source should delete its copy.
+    Move,
+    /// The drop target creates a link to the data.
+    Link,
+}
+
+bitflags! {
+    /// A set of [`DragAction`]s.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct DragActions: u8 {
+        const COPY = 1 << 0;
+        const MOVE = 1 << 1;
+        const LINK = 1 << 2;
+    }
+}
+
+impl DragActions {
+    /// The action to propose to drop targets, in order of preference.
+    pub fn preferred(self) -> Option<DragAction> {
+        [DragAction::Copy, DragAction::Move, DragAction::Link]
+            .into_iter()
+            .find(|&action| self.contains(action.into()))
+    }
+}
+
+impl From<DragAction> for DragActions {
+    fn from(action: DragAction) -> Self {
+        match action {
+            DragAction::Copy => Self::COPY,
+            DragAction::Move => Self::MOVE,
+            DragAction::Link => Self::LINK,
+        }
+    }
+}
+
+/// The outcome of a drag started with [`Window::start_drag()`].
+///
+/// [`Window::start_drag()`]: crate::window::Window::start_drag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DragResult {
+    /// The data was dropped on a target, which performed the given action.
+    Dropped(DragAction),
+    /// The drag was cancelled, or the data was dropped where nothing accepts it.
+    Cancelled,
+}
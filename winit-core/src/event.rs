@@ -4,6 +4,7 @@ use std::cmp::Ordering;
 use std::f64;
 use std::path::PathBuf;
 use std::sync::{Mutex, Weak};
+use std::time::Duration;
 
 use dpi::{PhysicalPosition, PhysicalSize};
 #[cfg(feature = "serde")]
@@ -11,9 +12,11 @@ use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 
 use crate::Instant;
+use crate::clipboard::{ClipboardData, ClipboardError};
+use crate::drag::{DragActions, DragResult};
 use crate::error::RequestError;
 use crate::event_loop::AsyncRequestSerial;
-use crate::keyboard::{self, ModifiersKeyState, ModifiersKeys, ModifiersState};
+use crate::keyboard::{self, KeyboardLayouts, ModifiersKeyState, ModifiersKeys, ModifiersState};
 #[cfg(doc)]
 use crate::window::Window;
 use crate::window::{ActivationToken, Theme};
@@ -44,6 +47,7 @@ pub enum StartCause {
 
 /// Describes an event from a [`Window`].
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum WindowEvent {
     /// The activation token was delivered back and now could be used.
     ActivationTokenDone { serial: AsyncRequestSerial, token: ActivationToken },
@@ -75,23 +79,51 @@ pub enum WindowEvent {
     /// The window has been destroyed.
     Destroyed,
 
-    /// A file drag operation has entered the window.
+    /// The popup was dismissed by the compositor or the window manager, and should be dropped.
+    ///
+    /// This happens e.g. when the user clicks outside of a popup with a grab.
+    ///
+    /// See [`WindowAttributes::with_popup()`].
+    ///
+    /// [`WindowAttributes::with_popup()`]: crate::window::WindowAttributes::with_popup
+    PopupDismissed,
+
+    /// A drag operation has entered the window.
+    ///
+    /// Whether the data may be dropped on the window is decided with [`Window::accept_drag`] and
+    /// [`Window::reject_drag`], and it is read with [`Window::request_drag_data`].
     DragEntered {
         /// List of paths that are being dragged onto the window.
+        ///
+        /// Empty when the dragged data isn't a list of files.
         paths: Vec<PathBuf>,
+        /// The MIME types the dragged data is offered as.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **macOS / Windows:** Always empty, only files are supported.
+        mime_types: Vec<String>,
+        /// The actions the drag source allows.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **macOS / Windows:** Always [`DragActions::COPY`].
+        actions: DragActions,
         /// (x,y) coordinates in pixels relative to the top-left corner of the window. May be
         /// negative on some platforms if something is dragged over a window's decorations (title
         /// bar, frame, etc).
         position: PhysicalPosition<f64>,
     },
-    /// A file drag operation has moved over the window.
+    /// A drag operation has moved over the window.
     DragMoved {
         /// (x,y) coordinates in pixels relative to the top-left corner of the window. May be
         /// negative on some platforms if something is dragged over a window's decorations (title
         /// bar, frame, etc).
         position: PhysicalPosition<f64>,
     },
-    /// The file drag operation has dropped file(s) on the window.
+    /// The drag operation has dropped data on the window.
+    ///
+    /// The data can still be read with [`Window::request_drag_data`] while handling this event.
     DragDropped {
         /// List of paths that are being dragged onto the window.
         paths: Vec<PathBuf>,
@@ -100,7 +132,7 @@ pub enum WindowEvent {
         /// bar, frame, etc).
         position: PhysicalPosition<f64>,
     },
-    /// The file drag operation has been cancelled or left the window.
+    /// The drag operation has been cancelled or left the window.
     DragLeft {
         /// (x,y) coordinates in pixels relative to the top-left corner of the window. May be
         /// negative on some platforms if something is dragged over a window's decorations (title
@@ -112,6 +144,22 @@ pub enum WindowEvent {
         position: Option<PhysicalPosition<f64>>,
     },
 
+    /// The data requested with [`Window::request_drag_data`] is available.
+    DragData {
+        /// The serial returned by [`Window::request_drag_data`].
+        serial: AsyncRequestSerial,
+        /// The data with the requested MIME type, or why it couldn't be read.
+        result: Result<ClipboardData, ClipboardError>,
+    },
+
+    /// A drag started with [`Window::start_drag`] has ended.
+    DragSourceFinished {
+        /// The serial returned by [`Window::start_drag`].
+        serial: AsyncRequestSerial,
+        /// Whether the data was dropped, and what the drop target did with it.
+        result: DragResult,
+    },
+
     /// The window gained or lost focus.
     ///
     /// The parameter is true if the window has gained focus, and false if it has lost focus.
@@ -120,6 +168,15 @@ pub enum WindowEvent {
     /// afterwards.
     Focused(bool),
 
+    /// The keyboard grab requested with [`Window::set_keyboard_grab`] became active or inactive.
+    ///
+    /// While the grab is active, keys and shortcuts normally handled by the system, like
+    /// <kbd>Alt</kbd>+<kbd>Tab</kbd>, are delivered to the window instead. The grab is lifted when
+    /// the window loses focus, and may be restored when it regains it.
+    ///
+    /// [`Window::set_keyboard_grab`]: crate::window::Window::set_keyboard_grab
+    KeyboardGrabbed(bool),
+
     /// An event from the keyboard has been received.
     ///
     /// ## Platform-specific
@@ -146,6 +203,20 @@ pub enum WindowEvent {
     /// The keyboard modifiers have changed.
     ModifiersChanged(Modifiers),
 
+    /// The keyboard layouts, or the active one, have changed.
+    ///
+    /// Also emitted when the window gains focus, so the layouts are known before any key is
+    /// pressed.
+    ///
+    /// See [`Window::set_keyboard_layout()`] to switch the active layout.
+    ///
+    /// ## Platform-specific
+    ///
+    /// Only emitted on X11 and Wayland.
+    ///
+    /// [`Window::set_keyboard_layout()`]: crate::window::Window::set_keyboard_layout
+    KeyboardLayoutChanged(KeyboardLayouts),
+
     /// An event from an input method.
     ///
     /// **Note:** You have to explicitly enable this event using [`Window::set_ime_allowed`].
@@ -235,7 +306,37 @@ pub enum WindowEvent {
     },
 
     /// A mouse wheel movement or touchpad scroll occurred.
-    MouseWheel { device_id: Option<DeviceId>, delta: MouseScrollDelta, phase: TouchPhase },
+    MouseWheel {
+        device_id: Option<DeviceId>,
+        delta: MouseScrollDelta,
+        /// The phase of the scroll.
+        ///
+        /// A scroll with the fingers ends with [`TouchPhase::Ended`] when they are lifted, which
+        /// is where kinetic scrolling should start. Wheel scrolls may never end.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **X11:** Scrolls never end, XInput2 doesn't report the fingers being lifted.
+        phase: TouchPhase,
+        /// What caused the scroll.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **X11:** Only distinguishes touchpads from wheels.
+        /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always
+        ///   [`ScrollSource::Unknown`].
+        source: ScrollSource,
+        /// The horizontal and vertical scroll in fractions of wheel steps, where 120 is one step.
+        ///
+        /// High-resolution wheels report fractions of a step. [`None`] if the scroll isn't made
+        /// of wheel steps. The sign convention is the one of [`MouseScrollDelta::LineDelta`].
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **Windows:** Touchpads also report their scroll in fractions of wheel steps.
+        /// - **Android / iOS / macOS / Orbital / Web:** Always [`None`].
+        value120: Option<(i32, i32)>,
+    },
 
     /// An mouse button press has been received.
     PointerButton {
@@ -267,7 +368,8 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **macOS**, **iOS**, and **Wayland**.
+    /// - Only available on **macOS**, **iOS**, **Wayland** and **X11**.
+    /// - **X11:** Requires XInput 2.4.
     /// - On iOS, not recognized by default. It must be enabled when needed.
     PinchGesture {
         device_id: Option<DeviceId>,
@@ -283,7 +385,8 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **iOS** and **Wayland**.
+    /// - Only available on **iOS**, **Wayland** and **X11**.
+    /// - **X11:** Requires XInput 2.4. Swipes with three or more fingers are reported as pans.
     /// - On iOS, not recognized by default. It must be enabled when needed.
     PanGesture {
         device_id: Option<DeviceId>,
@@ -319,7 +422,8 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **macOS**, **iOS**, and **Wayland**.
+    /// - Only available on **macOS**, **iOS**, **Wayland** and **X11**.
+    /// - **X11:** Requires XInput 2.4.
     /// - On iOS, not recognized by default. It must be enabled when needed.
     RotationGesture {
         device_id: Option<DeviceId>,
@@ -365,6 +469,9 @@ pub enum WindowEvent {
         /// Handle to update surface size during scale changes.
         ///
         /// See [`SurfaceSizeWriter`] docs for more details.
+        ///
+        /// It refers to a live window, so it isn't serialized.
+        #[cfg_attr(feature = "serde", serde(skip, default = "detached_surface_size_writer"))]
         surface_size_writer: SurfaceSizeWriter,
     },
 
@@ -421,6 +528,38 @@ pub enum WindowEvent {
     ///
     /// [the safe area]: crate::window::Window::safe_area
     RedrawRequested,
+
+    /// A frame of the window was presented on the screen.
+    ///
+    /// This is reported once for the frame submitted after calling
+    /// [`Window::pre_present_notify()`], so it has to be called for every frame whose
+    /// presentation should be reported.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Requires `wp_presentation`.
+    /// - **X11:** Requires the Present extension, and the frame to be presented through it, which
+    ///   the Mesa implementations of Vulkan, EGL and GLX do.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`Window::pre_present_notify()`]: crate::window::Window::pre_present_notify
+    FramePresented(FramePresentation),
+}
+
+/// The timing of a frame presented on the screen, see [`WindowEvent::FramePresented`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FramePresentation {
+    /// When the frame was shown, as the time since the epoch of `CLOCK_MONOTONIC`.
+    ///
+    /// On Wayland, the timestamps of compositors using another clock are converted to
+    /// `CLOCK_MONOTONIC`.
+    pub timestamp: Duration,
+    /// The time between two refreshes of the monitor, `None` when unknown, or when the monitor
+    /// doesn't refresh at a fixed rate.
+    pub refresh_interval: Option<Duration>,
+    /// The counter of the refreshes of the monitor when the frame was shown, `None` when unknown.
+    pub sequence: Option<u64>,
 }
 
 /// Represents the kind type of a pointer event.
@@ -430,6 +569,7 @@ pub enum WindowEvent {
 /// **Wayland/X11:** [`Unknown`](Self::Unknown) device types are converted to known variants by the
 /// system.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PointerKind {
     Mouse,
     /// See [`PointerSource::Touch`] for more details.
@@ -447,6 +587,7 @@ pub enum PointerKind {
 /// **Wayland/X11:** [`Unknown`](Self::Unknown) device types are converted to known variants by the
 /// system.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PointerSource {
     Mouse,
     /// Represents a touch event.
@@ -515,6 +656,7 @@ impl From<PointerSource> for PointerKind {
 /// **Wayland/X11:** [`Unknown`](Self::Unknown) device types are converted to known variants by the
 /// system.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ButtonSource {
     Mouse(MouseButton),
     /// See [`PointerSource::Touch`] for more details.
@@ -564,6 +706,7 @@ impl From<MouseButton> for ButtonSource {
 /// on-screen cursor and keyboard focus) or physical. Virtual devices typically aggregate inputs
 /// from multiple physical devices.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DeviceId(i64);
 
 impl DeviceId {
@@ -582,11 +725,50 @@ impl DeviceId {
     }
 }
 
+/// The kind of an input device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DeviceKind {
+    Keyboard,
+    Mouse,
+    Touchpad,
+    /// A drawing tablet, used with a pen or an eraser.
+    Tablet,
+    Touchscreen,
+}
+
+/// Information about an input device.
+///
+/// Listed by [`ActiveEventLoop::devices()`].
+///
+/// [`ActiveEventLoop::devices()`]: crate::event_loop::ActiveEventLoop::devices
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeviceInfo {
+    /// The identifier of the device, as passed to
+    /// [`ApplicationHandler::device_event()`][crate::application::ApplicationHandler::device_event].
+    pub id: DeviceId,
+    /// The name of the device, such as the product name.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Only tablets have a name.
+    pub name: Option<String>,
+    pub kind: DeviceKind,
+    /// The name of the seat the device belongs to.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The name of the master device the device is attached to, such as "Virtual core
+    ///   pointer".
+    pub seat: Option<String>,
+}
+
 /// Identifier of a finger in a touch event.
 ///
 /// Whenever a touch event is received it contains a `FingerId` which uniquely identifies the finger
 /// used for the current interaction.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FingerId(pub(crate) usize);
 
 impl FingerId {
@@ -616,6 +798,7 @@ impl FingerId {
 ///
 /// [window events]: WindowEvent
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DeviceEvent {
     /// Change in physical position of a pointing device.
     ///
@@ -646,6 +829,32 @@ pub enum DeviceEvent {
     },
 
     Key(RawKeyEvent),
+
+    /// The device was connected.
+    ///
+    /// Its information is listed by [`ActiveEventLoop::devices()`]. Unlike the other device
+    /// events, this is delivered regardless of [`DeviceEvents`].
+    ///
+    /// The devices connected when the event loop starts aren't reported.
+    ///
+    /// ## Platform-specific
+    ///
+    /// Only emitted on X11 and Wayland.
+    ///
+    /// [`ActiveEventLoop::devices()`]: crate::event_loop::ActiveEventLoop::devices
+    /// [`DeviceEvents`]: crate::event_loop::DeviceEvents
+    Added,
+
+    /// The device was disconnected.
+    ///
+    /// Like [`Added`][Self::Added], this is delivered regardless of [`DeviceEvents`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// Only emitted on X11 and Wayland.
+    ///
+    /// [`DeviceEvents`]: crate::event_loop::DeviceEvents
+    Removed,
 }
 
 /// Describes a keyboard input as a raw device event.
@@ -664,6 +873,7 @@ pub struct RawKeyEvent {
 
 /// Describes a keyboard input targeting a window.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct KeyEvent {
     /// Represents the position of a key independent of the currently active layout.
     ///
@@ -1451,6 +1661,24 @@ impl From<TabletToolButton> for Option<MouseButton> {
     }
 }
 
+/// Describes what caused a [`WindowEvent::MouseWheel`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum ScrollSource {
+    /// A mouse wheel, with discrete steps.
+    Wheel,
+    /// Fingers on a touch surface, like a touchpad.
+    Finger,
+    /// A continuous motion that isn't from fingers, like a trackball scrolling with a button held.
+    Continuous,
+    /// The side to side tilt of a mouse wheel.
+    WheelTilt,
+    /// The source isn't known.
+    #[default]
+    Unknown,
+}
+
 /// Describes a difference in the mouse scroll wheel state.
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -1520,6 +1748,12 @@ impl PartialEq for SurfaceSizeWriter {
 
 impl Eq for SurfaceSizeWriter {}
 
+/// A writer connected to no window, for deserialized [`WindowEvent::ScaleFactorChanged`]s.
+#[cfg(feature = "serde")]
+fn detached_surface_size_writer() -> SurfaceSizeWriter {
+    SurfaceSizeWriter::new(Weak::new())
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::{BTreeSet, HashSet};
@@ -1547,10 +1781,23 @@ mod tests {
             with_window_event(Focused(true));
             with_window_event(Moved((0, 0).into()));
             with_window_event(SurfaceResized((0, 0).into()));
-            with_window_event(DragEntered { paths: vec!["x.txt".into()], position: (0, 0).into() });
+            with_window_event(DragEntered {
+                paths: vec!["x.txt".into()],
+                mime_types: vec!["text/uri-list".into()],
+                actions: crate::drag::DragActions::COPY,
+                position: (0, 0).into(),
+            });
             with_window_event(DragMoved { position: (0, 0).into() });
             with_window_event(DragDropped { paths: vec!["x.txt".into()], position: (0, 0).into() });
             with_window_event(DragLeft { position: Some((0, 0).into()) });
+            with_window_event(DragData {
+                serial: crate::event_loop::AsyncRequestSerial::get(),
+                result: Err(crate::clipboard::ClipboardError::Empty),
+            });
+            with_window_event(DragSourceFinished {
+                serial: crate::event_loop::AsyncRequestSerial::get(),
+                result: crate::drag::DragResult::Cancelled,
+            });
             with_window_event(Ime(Enabled));
             with_window_event(PointerMoved {
                 device_id: None,
@@ -1559,6 +1806,7 @@ mod tests {
                 source: PointerSource::Mouse,
             });
             with_window_event(ModifiersChanged(event::Modifiers::default()));
+            with_window_event(KeyboardLayoutChanged(Default::default()));
             with_window_event(PointerEntered {
                 device_id: None,
                 primary: true,
@@ -1575,6 +1823,8 @@ mod tests {
                 device_id: None,
                 delta: event::MouseScrollDelta::LineDelta(0.0, 0.0),
                 phase: event::TouchPhase::Started,
+                source: event::ScrollSource::Wheel,
+                value120: Some((0, 120)),
             });
             with_window_event(PointerButton {
                 device_id: None,
@@ -1612,6 +1862,7 @@ mod tests {
             with_window_event(TouchpadPressure { device_id: None, pressure: 0.0, stage: 0 });
             with_window_event(ThemeChanged(crate::window::Theme::Light));
             with_window_event(Occluded(true));
+            with_window_event(KeyboardGrabbed(true));
         }};
         (device: $closure:expr) => {{
             use event::DeviceEvent::*;
@@ -1622,6 +1873,8 @@ mod tests {
             with_device_event(PointerMotion { delta: (0.0, 0.0).into() });
             with_device_event(MouseWheel { delta: event::MouseScrollDelta::LineDelta(0.0, 0.0) });
             with_device_event(Button { button: 0, state: event::ElementState::Pressed });
+            with_device_event(Added);
+            with_device_event(Removed);
         }};
     }
 
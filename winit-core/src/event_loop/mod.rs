@@ -12,8 +12,10 @@ use rwh_06::{DisplayHandle, HandleError, HasDisplayHandle};
 
 use crate::Instant;
 use crate::as_any::AsAny;
+use crate::clipboard::Clipboard;
 use crate::cursor::{CustomCursor, CustomCursorSource};
 use crate::error::RequestError;
+use crate::event::DeviceInfo;
 use crate::monitor::MonitorHandle;
 use crate::window::{Theme, Window, WindowAttributes};
 
@@ -62,6 +64,24 @@ pub trait ActiveEventLoop: AsAny + fmt::Debug {
     /// - **Web:** Always returns `None` without `detailed monitor permissions`.
     fn primary_monitor(&self) -> Option<MonitorHandle>;
 
+    /// Returns the input devices connected to the system.
+    ///
+    /// Devices being connected or disconnected are reported with [`DeviceEvent::Added`] and
+    /// [`DeviceEvent::Removed`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Only the physical devices are listed, not the master devices aggregating them.
+    /// - **Wayland:** The keyboard, pointer and touch of each seat are listed as one device each,
+    ///   along with the tablets.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, returns no devices.
+    ///
+    /// [`DeviceEvent::Added`]: crate::event::DeviceEvent::Added
+    /// [`DeviceEvent::Removed`]: crate::event::DeviceEvent::Removed
+    fn devices(&self) -> Box<dyn Iterator<Item = DeviceInfo>> {
+        Box::new(std::iter::empty())
+    }
+
     /// Change if or when [`DeviceEvent`]s are captured.
     ///
     /// Since the [`DeviceEvent`] capture can lead to high CPU usage for unfocused windows, winit
@@ -107,6 +127,15 @@ pub trait ActiveEventLoop: AsAny + fmt::Debug {
     /// Set by [`exit()`][Self::exit].
     fn exiting(&self) -> bool;
 
+    /// Returns the system clipboard.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, returns `None`.
+    fn clipboard(&self) -> Option<&dyn Clipboard> {
+        None
+    }
+
     /// Gets a persistent reference to the underlying platform display.
     ///
     /// See the [`OwnedDisplayHandle`] type for more information.
@@ -276,6 +305,7 @@ pub enum DeviceEvents {
 /// Then once event is arriving the working list is being traversed and a job
 /// executed and removed from the list.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AsyncRequestSerial {
     serial: usize,
 }
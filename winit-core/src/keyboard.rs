@@ -484,3 +484,19 @@ bitflags! {
         const RSUPER   = Self::RMETA.bits();
     }
 }
+
+/// The layouts of the keyboard, such as "English (US)" and "German", and which one is active.
+///
+/// Reported with [`WindowEvent::KeyboardLayoutChanged`].
+///
+/// [`WindowEvent::KeyboardLayoutChanged`]: crate::event::WindowEvent::KeyboardLayoutChanged
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KeyboardLayouts {
+    /// The names of the layouts, in the order the system switches between them.
+    ///
+    /// A layout without a name is reported as an empty string.
+    pub names: Vec<String>,
+    /// The index of the active layout in [`names`][Self::names].
+    pub active: usize,
+}
@@ -9,7 +9,9 @@
 
 #[macro_use]
 pub mod as_any;
+pub mod clipboard;
 pub mod cursor;
+pub mod drag;
 #[macro_use]
 pub mod error;
 pub mod application;
@@ -18,6 +20,8 @@ pub mod event_loop;
 pub mod icon;
 pub mod keyboard;
 pub mod monitor;
+#[cfg(feature = "serde")]
+pub mod record;
 pub mod window;
 
 // `Instant` is not actually available on `wasm32-unknown-unknown`, the `std` implementation there
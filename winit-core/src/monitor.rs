@@ -181,6 +181,26 @@ impl fmt::Display for VideoMode {
     }
 }
 
+/// A monitor being connected, disconnected or reconfigured.
+///
+/// Delivered through [`ApplicationHandler::monitor_event()`].
+///
+/// [`ApplicationHandler::monitor_event()`]: crate::application::ApplicationHandler::monitor_event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonitorEvent {
+    /// A monitor was connected.
+    Added(MonitorHandle),
+
+    /// A monitor was disconnected.
+    ///
+    /// The handle only reports the information the monitor had before it was disconnected, if
+    /// any.
+    Removed(MonitorHandle),
+
+    /// The position, scale factor or current video mode of a monitor changed.
+    Changed(MonitorHandle),
+}
+
 /// Fullscreen modes.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Fullscreen {
@@ -0,0 +1,960 @@
+//! Recording and replaying of event streams.
+//!
+//! A [`Recorder`] wraps an [`ApplicationHandler`] and records the window, device, clipboard and
+//! monitor events it receives into a [`Trace`], along with the requests made to the windows wrapped
+//! with [`WindowRecorder::wrap()`]. The trace can be saved with any [`serde`] format, and fed back
+//! to the application later with a [`Replayer`].
+//!
+//! ```no_run
+//! # use winit_core::application::ApplicationHandler;
+//! # use winit_core::event::WindowEvent;
+//! # use winit_core::event_loop::ActiveEventLoop;
+//! # use winit_core::record::{Recorder, WindowRecorder};
+//! # use winit_core::window::{Window, WindowAttributes, WindowId};
+//! struct App {
+//!     window_recorder: WindowRecorder,
+//!     window: Option<Box<dyn Window>>,
+//! }
+//!
+//! impl ApplicationHandler for App {
+//!     fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+//!         let window = event_loop.create_window(WindowAttributes::default()).unwrap();
+//!         self.window = Some(self.window_recorder.wrap(window));
+//!     }
+//!
+//!     // ...
+//! #   fn window_event(&mut self, _: &dyn ActiveEventLoop, _: WindowId, _: WindowEvent) {}
+//! }
+//!
+//! let window_recorder = WindowRecorder::new();
+//! let app = App { window_recorder: window_recorder.clone(), window: None };
+//! let mut recorder = Recorder::new(app, window_recorder);
+//! // Run the event loop with `&mut recorder`, then save `recorder.trace()`.
+//! ```
+//!
+//! [`ApplicationHandler`]: crate::application::ApplicationHandler
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cursor_icon::CursorIcon;
+use dpi::{PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
+use serde::{Deserialize, Serialize};
+
+use crate::Instant;
+use crate::application::{ApplicationHandler, macos};
+use crate::clipboard::{ClipboardData, ClipboardEvent};
+use crate::cursor::Cursor;
+use crate::drag::{DragAction, DragActions};
+use crate::error::RequestError;
+use crate::event::{DeviceEvent, DeviceId, StartCause, WindowEvent};
+use crate::event_loop::{ActiveEventLoop, AsyncRequestSerial, ControlFlow};
+use crate::icon::Icon;
+use crate::monitor::{Fullscreen, MonitorEvent, MonitorHandle};
+use crate::window::{
+    ContentType, CursorGrabMode, ImeCapabilities, ImePurpose, ImeRequest, ImeRequestError,
+    ResizeDirection, Theme, UserAttentionType, Window, WindowButtons, WindowId, WindowLevel,
+};
+
+/// A recorded stream of events and window requests.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Trace {
+    pub entries: Vec<TraceEntry>,
+}
+
+impl Trace {
+    /// The window requests, with the windows identified by their creation order.
+    ///
+    /// Unlike [`WindowId`]s, the creation order is the same in a recording and its replay, so
+    /// their requests can be compared.
+    pub fn window_requests(&self) -> Vec<(usize, WindowRequest)> {
+        let windows = self.windows();
+        self.entries
+            .iter()
+            .filter_map(|entry| match &entry.record {
+                Record::WindowRequest { window_id, request } => {
+                    let index = windows.iter().position(|id| id == window_id)?;
+                    Some((index, request.clone()))
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The recorded windows, in creation order.
+    fn windows(&self) -> Vec<WindowId> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry.record {
+                Record::WindowCreated { window_id } => Some(window_id),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    /// The time since the start of the recording.
+    pub time: Duration,
+    pub record: Record,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Record {
+    /// A window was wrapped with [`WindowRecorder::wrap()`].
+    WindowCreated {
+        window_id: WindowId,
+    },
+    WindowEvent {
+        window_id: WindowId,
+        event: WindowEvent,
+    },
+    DeviceEvent {
+        device_id: Option<DeviceId>,
+        event: DeviceEvent,
+    },
+    ClipboardEvent {
+        event: ClipboardEvent,
+    },
+    /// A monitor was connected, disconnected or reconfigured, identified by its
+    /// [`id`][crate::monitor::MonitorHandleProvider::id].
+    ///
+    /// Monitor handles refer to live monitors, so the event isn't replayed.
+    MonitorEvent {
+        change: MonitorChange,
+        monitor_id: u128,
+    },
+    ProxyWakeUp,
+    WindowRequest {
+        window_id: WindowId,
+        request: WindowRequest,
+    },
+}
+
+/// The kind of a recorded [`MonitorEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MonitorChange {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A request that changes the state of a [`Window`].
+///
+/// Requests whose arguments refer to live objects only record what can be replayed, such as
+/// whether a fullscreen mode or a window icon was set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WindowRequest {
+    RequestRedraw,
+    PrePresentNotify,
+    SetOuterPosition(Position),
+    RequestSurfaceSize(Size),
+    SetMinSurfaceSize(Option<Size>),
+    SetMaxSurfaceSize(Option<Size>),
+    SetSurfaceResizeIncrements(Option<Size>),
+    SetTitle(String),
+    SetTransparent(bool),
+    SetBlur(bool),
+    SetVisible(bool),
+    SetResizable(bool),
+    SetEnabledButtons(WindowButtons),
+    SetMinimized(bool),
+    SetMaximized(bool),
+    /// Whether the window was made fullscreen.
+    SetFullscreen(bool),
+    SetContentType(ContentType),
+    SetAllowTearing(bool),
+    SetDecorations(bool),
+    SetWindowLevel(WindowLevel),
+    /// The owner in creation order, `None` without an owner or when the owner isn't recorded.
+    SetOwnerWindow {
+        owner: Option<usize>,
+        modal: bool,
+    },
+    /// Whether an icon was set.
+    SetWindowIcon(bool),
+    Ime(ImeRequest),
+    SetKeyboardLayout(usize),
+    FocusWindow,
+    RequestUserAttention(Option<UserAttentionType>),
+    SetTheme(Option<Theme>),
+    SetContentProtected(bool),
+    SetIdleInhibited(bool),
+    /// The icon of the cursor, `None` for a custom cursor.
+    SetCursor(Option<CursorIcon>),
+    SetCursorPosition(Position),
+    SetCursorGrab(CursorGrabMode),
+    SetKeyboardGrab(bool),
+    SetCursorVisible(bool),
+    DragWindow,
+    DragResizeWindow(ResizeDirection),
+    StartDrag {
+        contents: Vec<ClipboardData>,
+        actions: DragActions,
+    },
+    AcceptDrag {
+        mime_type: String,
+        action: DragAction,
+    },
+    RejectDrag,
+    RequestDragData(String),
+    ShowWindowMenu(Position),
+    SetCursorHittest(bool),
+}
+
+/// The trace being recorded, shared with the windows.
+#[derive(Debug)]
+struct TraceRecording {
+    start: Instant,
+    trace: Trace,
+    /// The windows wrapped so far, in creation order.
+    windows: Vec<WindowId>,
+}
+
+impl TraceRecording {
+    fn push(&mut self, record: Record) {
+        let time = Instant::now().saturating_duration_since(self.start);
+        self.trace.entries.push(TraceEntry { time, record });
+    }
+}
+
+/// Records the requests made to windows.
+///
+/// Cloning it gives another handle to the same recording.
+#[derive(Debug, Clone)]
+pub struct WindowRecorder {
+    recording: Arc<Mutex<TraceRecording>>,
+}
+
+impl Default for WindowRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WindowRecorder {
+    pub fn new() -> Self {
+        let recording =
+            TraceRecording { start: Instant::now(), trace: Trace::default(), windows: Vec::new() };
+        Self { recording: Arc::new(Mutex::new(recording)) }
+    }
+
+    /// Wrap a window, to record the requests made to it.
+    pub fn wrap(&self, window: Box<dyn Window>) -> Box<dyn Window> {
+        let window_id = window.id();
+        let mut recording = self.recording.lock().unwrap();
+        recording.windows.push(window_id);
+        recording.push(Record::WindowCreated { window_id });
+        Box::new(RecordedWindow { window, recorder: self.clone() })
+    }
+
+    /// The trace recorded so far.
+    pub fn trace(&self) -> Trace {
+        self.recording.lock().unwrap().trace.clone()
+    }
+
+    fn push(&self, record: Record) {
+        self.recording.lock().unwrap().push(record);
+    }
+
+    /// The window created at `index`, in creation order.
+    fn window(&self, index: usize) -> Option<WindowId> {
+        self.recording.lock().unwrap().windows.get(index).copied()
+    }
+
+    /// The creation order of `window_id`.
+    fn index(&self, window_id: WindowId) -> Option<usize> {
+        self.recording.lock().unwrap().windows.iter().position(|&id| id == window_id)
+    }
+}
+
+/// An [`ApplicationHandler`] recording the events the application receives.
+#[derive(Debug)]
+pub struct Recorder<A> {
+    app: A,
+    recorder: WindowRecorder,
+}
+
+impl<A: ApplicationHandler> Recorder<A> {
+    /// Record the events received by `app`, and the requests of the windows wrapped by
+    /// `recorder`.
+    pub fn new(app: A, recorder: WindowRecorder) -> Self {
+        Self { app, recorder }
+    }
+
+    /// The trace recorded so far.
+    pub fn trace(&self) -> Trace {
+        self.recorder.trace()
+    }
+
+    pub fn app(&self) -> &A {
+        &self.app
+    }
+
+    pub fn app_mut(&mut self) -> &mut A {
+        &mut self.app
+    }
+}
+
+#[deny(clippy::missing_trait_methods)]
+impl<A: ApplicationHandler> ApplicationHandler for Recorder<A> {
+    fn new_events(&mut self, event_loop: &dyn ActiveEventLoop, cause: StartCause) {
+        self.app.new_events(event_loop, cause);
+    }
+
+    fn resumed(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.app.resumed(event_loop);
+    }
+
+    fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.app.can_create_surfaces(event_loop);
+    }
+
+    fn proxy_wake_up(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.recorder.push(Record::ProxyWakeUp);
+        self.app.proxy_wake_up(event_loop);
+    }
+
+    fn window_event(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        self.recorder.push(Record::WindowEvent { window_id, event: event.clone() });
+        self.app.window_event(event_loop, window_id, event);
+    }
+
+    fn device_event(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        device_id: Option<DeviceId>,
+        event: DeviceEvent,
+    ) {
+        self.recorder.push(Record::DeviceEvent { device_id, event });
+        self.app.device_event(event_loop, device_id, event);
+    }
+
+    fn clipboard_event(&mut self, event_loop: &dyn ActiveEventLoop, event: ClipboardEvent) {
+        self.recorder.push(Record::ClipboardEvent { event: event.clone() });
+        self.app.clipboard_event(event_loop, event);
+    }
+
+    fn monitor_event(&mut self, event_loop: &dyn ActiveEventLoop, event: MonitorEvent) {
+        let (change, monitor) = match &event {
+            MonitorEvent::Added(monitor) => (MonitorChange::Added, monitor),
+            MonitorEvent::Removed(monitor) => (MonitorChange::Removed, monitor),
+            MonitorEvent::Changed(monitor) => (MonitorChange::Changed, monitor),
+        };
+        self.recorder.push(Record::MonitorEvent { change, monitor_id: monitor.id() });
+        self.app.monitor_event(event_loop, event);
+    }
+
+    fn about_to_wait(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.app.about_to_wait(event_loop);
+    }
+
+    fn suspended(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.app.suspended(event_loop);
+    }
+
+    fn destroy_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.app.destroy_surfaces(event_loop);
+    }
+
+    fn memory_warning(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.app.memory_warning(event_loop);
+    }
+
+    fn macos_handler(&mut self) -> Option<&mut dyn macos::ApplicationHandlerExtMacOS> {
+        self.app.macos_handler()
+    }
+}
+
+/// How fast a [`Replayer`] feeds the trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReplaySpeed {
+    /// Deliver the events with the timing they were recorded with.
+    Original,
+    /// Deliver all the events as soon as possible.
+    AsFastAsPossible,
+}
+
+/// An [`ApplicationHandler`] feeding a recorded [`Trace`] to the application.
+///
+/// The recorded windows are matched with the windows wrapped by the replayer's
+/// [`WindowRecorder`] in creation order, so the application must wrap its windows like it did
+/// while recording. Events for a window that wasn't created yet are dropped.
+///
+/// While the trace is replayed, the live window, device and clipboard events are dropped, except
+/// [`WindowEvent::RedrawRequested`], so the application keeps drawing. The replay is itself
+/// recorded, see [`Replayer::trace()`].
+#[derive(Debug)]
+pub struct Replayer<A> {
+    app: A,
+    recorder: WindowRecorder,
+    /// The events left to replay.
+    entries: VecDeque<TraceEntry>,
+    /// The recorded windows, in creation order.
+    windows: Vec<WindowId>,
+    speed: ReplaySpeed,
+    /// When the replay started, once the application could create its windows.
+    start: Option<Instant>,
+    /// The latest wake up time reached, as the clock of the event loop may run ahead of
+    /// [`Instant::now()`], like the one of the headless backend.
+    resumed_at: Option<Instant>,
+    /// The control flow set to wait for the next event, with the one of the application.
+    control_flow: Option<(ControlFlow, ControlFlow)>,
+}
+
+impl<A: ApplicationHandler> Replayer<A> {
+    /// Replay `trace` into `app`, matching the recorded windows with the ones wrapped by
+    /// `recorder`.
+    pub fn new(app: A, recorder: WindowRecorder, trace: Trace, speed: ReplaySpeed) -> Self {
+        let windows = trace.windows();
+        let entries = trace
+            .entries
+            .into_iter()
+            .filter(|entry| {
+                matches!(
+                    entry.record,
+                    Record::WindowEvent { .. }
+                        | Record::DeviceEvent { .. }
+                        | Record::ClipboardEvent { .. }
+                        | Record::ProxyWakeUp
+                )
+            })
+            .collect();
+        Self {
+            app,
+            recorder,
+            entries,
+            windows,
+            speed,
+            start: None,
+            resumed_at: None,
+            control_flow: None,
+        }
+    }
+
+    /// Whether the whole trace was replayed.
+    pub fn is_done(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The trace of the replay, to compare it with the original one.
+    pub fn trace(&self) -> Trace {
+        self.recorder.trace()
+    }
+
+    pub fn app(&self) -> &A {
+        &self.app
+    }
+
+    pub fn app_mut(&mut self) -> &mut A {
+        &mut self.app
+    }
+
+    /// The live window matching a recorded one.
+    fn live_window(&self, window_id: WindowId) -> Option<WindowId> {
+        let index = self.windows.iter().position(|&id| id == window_id)?;
+        self.recorder.window(index)
+    }
+
+    fn replay_due(&mut self, event_loop: &dyn ActiveEventLoop, start: Instant) {
+        let now = Instant::now().max(self.resumed_at.unwrap_or(start));
+        while let Some(entry) = self.entries.front() {
+            if self.speed == ReplaySpeed::Original && start + entry.time > now {
+                break;
+            }
+
+            let entry = self.entries.pop_front().unwrap();
+            match entry.record {
+                Record::WindowEvent { window_id, event } => {
+                    if let Some(window_id) = self.live_window(window_id) {
+                        self.recorder.push(Record::WindowEvent { window_id, event: event.clone() });
+                        self.app.window_event(event_loop, window_id, event);
+                    }
+                },
+                Record::DeviceEvent { device_id, event } => {
+                    self.recorder.push(Record::DeviceEvent { device_id, event });
+                    self.app.device_event(event_loop, device_id, event);
+                },
+                Record::ClipboardEvent { event } => {
+                    self.recorder.push(Record::ClipboardEvent { event: event.clone() });
+                    self.app.clipboard_event(event_loop, event);
+                },
+                Record::ProxyWakeUp => {
+                    self.recorder.push(Record::ProxyWakeUp);
+                    self.app.proxy_wake_up(event_loop);
+                },
+                Record::WindowCreated { .. }
+                | Record::MonitorEvent { .. }
+                | Record::WindowRequest { .. } => (),
+            }
+        }
+    }
+}
+
+#[deny(clippy::missing_trait_methods)]
+impl<A: ApplicationHandler> ApplicationHandler for Replayer<A> {
+    fn new_events(&mut self, event_loop: &dyn ActiveEventLoop, cause: StartCause) {
+        if let StartCause::ResumeTimeReached { requested_resume, .. } = cause {
+            self.resumed_at = Some(requested_resume);
+        }
+        self.app.new_events(event_loop, cause);
+    }
+
+    fn resumed(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.app.resumed(event_loop);
+    }
+
+    fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.app.can_create_surfaces(event_loop);
+        self.start.get_or_insert_with(Instant::now);
+    }
+
+    fn proxy_wake_up(&mut self, event_loop: &dyn ActiveEventLoop) {
+        if self.is_done() {
+            self.recorder.push(Record::ProxyWakeUp);
+            self.app.proxy_wake_up(event_loop);
+        }
+    }
+
+    fn window_event(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        if self.is_done() || event == WindowEvent::RedrawRequested {
+            self.recorder.push(Record::WindowEvent { window_id, event: event.clone() });
+            self.app.window_event(event_loop, window_id, event);
+        }
+    }
+
+    fn device_event(
+        &mut self,
+        event_loop: &dyn ActiveEventLoop,
+        device_id: Option<DeviceId>,
+        event: DeviceEvent,
+    ) {
+        if self.is_done() {
+            self.recorder.push(Record::DeviceEvent { device_id, event });
+            self.app.device_event(event_loop, device_id, event);
+        }
+    }
+
+    fn clipboard_event(&mut self, event_loop: &dyn ActiveEventLoop, event: ClipboardEvent) {
+        if self.is_done() {
+            self.recorder.push(Record::ClipboardEvent { event: event.clone() });
+            self.app.clipboard_event(event_loop, event);
+        }
+    }
+
+    fn monitor_event(&mut self, event_loop: &dyn ActiveEventLoop, event: MonitorEvent) {
+        self.app.monitor_event(event_loop, event);
+    }
+
+    fn about_to_wait(&mut self, event_loop: &dyn ActiveEventLoop) {
+        // Give the application its control flow back, unless it changed it since.
+        if let Some((replay_flow, app_flow)) = self.control_flow.take() {
+            if event_loop.control_flow() == replay_flow {
+                event_loop.set_control_flow(app_flow);
+            }
+        }
+
+        if let Some(start) = self.start {
+            self.replay_due(event_loop, start);
+        }
+
+        self.app.about_to_wait(event_loop);
+
+        // Wake up for the next event.
+        let (Some(start), Some(entry)) = (self.start, self.entries.front()) else {
+            return;
+        };
+        let requested_resume = start + entry.time;
+        let app_flow = event_loop.control_flow();
+        let replay_flow = match app_flow {
+            ControlFlow::Poll => return,
+            ControlFlow::Wait => ControlFlow::WaitUntil(requested_resume),
+            ControlFlow::WaitUntil(instant) => {
+                ControlFlow::WaitUntil(instant.min(requested_resume))
+            },
+        };
+        if replay_flow != app_flow {
+            event_loop.set_control_flow(replay_flow);
+            self.control_flow = Some((replay_flow, app_flow));
+        }
+    }
+
+    fn suspended(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.app.suspended(event_loop);
+    }
+
+    fn destroy_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.app.destroy_surfaces(event_loop);
+    }
+
+    fn memory_warning(&mut self, event_loop: &dyn ActiveEventLoop) {
+        self.app.memory_warning(event_loop);
+    }
+
+    fn macos_handler(&mut self) -> Option<&mut dyn macos::ApplicationHandlerExtMacOS> {
+        self.app.macos_handler()
+    }
+}
+
+/// A window recording the requests made to it.
+#[derive(Debug)]
+struct RecordedWindow {
+    window: Box<dyn Window>,
+    recorder: WindowRecorder,
+}
+
+impl RecordedWindow {
+    fn record(&self, request: WindowRequest) {
+        self.recorder.push(Record::WindowRequest { window_id: self.window.id(), request });
+    }
+}
+
+#[deny(clippy::missing_trait_methods)]
+impl Window for RecordedWindow {
+    fn id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.window.scale_factor()
+    }
+
+    fn request_redraw(&self) {
+        self.record(WindowRequest::RequestRedraw);
+        self.window.request_redraw();
+    }
+
+    fn pre_present_notify(&self) {
+        self.record(WindowRequest::PrePresentNotify);
+        self.window.pre_present_notify();
+    }
+
+    fn reset_dead_keys(&self) {
+        self.window.reset_dead_keys();
+    }
+
+    fn surface_position(&self) -> PhysicalPosition<i32> {
+        self.window.surface_position()
+    }
+
+    fn outer_position(&self) -> Result<PhysicalPosition<i32>, RequestError> {
+        self.window.outer_position()
+    }
+
+    fn set_outer_position(&self, position: Position) {
+        self.record(WindowRequest::SetOuterPosition(position));
+        self.window.set_outer_position(position);
+    }
+
+    fn surface_size(&self) -> PhysicalSize<u32> {
+        self.window.surface_size()
+    }
+
+    fn request_surface_size(&self, size: Size) -> Option<PhysicalSize<u32>> {
+        self.record(WindowRequest::RequestSurfaceSize(size));
+        self.window.request_surface_size(size)
+    }
+
+    fn outer_size(&self) -> PhysicalSize<u32> {
+        self.window.outer_size()
+    }
+
+    fn safe_area(&self) -> PhysicalInsets<u32> {
+        self.window.safe_area()
+    }
+
+    fn set_min_surface_size(&self, min_size: Option<Size>) {
+        self.record(WindowRequest::SetMinSurfaceSize(min_size));
+        self.window.set_min_surface_size(min_size);
+    }
+
+    fn set_max_surface_size(&self, max_size: Option<Size>) {
+        self.record(WindowRequest::SetMaxSurfaceSize(max_size));
+        self.window.set_max_surface_size(max_size);
+    }
+
+    fn surface_resize_increments(&self) -> Option<PhysicalSize<u32>> {
+        self.window.surface_resize_increments()
+    }
+
+    fn set_surface_resize_increments(&self, increments: Option<Size>) {
+        self.record(WindowRequest::SetSurfaceResizeIncrements(increments));
+        self.window.set_surface_resize_increments(increments);
+    }
+
+    fn set_title(&self, title: &str) {
+        self.record(WindowRequest::SetTitle(title.to_owned()));
+        self.window.set_title(title);
+    }
+
+    fn set_transparent(&self, transparent: bool) {
+        self.record(WindowRequest::SetTransparent(transparent));
+        self.window.set_transparent(transparent);
+    }
+
+    fn set_blur(&self, blur: bool) {
+        self.record(WindowRequest::SetBlur(blur));
+        self.window.set_blur(blur);
+    }
+
+    fn set_visible(&self, visible: bool) {
+        self.record(WindowRequest::SetVisible(visible));
+        self.window.set_visible(visible);
+    }
+
+    fn is_visible(&self) -> Option<bool> {
+        self.window.is_visible()
+    }
+
+    fn set_resizable(&self, resizable: bool) {
+        self.record(WindowRequest::SetResizable(resizable));
+        self.window.set_resizable(resizable);
+    }
+
+    fn is_resizable(&self) -> bool {
+        self.window.is_resizable()
+    }
+
+    fn set_enabled_buttons(&self, buttons: WindowButtons) {
+        self.record(WindowRequest::SetEnabledButtons(buttons));
+        self.window.set_enabled_buttons(buttons);
+    }
+
+    fn enabled_buttons(&self) -> WindowButtons {
+        self.window.enabled_buttons()
+    }
+
+    fn set_minimized(&self, minimized: bool) {
+        self.record(WindowRequest::SetMinimized(minimized));
+        self.window.set_minimized(minimized);
+    }
+
+    fn is_minimized(&self) -> Option<bool> {
+        self.window.is_minimized()
+    }
+
+    fn set_maximized(&self, maximized: bool) {
+        self.record(WindowRequest::SetMaximized(maximized));
+        self.window.set_maximized(maximized);
+    }
+
+    fn is_maximized(&self) -> bool {
+        self.window.is_maximized()
+    }
+
+    fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.record(WindowRequest::SetFullscreen(fullscreen.is_some()));
+        self.window.set_fullscreen(fullscreen);
+    }
+
+    fn fullscreen(&self) -> Option<Fullscreen> {
+        self.window.fullscreen()
+    }
+
+    fn set_content_type(&self, content_type: ContentType) -> Result<(), RequestError> {
+        self.record(WindowRequest::SetContentType(content_type));
+        self.window.set_content_type(content_type)
+    }
+
+    fn set_allow_tearing(&self, allow_tearing: bool) -> Result<(), RequestError> {
+        self.record(WindowRequest::SetAllowTearing(allow_tearing));
+        self.window.set_allow_tearing(allow_tearing)
+    }
+
+    fn set_decorations(&self, decorations: bool) {
+        self.record(WindowRequest::SetDecorations(decorations));
+        self.window.set_decorations(decorations);
+    }
+
+    fn is_decorated(&self) -> bool {
+        self.window.is_decorated()
+    }
+
+    fn set_window_level(&self, level: WindowLevel) {
+        self.record(WindowRequest::SetWindowLevel(level));
+        self.window.set_window_level(level);
+    }
+
+    fn set_owner_window(&self, owner: Option<WindowId>, modal: bool) -> Result<(), RequestError> {
+        let index = owner.and_then(|owner| self.recorder.index(owner));
+        self.record(WindowRequest::SetOwnerWindow { owner: index, modal });
+        self.window.set_owner_window(owner, modal)
+    }
+
+    fn set_window_icon(&self, window_icon: Option<Icon>) {
+        self.record(WindowRequest::SetWindowIcon(window_icon.is_some()));
+        self.window.set_window_icon(window_icon);
+    }
+
+    // The deprecated IME setters are forwarded, as backends may override them, and aren't
+    // recorded.
+    #[allow(deprecated)]
+    fn set_ime_cursor_area(&self, position: Position, size: Size) {
+        self.window.set_ime_cursor_area(position, size);
+    }
+
+    #[allow(deprecated)]
+    fn set_ime_allowed(&self, allowed: bool) {
+        self.window.set_ime_allowed(allowed);
+    }
+
+    #[allow(deprecated)]
+    fn set_ime_purpose(&self, purpose: ImePurpose) {
+        self.window.set_ime_purpose(purpose);
+    }
+
+    fn request_ime_update(&self, request: ImeRequest) -> Result<(), ImeRequestError> {
+        self.record(WindowRequest::Ime(request.clone()));
+        self.window.request_ime_update(request)
+    }
+
+    fn ime_capabilities(&self) -> Option<ImeCapabilities> {
+        self.window.ime_capabilities()
+    }
+
+    fn set_keyboard_layout(&self, index: usize) -> Result<(), RequestError> {
+        self.record(WindowRequest::SetKeyboardLayout(index));
+        self.window.set_keyboard_layout(index)
+    }
+
+    fn focus_window(&self) {
+        self.record(WindowRequest::FocusWindow);
+        self.window.focus_window();
+    }
+
+    fn has_focus(&self) -> bool {
+        self.window.has_focus()
+    }
+
+    fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
+        self.record(WindowRequest::RequestUserAttention(request_type));
+        self.window.request_user_attention(request_type);
+    }
+
+    fn set_theme(&self, theme: Option<Theme>) {
+        self.record(WindowRequest::SetTheme(theme));
+        self.window.set_theme(theme);
+    }
+
+    fn theme(&self) -> Option<Theme> {
+        self.window.theme()
+    }
+
+    fn set_content_protected(&self, protected: bool) {
+        self.record(WindowRequest::SetContentProtected(protected));
+        self.window.set_content_protected(protected);
+    }
+
+    fn set_idle_inhibited(&self, inhibited: bool) -> Result<(), RequestError> {
+        self.record(WindowRequest::SetIdleInhibited(inhibited));
+        self.window.set_idle_inhibited(inhibited)
+    }
+
+    fn title(&self) -> String {
+        self.window.title()
+    }
+
+    fn set_cursor(&self, cursor: Cursor) {
+        let icon = match cursor {
+            Cursor::Icon(icon) => Some(icon),
+            Cursor::Custom(_) => None,
+        };
+        self.record(WindowRequest::SetCursor(icon));
+        self.window.set_cursor(cursor);
+    }
+
+    fn set_cursor_position(&self, position: Position) -> Result<(), RequestError> {
+        self.record(WindowRequest::SetCursorPosition(position));
+        self.window.set_cursor_position(position)
+    }
+
+    fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), RequestError> {
+        self.record(WindowRequest::SetCursorGrab(mode));
+        self.window.set_cursor_grab(mode)
+    }
+
+    fn set_keyboard_grab(&self, grab: bool) -> Result<(), RequestError> {
+        self.record(WindowRequest::SetKeyboardGrab(grab));
+        self.window.set_keyboard_grab(grab)
+    }
+
+    fn set_cursor_visible(&self, visible: bool) {
+        self.record(WindowRequest::SetCursorVisible(visible));
+        self.window.set_cursor_visible(visible);
+    }
+
+    fn drag_window(&self) -> Result<(), RequestError> {
+        self.record(WindowRequest::DragWindow);
+        self.window.drag_window()
+    }
+
+    fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), RequestError> {
+        self.record(WindowRequest::DragResizeWindow(direction));
+        self.window.drag_resize_window(direction)
+    }
+
+    fn start_drag(
+        &self,
+        contents: Vec<ClipboardData>,
+        actions: DragActions,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        self.record(WindowRequest::StartDrag { contents: contents.clone(), actions });
+        self.window.start_drag(contents, actions)
+    }
+
+    fn accept_drag(&self, mime_type: &str, action: DragAction) -> Result<(), RequestError> {
+        self.record(WindowRequest::AcceptDrag { mime_type: mime_type.to_owned(), action });
+        self.window.accept_drag(mime_type, action)
+    }
+
+    fn reject_drag(&self) -> Result<(), RequestError> {
+        self.record(WindowRequest::RejectDrag);
+        self.window.reject_drag()
+    }
+
+    fn request_drag_data(&self, mime_type: &str) -> Result<AsyncRequestSerial, RequestError> {
+        self.record(WindowRequest::RequestDragData(mime_type.to_owned()));
+        self.window.request_drag_data(mime_type)
+    }
+
+    fn show_window_menu(&self, position: Position) {
+        self.record(WindowRequest::ShowWindowMenu(position));
+        self.window.show_window_menu(position);
+    }
+
+    fn set_cursor_hittest(&self, hittest: bool) -> Result<(), RequestError> {
+        self.record(WindowRequest::SetCursorHittest(hittest));
+        self.window.set_cursor_hittest(hittest)
+    }
+
+    fn current_monitor(&self) -> Option<MonitorHandle> {
+        self.window.current_monitor()
+    }
+
+    fn available_monitors(&self) -> Box<dyn Iterator<Item = MonitorHandle>> {
+        self.window.available_monitors()
+    }
+
+    fn primary_monitor(&self) -> Option<MonitorHandle> {
+        self.window.primary_monitor()
+    }
+
+    fn rwh_06_display_handle(&self) -> &dyn rwh_06::HasDisplayHandle {
+        self.window.rwh_06_display_handle()
+    }
+
+    fn rwh_06_window_handle(&self) -> &dyn rwh_06::HasWindowHandle {
+        self.window.rwh_06_window_handle()
+    }
+}
@@ -10,8 +10,11 @@ use dpi::{
 use serde::{Deserialize, Serialize};
 
 use crate::as_any::AsAny;
+use crate::clipboard::ClipboardData;
 use crate::cursor::Cursor;
-use crate::error::RequestError;
+use crate::drag::{DragAction, DragActions};
+use crate::error::{NotSupportedError, RequestError};
+use crate::event_loop::AsyncRequestSerial;
 use crate::icon::Icon;
 use crate::monitor::{Fullscreen, MonitorHandle};
 
@@ -22,6 +25,7 @@ use crate::monitor::{Fullscreen, MonitorHandle};
 /// Whenever you receive an event specific to a window, this event contains a `WindowId` which you
 /// can then compare to the ids of your windows.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct WindowId(usize);
 
 impl WindowId {
@@ -67,10 +71,15 @@ pub struct WindowAttributes {
     pub preferred_theme: Option<Theme>,
     pub content_protected: bool,
     pub window_level: WindowLevel,
+    pub content_type: ContentType,
+    pub allow_tearing: bool,
     pub active: bool,
     pub cursor: Cursor,
     pub(crate) parent_window: Option<SendSyncRawWindowHandle>,
+    pub owner_window: Option<WindowId>,
+    pub modal: bool,
     pub fullscreen: Option<Fullscreen>,
+    pub popup: Option<PopupAttributes>,
     pub platform: Option<Box<dyn PlatformWindowAttributes>>,
 }
 
@@ -145,6 +154,7 @@ impl WindowAttributes {
     ///   position. There may be a small gap between this position and the window due to the
     ///   specifics of the Window Manager.
     /// - **X11:** The top left corner of the window, the window's "outer" position.
+    /// - **Wayland:** The position of a child window relative to its parent, ignored otherwise.
     /// - **Others:** Ignored.
     #[inline]
     pub fn with_position<P: Into<Position>>(mut self, position: P) -> Self {
@@ -273,6 +283,28 @@ impl WindowAttributes {
         self
     }
 
+    /// Sets the kind of content shown by the window.
+    ///
+    /// The default is [`ContentType::None`].
+    ///
+    /// See [`Window::set_content_type`] for details.
+    #[inline]
+    pub fn with_content_type(mut self, content_type: ContentType) -> Self {
+        self.content_type = content_type;
+        self
+    }
+
+    /// Sets whether the window may present frames without waiting for vertical sync.
+    ///
+    /// The default is `false`.
+    ///
+    /// See [`Window::set_allow_tearing`] for details.
+    #[inline]
+    pub fn with_allow_tearing(mut self, allow_tearing: bool) -> Self {
+        self.allow_tearing = allow_tearing;
+        self
+    }
+
     /// Sets the window icon.
     ///
     /// The default is `None`.
@@ -360,7 +392,9 @@ impl WindowAttributes {
     ///   to the client area of its parent window. For more information, see
     ///   <https://docs.microsoft.com/en-us/windows/win32/winmsg/window-features#child-windows>
     /// - **X11**: A child window is confined to the client area of its parent window.
-    /// - **Android / iOS / Wayland / Web:** Unsupported.
+    /// - **Wayland:** A child window is a subsurface placed relative to the surface of its parent,
+    ///   which must be a window of the same event loop. The keyboard focus stays with the parent.
+    /// - **Android / iOS / Web:** Unsupported.
     #[inline]
     pub unsafe fn with_parent_window(
         mut self,
@@ -370,6 +404,57 @@ impl WindowAttributes {
         self
     }
 
+    /// Build a dialog window owned by another toplevel window.
+    ///
+    /// The window stays above its owner and is minimized along with it. The owner must be a
+    /// window of the same event loop.
+    ///
+    /// The default is `None`.
+    ///
+    /// See [`Window::set_owner_window`] for details.
+    #[inline]
+    pub fn with_owner_window(mut self, owner_window: Option<WindowId>) -> Self {
+        self.owner_window = owner_window;
+        self
+    }
+
+    /// Sets whether the window prevents interaction with its owner while it's shown.
+    ///
+    /// This has no effect without an [owner window].
+    ///
+    /// The default is `false`.
+    ///
+    /// See [`Window::set_owner_window`] for details.
+    ///
+    /// [owner window]: Self::with_owner_window
+    #[inline]
+    pub fn with_modal(mut self, modal: bool) -> Self {
+        self.modal = modal;
+        self
+    }
+
+    /// Build a popup window, such as a menu or a tooltip, placed relative to its parent window.
+    ///
+    /// When the compositor or the window manager dismisses the popup, e.g. because the user
+    /// clicked outside of it, [`WindowEvent::PopupDismissed`] is delivered to the popup, which
+    /// should then be dropped.
+    ///
+    /// The default is `None`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Created with `xdg_popup`, the parent must be a window of the same event loop.
+    /// - **X11:** Created as an override-redirect window placed by winit, the constraint
+    ///   adjustments use the monitor the anchor rectangle is on.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`WindowEvent::PopupDismissed`]: crate::event::WindowEvent::PopupDismissed
+    #[inline]
+    pub fn with_popup(mut self, popup: Option<PopupAttributes>) -> Self {
+        self.popup = popup;
+        self
+    }
+
     /// Set the platform specific opaque attribute object.
     ///
     /// The interpretation will depend on the underlying backend that will be used.
@@ -400,10 +485,15 @@ impl Clone for WindowAttributes {
             preferred_theme: self.preferred_theme,
             content_protected: self.content_protected,
             window_level: self.window_level,
+            content_type: self.content_type,
+            allow_tearing: self.allow_tearing,
             active: self.active,
             cursor: self.cursor.clone(),
             parent_window: self.parent_window.clone(),
+            owner_window: self.owner_window,
+            modal: self.modal,
             fullscreen: self.fullscreen.clone(),
+            popup: self.popup,
             platform: self.platform.as_ref().map(|platform| platform.box_clone()),
         }
     }
@@ -425,11 +515,16 @@ impl Default for WindowAttributes {
             max_surface_size: Default::default(),
             preferred_theme: Default::default(),
             parent_window: Default::default(),
+            owner_window: Default::default(),
+            modal: Default::default(),
             surface_size: Default::default(),
             window_level: Default::default(),
+            content_type: Default::default(),
+            allow_tearing: Default::default(),
             window_icon: Default::default(),
             transparent: Default::default(),
             fullscreen: Default::default(),
+            popup: Default::default(),
             maximized: Default::default(),
             position: Default::default(),
             platform: Default::default(),
@@ -439,6 +534,135 @@ impl Default for WindowAttributes {
     }
 }
 
+/// The placement of a popup window, see [`WindowAttributes::with_popup()`].
+///
+/// The popup is placed relative to an anchor rectangle in the surface of its parent: the
+/// [`anchor`][Self::anchor] picks a point on the rectangle, and the [`gravity`][Self::gravity]
+/// picks the direction the popup extends to from that point. When the popup doesn't fit on the
+/// screen, it is moved according to the [`constraint_adjustment`][Self::constraint_adjustment].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PopupAttributes {
+    /// The window the popup is attached to.
+    pub parent: WindowId,
+    /// The position of the anchor rectangle, relative to the surface of the parent.
+    pub anchor_position: Position,
+    /// The size of the anchor rectangle.
+    pub anchor_size: Size,
+    /// The default is [`PopupAnchor::BottomLeft`].
+    pub anchor: PopupAnchor,
+    /// The default is [`PopupGravity::BottomRight`].
+    pub gravity: PopupGravity,
+    /// The default is to flip and slide the popup along both axes.
+    pub constraint_adjustment: PopupConstraintAdjustment,
+    /// Whether the popup grabs the input, like menus do.
+    ///
+    /// A popup with a grab gets the keyboard focus, and is dismissed when the user clicks outside
+    /// of it. This requires the popup to be created in response to user input, such as a click.
+    ///
+    /// The default is `true`, use `false` for tooltips.
+    pub grab: bool,
+}
+
+impl PopupAttributes {
+    /// A popup below the anchor rectangle, such as a drop down menu below a button.
+    pub fn new(parent: WindowId, anchor_position: Position, anchor_size: Size) -> Self {
+        Self {
+            parent,
+            anchor_position,
+            anchor_size,
+            anchor: PopupAnchor::BottomLeft,
+            gravity: PopupGravity::BottomRight,
+            constraint_adjustment: PopupConstraintAdjustment::FLIP_X
+                | PopupConstraintAdjustment::FLIP_Y
+                | PopupConstraintAdjustment::SLIDE_X
+                | PopupConstraintAdjustment::SLIDE_Y,
+            grab: true,
+        }
+    }
+
+    #[inline]
+    pub fn with_anchor(mut self, anchor: PopupAnchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    #[inline]
+    pub fn with_gravity(mut self, gravity: PopupGravity) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    #[inline]
+    pub fn with_constraint_adjustment(mut self, adjustment: PopupConstraintAdjustment) -> Self {
+        self.constraint_adjustment = adjustment;
+        self
+    }
+
+    #[inline]
+    pub fn with_grab(mut self, grab: bool) -> Self {
+        self.grab = grab;
+        self
+    }
+}
+
+/// The point of the anchor rectangle a popup is placed at.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PopupAnchor {
+    #[default]
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    BottomLeft,
+    TopRight,
+    BottomRight,
+}
+
+/// The direction a popup extends to from its anchor point.
+///
+/// For example, with [`PopupGravity::BottomRight`] the top left corner of the popup is at the
+/// anchor point.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PopupGravity {
+    #[default]
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    BottomLeft,
+    TopRight,
+    BottomRight,
+}
+
+bitflags! {
+    /// How a popup that doesn't fit on the screen is moved.
+    ///
+    /// The adjustments are tried in order: flipping the anchor and the gravity, then sliding the
+    /// popup, then resizing it.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct PopupConstraintAdjustment: u32 {
+        /// Slide the popup horizontally until it fits.
+        const SLIDE_X = 1 << 0;
+        /// Slide the popup vertically until it fits.
+        const SLIDE_Y = 1 << 1;
+        /// Place the popup on the other side of the anchor rectangle horizontally.
+        const FLIP_X = 1 << 2;
+        /// Place the popup on the other side of the anchor rectangle vertically.
+        const FLIP_Y = 1 << 3;
+        /// Shrink the popup horizontally until it fits.
+        const RESIZE_X = 1 << 4;
+        /// Shrink the popup vertically until it fits.
+        const RESIZE_Y = 1 << 5;
+    }
+}
+
 /// Wrapper for [`rwh_06::RawWindowHandle`] for [`WindowAttributes::parent_window`].
 ///
 /// # Safety
@@ -599,10 +823,13 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     ///
     /// ## Platform-specific
     ///
-    /// - **Android / iOS / X11 / Web / Windows / macOS / Orbital:** Unsupported.
-    /// - **Wayland:** Schedules a frame callback to throttle [`WindowEvent::RedrawRequested`].
+    /// - **Android / iOS / Web / Windows / macOS / Orbital:** Unsupported.
+    /// - **Wayland:** Schedules a frame callback to throttle [`WindowEvent::RedrawRequested`], and
+    ///   requests [`WindowEvent::FramePresented`] for the frame.
+    /// - **X11:** Requests [`WindowEvent::FramePresented`] for the frame.
     ///
     /// [`WindowEvent::RedrawRequested`]: crate::event::WindowEvent::RedrawRequested
+    /// [`WindowEvent::FramePresented`]: crate::event::WindowEvent::FramePresented
     fn pre_present_notify(&self);
 
     /// Reset the dead key state of the keyboard.
@@ -651,7 +878,9 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// ## Platform-specific
     ///
     /// - **Web:** Returns the top-left coordinates relative to the viewport.
-    /// - **Android / Wayland:** Always returns [`RequestError::NotSupported`].
+    /// - **Wayland:** Returns the position relative to the parent for child windows, and
+    ///   [`RequestError::NotSupported`] otherwise.
+    /// - **Android:** Always returns [`RequestError::NotSupported`].
     fn outer_position(&self) -> Result<PhysicalPosition<i32>, RequestError>;
 
     /// Sets the position of the window on the desktop.
@@ -677,7 +906,9 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     ///   system.
     /// - **Web:** Sets the top-left coordinates relative to the viewport. Doesn't account for CSS
     ///   [`transform`].
-    /// - **Android / Wayland:** Unsupported.
+    /// - **Wayland:** Only supported for child windows, relative to their parent. The position is
+    ///   applied with the next frame of the parent, for which a redraw is requested.
+    /// - **Android:** Unsupported.
     ///
     /// [`transform`]: https://developer.mozilla.org/en-US/docs/Web/CSS/transform
     fn set_outer_position(&self, position: Position);
@@ -870,8 +1101,9 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     ///
     /// - **macOS:** This will reset the window's background color.
     /// - **Web / iOS / Android:** Unsupported.
-    /// - **X11:** Can only be set while building the window, with
-    ///   [`WindowAttributes::with_transparent`].
+    /// - **X11:** Only has an effect on windows with an ARGB visual, which are created with
+    ///   [`WindowAttributes::with_transparent`] or an ARGB visual from the platform attributes.
+    ///   Other windows are always opaque.
     fn set_transparent(&self, transparent: bool);
 
     /// Change the window blur state.
@@ -880,8 +1112,9 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     ///
     /// ## Platform-specific
     ///
-    /// - **Android / iOS / X11 / Web / Windows:** Unsupported.
+    /// - **Android / iOS / Web / Windows:** Unsupported.
     /// - **Wayland:** Only works with org_kde_kwin_blur_manager protocol.
+    /// - **X11:** Only works with compositors supporting `_KDE_NET_WM_BLUR_BEHIND_REGION`.
     fn set_blur(&self, blur: bool);
 
     /// Modifies the window's visibility.
@@ -933,7 +1166,8 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     ///
     /// ## Platform-specific
     ///
-    /// - **Wayland / X11 / Orbital:** Not implemented.
+    /// - **Wayland / Orbital:** Not implemented.
+    /// - **X11:** Relies on the window manager honoring the Motif hints.
     /// - **Web / iOS / Android:** Unsupported.
     fn set_enabled_buttons(&self, buttons: WindowButtons);
 
@@ -941,7 +1175,7 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     ///
     /// ## Platform-specific
     ///
-    /// - **Wayland / X11 / Orbital:** Not implemented. Always returns [`WindowButtons::all`].
+    /// - **Wayland / Orbital:** Not implemented. Always returns [`WindowButtons::all`].
     /// - **Web / iOS / Android:** Unsupported. Always returns [`WindowButtons::all`].
     fn enabled_buttons(&self) -> WindowButtons;
 
@@ -1015,6 +1249,40 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// - **Wayland:** Can return `Borderless(None)` when there are no monitors.
     fn fullscreen(&self) -> Option<Fullscreen>;
 
+    /// Hints the kind of content shown by the window.
+    ///
+    /// The system may use it to pick a presentation better suited to the content, like
+    /// bypassing the compositor for games in fullscreen.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Requires the `wp_content_type_v1` protocol.
+    /// - **X11:** Sets `_NET_WM_BYPASS_COMPOSITOR` to request that the compositor is bypassed for
+    ///   [`ContentType::Video`] and [`ContentType::Game`].
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    fn set_content_type(&self, content_type: ContentType) -> Result<(), RequestError> {
+        let _ = content_type;
+        Err(NotSupportedError::new("set_content_type is not supported").into())
+    }
+
+    /// Allows the window to present frames as soon as possible, without waiting for vertical
+    /// sync, at the cost of tearing.
+    ///
+    /// This is usually only honored in fullscreen, and reduces latency for games.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Requires the `wp_tearing_control_v1` protocol.
+    /// - **X11:** Sets `_NET_WM_BYPASS_COMPOSITOR` to request that the compositor is bypassed, the
+    ///   tearing then depends on how the frames are presented.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    fn set_allow_tearing(&self, allow_tearing: bool) -> Result<(), RequestError> {
+        let _ = allow_tearing;
+        Err(NotSupportedError::new("set_allow_tearing is not supported").into())
+    }
+
     /// Turn window decorations on or off.
     ///
     /// Enable/disable window decorations provided by the server or Winit.
@@ -1043,6 +1311,26 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// See [`WindowLevel`] for details.
     fn set_window_level(&self, level: WindowLevel);
 
+    /// Sets the owner of the window, turning it into a dialog tied to the `owner` window, which
+    /// can be `modal` to block the interaction with its owner.
+    ///
+    /// The window stays above its owner and is minimized along with it. The owner must be a
+    /// window of the same event loop, like with [`WindowAttributes::with_owner_window`]. Passing
+    /// `None` makes it a regular window again.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Uses `xdg_toplevel.set_parent`, the modality requires the `xdg_dialog_v1`
+    ///   protocol. Unsupported for popups, layer surfaces and child windows.
+    /// - **X11:** Sets `WM_TRANSIENT_FOR` and `_NET_WM_STATE_MODAL`. A window created with an owner
+    ///   gets the `_NET_WM_WINDOW_TYPE_DIALOG` type, unless other types were requested.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    fn set_owner_window(&self, owner: Option<WindowId>, modal: bool) -> Result<(), RequestError> {
+        let _ = (owner, modal);
+        Err(NotSupportedError::new("set_owner_window is not supported").into())
+    }
+
     /// Sets the window icon.
     ///
     /// On Windows, Wayland and X11, this is typically the small icon in the top-left
@@ -1223,6 +1511,25 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// By default IME is disabled, thus will return `None`.
     fn ime_capabilities(&self) -> Option<ImeCapabilities>;
 
+    /// Switches the active keyboard layout to the one at `index` in
+    /// [`KeyboardLayouts::names`].
+    ///
+    /// The layout is switched for the whole keyboard, not only this window. The change is reported
+    /// with [`WindowEvent::KeyboardLayoutChanged`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** At most 4 layouts can be switched to, other indices are ignored.
+    /// - **Android / iOS / macOS / Orbital / Wayland / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`KeyboardLayouts::names`]: crate::keyboard::KeyboardLayouts::names
+    /// [`WindowEvent::KeyboardLayoutChanged`]: crate::event::WindowEvent::KeyboardLayoutChanged
+    fn set_keyboard_layout(&self, index: usize) -> Result<(), RequestError> {
+        let _ = index;
+        Err(NotSupportedError::new("set_keyboard_layout is not supported").into())
+    }
+
     /// Brings the window to the front and sets input focus. Has no effect if the window is
     /// already in focus, minimized, or not visible.
     ///
@@ -1291,6 +1598,27 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// [`NSWindowSharingNone`]: https://developer.apple.com/documentation/appkit/nswindowsharingtype/nswindowsharingnone
     fn set_content_protected(&self, protected: bool);
 
+    /// Prevents the system from going idle, e.g. dimming the screen or starting the screensaver.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Requires the `zwp_idle_inhibit_manager_v1` protocol. The inhibition only
+    ///   holds while the window is visible.
+    /// - **X11:** Suspends the screensaver with the MIT-SCREEN-SAVER extension, or inhibits it with
+    ///   the `org.freedesktop.ScreenSaver` D-Bus service when the extension is missing. The
+    ///   inhibition holds whether the window is visible or not. The D-Bus inhibition is best
+    ///   effort: it's made asynchronously, and its failures, like a missing session bus, are only
+    ///   logged.
+    /// - **Web:** Uses the Screen Wake Lock API, which the browser releases when the page is
+    ///   hidden. The lock is requested again when the page becomes visible. The lock is requested
+    ///   asynchronously, failures are only logged.
+    /// - **Android / iOS / macOS / Orbital / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    fn set_idle_inhibited(&self, inhibited: bool) -> Result<(), RequestError> {
+        let _ = inhibited;
+        Err(NotSupportedError::new("set_idle_inhibited is not supported").into())
+    }
+
     /// Gets the current title of the window.
     ///
     /// ## Platform-specific
@@ -1344,6 +1672,28 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// ```
     fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), RequestError>;
 
+    /// Grabs the keyboard, so that system shortcuts are delivered to the window.
+    ///
+    /// This is meant for remote desktop and virtual machine clients, which need to forward keys
+    /// like <kbd>Alt</kbd>+<kbd>Tab</kbd> or <kbd>Super</kbd>. The grab only applies while the
+    /// window has focus; [`WindowEvent::KeyboardGrabbed`] is emitted whenever it becomes active or
+    /// inactive.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Requires the `zwp_keyboard_shortcuts_inhibit_manager_v1` protocol. The
+    ///   compositor decides when the grab is active, and may ask the user first.
+    /// - **X11:** Actively grabs the keyboard when the window is focused, and releases it when the
+    ///   window loses focus.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`WindowEvent::KeyboardGrabbed`]: crate::event::WindowEvent::KeyboardGrabbed
+    fn set_keyboard_grab(&self, grab: bool) -> Result<(), RequestError> {
+        let _ = grab;
+        Err(NotSupportedError::new("set_keyboard_grab is not supported").into())
+    }
+
     /// Modifies the cursor's visibility.
     ///
     /// If `false`, this will hide the cursor. If `true`, this will show the cursor.
@@ -1382,6 +1732,86 @@ pub trait Window: AsAny + Send + Sync + fmt::Debug {
     /// - **iOS / Android / Web:** Always returns an [`RequestError::NotSupported`].
     fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), RequestError>;
 
+    /// Starts dragging `contents` out of the window with the pointer button being held.
+    ///
+    /// Each entry of `contents` is a different representation of the dragged data, such as
+    /// [`ClipboardData::files()`] for files, and the drop target picks the one it prefers.
+    /// `actions` lists what the drop target may do with the data.
+    ///
+    /// As with [`drag_window()`][Self::drag_window], this should be called right after a pointer
+    /// button was pressed over the window. The outcome is reported through
+    /// [`WindowEvent::DragSourceFinished`] with the returned serial.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11 / Wayland:** Starting a drag cancels the one in progress, if any.
+    /// - **Wayland:** [`DragAction::Link`] isn't supported and is never performed.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`ClipboardData::files()`]: crate::clipboard::ClipboardData::files
+    /// [`WindowEvent::DragSourceFinished`]: crate::event::WindowEvent::DragSourceFinished
+    /// [`DragAction::Link`]: crate::drag::DragAction::Link
+    fn start_drag(
+        &self,
+        contents: Vec<ClipboardData>,
+        actions: DragActions,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let _ = (contents, actions);
+        Err(NotSupportedError::new("start_drag is not supported").into())
+    }
+
+    /// Accepts dropping the data being dragged over the window as `mime_type`, with `action`.
+    ///
+    /// A drag offering [`URI_LIST_MIME_TYPE`] is accepted by default, any other drag is rejected
+    /// until this is called. The choice holds until the drag leaves the window or
+    /// [`reject_drag()`][Self::reject_drag] is called.
+    ///
+    /// `mime_type` should be one of [`WindowEvent::DragEntered::mime_types`], the drag is
+    /// rejected otherwise.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The drop target doesn't choose the MIME type, `mime_type` is only checked.
+    /// - **Wayland:** There's no link action, accepting with [`DragAction::Link`] rejects the drop.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`URI_LIST_MIME_TYPE`]: crate::clipboard::URI_LIST_MIME_TYPE
+    /// [`WindowEvent::DragEntered::mime_types`]: crate::event::WindowEvent::DragEntered::mime_types
+    fn accept_drag(&self, mime_type: &str, action: DragAction) -> Result<(), RequestError> {
+        let _ = (mime_type, action);
+        Err(NotSupportedError::new("accept_drag is not supported").into())
+    }
+
+    /// Rejects dropping the data being dragged over the window.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    fn reject_drag(&self) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("reject_drag is not supported").into())
+    }
+
+    /// Requests the data being dragged over the window as `mime_type`.
+    ///
+    /// The data is delivered with [`WindowEvent::DragData`] along with the returned serial. It
+    /// can be requested while the drag is over the window, and until the handling of
+    /// [`WindowEvent::DragDropped`] returns, which is when the dropped data should be requested.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`WindowEvent::DragData`]: crate::event::WindowEvent::DragData
+    /// [`WindowEvent::DragDropped`]: crate::event::WindowEvent::DragDropped
+    fn request_drag_data(&self, mime_type: &str) -> Result<AsyncRequestSerial, RequestError> {
+        let _ = mime_type;
+        Err(NotSupportedError::new("request_drag_data is not supported").into())
+    }
+
     /// Show [window menu] at a specified position in surface coordinates.
     ///
     /// This is the context menu that is normally shown when interacting with
@@ -1492,8 +1922,13 @@ pub enum CursorGrabMode {
     ///
     /// ## Platform-specific
     ///
-    /// - **X11:** Not implemented. Always returns [`RequestError::NotSupported`] for now.
+    /// - **X11:** Emulated by confining the cursor and warping it back to the lock position, so
+    ///   [`WindowEvent::PointerMoved`] isn't emitted while locked; use
+    ///   [`DeviceEvent::PointerMotion`] for relative motion.
     /// - **iOS / Android:** Always returns an [`RequestError::NotSupported`].
+    ///
+    /// [`WindowEvent::PointerMoved`]: crate::event::WindowEvent::PointerMoved
+    /// [`DeviceEvent::PointerMotion`]: crate::event::DeviceEvent::PointerMotion
     Locked,
 }
 
@@ -1565,6 +2000,7 @@ pub enum UserAttentionType {
 
 bitflags::bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct WindowButtons: u32 {
         const CLOSE  = 1 << 0;
         const MINIMIZE  = 1 << 1;
@@ -1596,6 +2032,24 @@ pub enum WindowLevel {
     AlwaysOnTop,
 }
 
+/// The kind of content shown by a window, see [`Window::set_content_type`].
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ContentType {
+    /// No particular content, the default.
+    #[default]
+    None,
+
+    /// Still images, which benefit from accurate colors.
+    Photo,
+
+    /// Video or animations, which benefit from a steady frame rate.
+    Video,
+
+    /// Interactive content like games, which benefits from low latency.
+    Game,
+}
+
 /// Generic IME purposes for use in [`Window::set_ime_purpose`].
 ///
 /// The purpose should reflect the kind of data to be entered.
@@ -1794,6 +2248,7 @@ impl ImeSurroundingText {
 
 /// Request to send to IME.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ImeRequest {
     /// Enable the IME with the [`ImeCapabilities`] and [`ImeRequestData`] as initial state. When
     /// the [`ImeRequestData`] is **not** matching capabilities fully, the default values will be
@@ -1817,6 +2272,7 @@ pub enum ImeRequest {
 
 /// Initial IME request.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ImeEnableRequest {
     capabilities: ImeCapabilities,
     request_data: ImeRequestData,
@@ -1869,6 +2325,7 @@ impl ImeEnableRequest {
 ///
 /// New capabilities may be added to this struct in the future.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ImeCapabilities(ImeCapabilitiesFlags);
 
 impl ImeCapabilities {
@@ -1937,6 +2394,7 @@ impl ImeCapabilities {
 
 bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub(crate) struct ImeCapabilitiesFlags : u8 {
         /// Client supports setting IME hint and purpose.
         const HINT_AND_PURPOSE = 1 << 0;
@@ -2061,6 +2519,7 @@ impl std::error::Error for ImeRequestError {}
 ///
 /// [`Window`]: crate::window::Window
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ActivationToken {
     pub(crate) token: String,
 }
@@ -0,0 +1,594 @@
+use std::cell::Cell;
+use std::collections::{BTreeMap, VecDeque};
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use winit_core::application::ApplicationHandler;
+use winit_core::cursor::{CustomCursor, CustomCursorProvider, CustomCursorSource};
+use winit_core::error::RequestError;
+use winit_core::event::{DeviceEvent, DeviceId, StartCause, SurfaceSizeWriter, WindowEvent};
+use winit_core::event_loop::pump_events::{EventLoopExtPumpEvents, PumpStatus};
+use winit_core::event_loop::{
+    ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents,
+    EventLoopProxy as CoreEventLoopProxy, EventLoopProxyProvider,
+    OwnedDisplayHandle as CoreOwnedDisplayHandle,
+};
+use winit_core::monitor::{MonitorEvent, MonitorHandle as CoreMonitorHandle};
+use winit_core::window::{Theme, Window as CoreWindow, WindowAttributes, WindowId};
+
+use crate::monitor::{Monitor, MonitorHandle};
+use crate::window::{Window, WindowState};
+
+#[derive(Debug)]
+pub(crate) enum Event {
+    Window { window_id: WindowId, event: WindowEvent },
+    ScaleFactor { window_id: WindowId, scale_factor: f64 },
+    Device { device_id: Option<DeviceId>, event: DeviceEvent },
+    Monitor { event: MonitorEvent },
+}
+
+/// The state shared between the event loop and the windows.
+#[derive(Debug, Default)]
+pub(crate) struct Shared {
+    pub(crate) windows: BTreeMap<WindowId, WindowState>,
+    pub(crate) redraws: VecDeque<WindowId>,
+    pub(crate) events: VecDeque<Event>,
+    pub(crate) monitors: Vec<CoreMonitorHandle>,
+    window_ids: usize,
+    monitor_ids: u64,
+}
+
+impl Shared {
+    pub(crate) fn next_window_id(&mut self) -> WindowId {
+        self.window_ids += 1;
+        WindowId::from_raw(self.window_ids)
+    }
+
+    pub(crate) fn request_redraw(&mut self, window_id: WindowId) {
+        if !self.redraws.contains(&window_id) {
+            self.redraws.push_back(window_id);
+        }
+    }
+
+    fn has_pending_events(&self) -> bool {
+        !self.events.is_empty() || !self.redraws.is_empty()
+    }
+}
+
+/// An event loop without any display server.
+///
+/// Events only come from the `send_*` methods, and time only moves forward when
+/// [`advance_time()`][Self::advance_time] is called or when
+/// [`pump_app_events()`][EventLoopExtPumpEvents::pump_app_events] waits. The loop never blocks:
+/// waiting jumps the clock to the end of the wait right away, to the earliest of the pump timeout
+/// and the [`ControlFlow::WaitUntil`] deadline.
+#[derive(Debug)]
+pub struct EventLoop {
+    active_event_loop: ActiveEventLoop,
+    running: bool,
+}
+
+impl Default for EventLoop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventLoop {
+    pub fn new() -> Self {
+        Self {
+            active_event_loop: ActiveEventLoop {
+                shared: Default::default(),
+                proxy: Arc::new(EventLoopProxy { woken: AtomicBool::new(false) }),
+                control_flow: Cell::new(ControlFlow::default()),
+                exit: Cell::new(false),
+                now: Cell::new(Instant::now()),
+                system_theme: Cell::new(None),
+                device_events: Cell::new(DeviceEvents::default()),
+            },
+            running: false,
+        }
+    }
+
+    /// The current time of the event loop's clock.
+    pub fn now(&self) -> Instant {
+        self.active_event_loop.now.get()
+    }
+
+    /// Move the event loop's clock forward.
+    pub fn advance_time(&mut self, duration: Duration) {
+        self.active_event_loop.now.set(self.now() + duration);
+    }
+
+    pub fn set_system_theme(&mut self, theme: Option<Theme>) {
+        self.active_event_loop.system_theme.set(theme);
+    }
+
+    /// Connect a monitor, the first one connected is the primary monitor.
+    ///
+    /// A [`MonitorEvent::Added`] is delivered if the event loop is already running.
+    pub fn add_monitor(&mut self, monitor: Monitor) -> CoreMonitorHandle {
+        let mut shared = self.active_event_loop.shared.lock().unwrap();
+        shared.monitor_ids += 1;
+        let handle = CoreMonitorHandle(Arc::new(MonitorHandle::new(shared.monitor_ids, monitor)));
+        shared.monitors.push(handle.clone());
+        if self.running {
+            shared.events.push_back(Event::Monitor { event: MonitorEvent::Added(handle.clone()) });
+        }
+
+        handle
+    }
+
+    /// Disconnect a monitor.
+    ///
+    /// A [`MonitorEvent::Removed`] is delivered if the event loop is already running.
+    pub fn remove_monitor(&mut self, monitor: &CoreMonitorHandle) {
+        let mut shared = self.active_event_loop.shared.lock().unwrap();
+        shared.monitors.retain(|handle| handle != monitor);
+        if self.running {
+            shared
+                .events
+                .push_back(Event::Monitor { event: MonitorEvent::Removed(monitor.clone()) });
+        }
+    }
+
+    /// Queue an event for a window.
+    ///
+    /// [`WindowEvent::Focused`], [`WindowEvent::Moved`] and [`WindowEvent::SurfaceResized`] are
+    /// also applied to the window's [`WindowState`] when they're delivered.
+    pub fn send_window_event(&mut self, window_id: WindowId, event: WindowEvent) {
+        let mut shared = self.active_event_loop.shared.lock().unwrap();
+        shared.events.push_back(Event::Window { window_id, event });
+    }
+
+    /// Queue a change of a window's scale factor.
+    ///
+    /// It's delivered as [`WindowEvent::ScaleFactorChanged`], with the surface size scaled
+    /// accordingly. A [`WindowEvent::SurfaceResized`] follows when the surface size changes.
+    pub fn send_scale_factor_change(&mut self, window_id: WindowId, scale_factor: f64) {
+        let mut shared = self.active_event_loop.shared.lock().unwrap();
+        shared.events.push_back(Event::ScaleFactor { window_id, scale_factor });
+    }
+
+    /// Queue a device event.
+    ///
+    /// It's only delivered when [`ActiveEventLoop::listen_device_events()`] allows it.
+    ///
+    /// [`ActiveEventLoop::listen_device_events()`]: RootActiveEventLoop::listen_device_events
+    pub fn send_device_event(&mut self, device_id: Option<DeviceId>, event: DeviceEvent) {
+        let mut shared = self.active_event_loop.shared.lock().unwrap();
+        shared.events.push_back(Event::Device { device_id, event });
+    }
+
+    /// The windows alive, in creation order.
+    pub fn window_ids(&self) -> Vec<WindowId> {
+        self.active_event_loop.shared.lock().unwrap().windows.keys().copied().collect()
+    }
+
+    /// The state requested by the application for a window.
+    ///
+    /// Returns `None` once the window is dropped.
+    pub fn window_state(&self, window_id: WindowId) -> Option<WindowState> {
+        self.active_event_loop.shared.lock().unwrap().windows.get(&window_id).cloned()
+    }
+
+    /// Change the state of a window, as the windowing system would.
+    ///
+    /// No event is generated, send the matching events to inform the application.
+    ///
+    /// Returns `false` if the window doesn't exist.
+    pub fn update_window(&mut self, window_id: WindowId, f: impl FnOnce(&mut WindowState)) -> bool {
+        let mut shared = self.active_event_loop.shared.lock().unwrap();
+        shared.windows.get_mut(&window_id).map(f).is_some()
+    }
+
+    pub fn window_target(&self) -> &dyn RootActiveEventLoop {
+        &self.active_event_loop
+    }
+
+    /// Wait for events, as much as the control flow and `timeout` allow.
+    fn wait(&mut self, timeout: Option<Duration>) -> StartCause {
+        let target = &self.active_event_loop;
+        let start = target.now.get();
+        let requested_resume = match target.control_flow.get() {
+            ControlFlow::Poll => return StartCause::Poll,
+            ControlFlow::Wait => None,
+            ControlFlow::WaitUntil(requested_resume) => Some(requested_resume),
+        };
+
+        let pending = target.proxy.woken.load(Ordering::Relaxed)
+            || target.shared.lock().unwrap().has_pending_events();
+        if !pending {
+            // Only time can end the wait, skip to the moment it does.
+            let timeout = timeout.and_then(|timeout| start.checked_add(timeout));
+            let end = match (requested_resume, timeout) {
+                (Some(requested_resume), Some(timeout)) => Some(requested_resume.min(timeout)),
+                (requested_resume, timeout) => requested_resume.or(timeout),
+            };
+            target.now.set(end.map_or(start, |end| end.max(start)));
+        }
+
+        match requested_resume {
+            Some(requested_resume) if target.now.get() >= requested_resume => {
+                StartCause::ResumeTimeReached { start, requested_resume }
+            },
+            _ => StartCause::WaitCancelled { start, requested_resume },
+        }
+    }
+
+    fn dispatch<A: ApplicationHandler>(&mut self, app: &mut A) {
+        let target = &self.active_event_loop;
+
+        if target.proxy.woken.swap(false, Ordering::Relaxed) {
+            app.proxy_wake_up(target);
+        }
+
+        // Events queued while handling these are delivered on the next iteration.
+        let events = mem::take(&mut target.shared.lock().unwrap().events);
+        for event in events {
+            match event {
+                Event::Window { window_id, event } => {
+                    target.apply_window_event(window_id, &event);
+                    app.window_event(target, window_id, event);
+                },
+                Event::ScaleFactor { window_id, scale_factor } => {
+                    target.change_scale_factor(app, window_id, scale_factor);
+                },
+                Event::Device { device_id, event } => {
+                    if target.device_event_allowed(&event) {
+                        app.device_event(target, device_id, event);
+                    }
+                },
+                Event::Monitor { event } => app.monitor_event(target, event),
+            }
+        }
+
+        let redraws = mem::take(&mut target.shared.lock().unwrap().redraws);
+        for window_id in redraws {
+            app.window_event(target, window_id, WindowEvent::RedrawRequested);
+        }
+
+        app.about_to_wait(target);
+    }
+}
+
+impl EventLoopExtPumpEvents for EventLoop {
+    fn pump_app_events<A: ApplicationHandler>(
+        &mut self,
+        timeout: Option<Duration>,
+        mut app: A,
+    ) -> PumpStatus {
+        let cause = if self.running {
+            self.wait(timeout)
+        } else {
+            self.running = true;
+            StartCause::Init
+        };
+
+        app.new_events(&self.active_event_loop, cause);
+        if cause == StartCause::Init {
+            app.can_create_surfaces(&self.active_event_loop);
+        }
+
+        self.dispatch(&mut app);
+
+        if self.active_event_loop.exiting() {
+            // Like the other backends, pumping again restarts the loop.
+            self.running = false;
+            self.active_event_loop.exit.set(false);
+            PumpStatus::Exit(0)
+        } else {
+            PumpStatus::Continue
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EventLoopProxy {
+    woken: AtomicBool,
+}
+
+impl EventLoopProxyProvider for EventLoopProxy {
+    fn wake_up(&self) {
+        self.woken.store(true, Ordering::Relaxed);
+    }
+}
+
+/// A custom cursor, keeping the source it was created from.
+#[derive(Debug)]
+pub struct HeadlessCustomCursor(pub CustomCursorSource);
+
+impl CustomCursorProvider for HeadlessCustomCursor {
+    fn is_animated(&self) -> bool {
+        matches!(self.0, CustomCursorSource::Animation(_))
+    }
+}
+
+#[derive(Debug)]
+pub struct ActiveEventLoop {
+    shared: Arc<Mutex<Shared>>,
+    proxy: Arc<EventLoopProxy>,
+    control_flow: Cell<ControlFlow>,
+    exit: Cell<bool>,
+    now: Cell<Instant>,
+    system_theme: Cell<Option<Theme>>,
+    device_events: Cell<DeviceEvents>,
+}
+
+impl ActiveEventLoop {
+    /// The current time of the event loop's clock.
+    ///
+    /// Use it instead of [`Instant::now()`] for [`ControlFlow::WaitUntil`] deadlines.
+    pub fn now(&self) -> Instant {
+        self.now.get()
+    }
+
+    fn apply_window_event(&self, window_id: WindowId, event: &WindowEvent) {
+        let mut shared = self.shared.lock().unwrap();
+        let Some(state) = shared.windows.get_mut(&window_id) else {
+            return;
+        };
+
+        match *event {
+            WindowEvent::Focused(focused) => state.focused = focused,
+            WindowEvent::Moved(position) => state.outer_position = position,
+            WindowEvent::SurfaceResized(size) => state.surface_size = size,
+            _ => (),
+        }
+    }
+
+    fn change_scale_factor<A: ApplicationHandler>(
+        &self,
+        app: &mut A,
+        window_id: WindowId,
+        scale_factor: f64,
+    ) {
+        let (old_size, new_size) = {
+            let mut shared = self.shared.lock().unwrap();
+            let Some(state) = shared.windows.get_mut(&window_id) else {
+                return;
+            };
+
+            let old_size = state.surface_size;
+            let logical_size = old_size.to_logical::<f64>(state.scale_factor);
+            state.scale_factor = scale_factor;
+            (old_size, logical_size.to_physical(scale_factor))
+        };
+
+        let new_size = Arc::new(Mutex::new(new_size));
+        let event = WindowEvent::ScaleFactorChanged {
+            scale_factor,
+            surface_size_writer: SurfaceSizeWriter::new(Arc::downgrade(&new_size)),
+        };
+        app.window_event(self, window_id, event);
+
+        let new_size = *new_size.lock().unwrap();
+        if let Some(state) = self.shared.lock().unwrap().windows.get_mut(&window_id) {
+            state.surface_size = new_size;
+        }
+
+        if new_size != old_size {
+            app.window_event(self, window_id, WindowEvent::SurfaceResized(new_size));
+        }
+    }
+
+    fn device_event_allowed(&self, event: &DeviceEvent) -> bool {
+        if matches!(event, DeviceEvent::Added | DeviceEvent::Removed) {
+            return true;
+        }
+
+        match self.device_events.get() {
+            DeviceEvents::Always => true,
+            DeviceEvents::WhenFocused => {
+                self.shared.lock().unwrap().windows.values().any(|state| state.focused)
+            },
+            DeviceEvents::Never => false,
+        }
+    }
+}
+
+impl RootActiveEventLoop for ActiveEventLoop {
+    fn create_proxy(&self) -> CoreEventLoopProxy {
+        CoreEventLoopProxy::new(self.proxy.clone())
+    }
+
+    fn create_window(
+        &self,
+        window_attributes: WindowAttributes,
+    ) -> Result<Box<dyn CoreWindow>, RequestError> {
+        Ok(Box::new(Window::new(self.shared.clone(), window_attributes)))
+    }
+
+    fn create_custom_cursor(
+        &self,
+        source: CustomCursorSource,
+    ) -> Result<CustomCursor, RequestError> {
+        Ok(CustomCursor(Arc::new(HeadlessCustomCursor(source))))
+    }
+
+    fn available_monitors(&self) -> Box<dyn Iterator<Item = CoreMonitorHandle>> {
+        Box::new(self.shared.lock().unwrap().monitors.clone().into_iter())
+    }
+
+    fn primary_monitor(&self) -> Option<CoreMonitorHandle> {
+        self.shared.lock().unwrap().monitors.first().cloned()
+    }
+
+    fn listen_device_events(&self, allowed: DeviceEvents) {
+        self.device_events.set(allowed);
+    }
+
+    fn system_theme(&self) -> Option<Theme> {
+        self.system_theme.get()
+    }
+
+    fn set_control_flow(&self, control_flow: ControlFlow) {
+        self.control_flow.set(control_flow)
+    }
+
+    fn control_flow(&self) -> ControlFlow {
+        self.control_flow.get()
+    }
+
+    fn exit(&self) {
+        self.exit.set(true);
+    }
+
+    fn exiting(&self) -> bool {
+        self.exit.get()
+    }
+
+    fn owned_display_handle(&self) -> CoreOwnedDisplayHandle {
+        CoreOwnedDisplayHandle::new(Arc::new(OwnedDisplayHandle))
+    }
+
+    fn rwh_06_handle(&self) -> &dyn rwh_06::HasDisplayHandle {
+        self
+    }
+}
+
+impl rwh_06::HasDisplayHandle for ActiveEventLoop {
+    fn display_handle(&self) -> Result<rwh_06::DisplayHandle<'_>, rwh_06::HandleError> {
+        Err(rwh_06::HandleError::NotSupported)
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct OwnedDisplayHandle;
+
+impl rwh_06::HasDisplayHandle for OwnedDisplayHandle {
+    fn display_handle(&self) -> Result<rwh_06::DisplayHandle<'_>, rwh_06::HandleError> {
+        Err(rwh_06::HandleError::NotSupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dpi::PhysicalSize;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct App {
+        window: Option<Box<dyn CoreWindow>>,
+        causes: Vec<StartCause>,
+        events: Vec<WindowEvent>,
+        device_events: usize,
+        wake_ups: usize,
+    }
+
+    impl ApplicationHandler for App {
+        fn new_events(&mut self, _: &dyn RootActiveEventLoop, cause: StartCause) {
+            self.causes.push(cause);
+        }
+
+        fn can_create_surfaces(&mut self, event_loop: &dyn RootActiveEventLoop) {
+            self.window = Some(event_loop.create_window(WindowAttributes::default()).unwrap());
+        }
+
+        fn proxy_wake_up(&mut self, _: &dyn RootActiveEventLoop) {
+            self.wake_ups += 1;
+        }
+
+        fn window_event(&mut self, _: &dyn RootActiveEventLoop, _: WindowId, event: WindowEvent) {
+            self.events.push(event);
+        }
+
+        fn device_event(
+            &mut self,
+            _: &dyn RootActiveEventLoop,
+            _: Option<DeviceId>,
+            _: DeviceEvent,
+        ) {
+            self.device_events += 1;
+        }
+    }
+
+    fn start() -> (EventLoop, App) {
+        let mut event_loop = EventLoop::new();
+        let mut app = App::default();
+        assert_eq!(event_loop.pump_app_events(None, &mut app), PumpStatus::Continue);
+        assert_eq!(app.events, [WindowEvent::RedrawRequested]);
+        app.events.clear();
+        (event_loop, app)
+    }
+
+    #[test]
+    fn wait_until_advances_the_clock() {
+        let (mut event_loop, mut app) = start();
+        let start = event_loop.now();
+        let requested_resume = start + Duration::from_secs(5);
+        event_loop.window_target().set_control_flow(ControlFlow::WaitUntil(requested_resume));
+
+        event_loop.pump_app_events(Some(Duration::from_secs(2)), &mut app);
+        assert_eq!(
+            app.causes.last(),
+            Some(&StartCause::WaitCancelled { start, requested_resume: Some(requested_resume) })
+        );
+        assert_eq!(event_loop.now(), start + Duration::from_secs(2));
+
+        event_loop.pump_app_events(None, &mut app);
+        assert_eq!(
+            app.causes.last(),
+            Some(&StartCause::ResumeTimeReached {
+                start: start + Duration::from_secs(2),
+                requested_resume
+            })
+        );
+        assert_eq!(event_loop.now(), requested_resume);
+    }
+
+    #[test]
+    fn proxy_wakes_up_the_loop() {
+        let (mut event_loop, mut app) = start();
+        event_loop.window_target().create_proxy().wake_up();
+
+        event_loop.pump_app_events(None, &mut app);
+        assert_eq!(app.wake_ups, 1);
+        event_loop.pump_app_events(None, &mut app);
+        assert_eq!(app.wake_ups, 1);
+    }
+
+    #[test]
+    fn scale_factor_change_resizes_the_surface() {
+        let (mut event_loop, mut app) = start();
+        let window_id = app.window.as_ref().unwrap().id();
+        let size = event_loop.window_state(window_id).unwrap().surface_size;
+
+        event_loop.send_scale_factor_change(window_id, 2.);
+        event_loop.pump_app_events(None, &mut app);
+
+        let new_size = PhysicalSize::new(size.width * 2, size.height * 2);
+        assert!(matches!(app.events[0], WindowEvent::ScaleFactorChanged { scale_factor: 2., .. }));
+        assert_eq!(app.events[1], WindowEvent::SurfaceResized(new_size));
+        assert_eq!(app.window.as_ref().unwrap().surface_size(), new_size);
+    }
+
+    #[test]
+    fn device_events_follow_focus() {
+        let (mut event_loop, mut app) = start();
+        let window_id = app.window.as_ref().unwrap().id();
+
+        event_loop.send_device_event(None, DeviceEvent::PointerMotion { delta: (1., 1.) });
+        event_loop.pump_app_events(None, &mut app);
+        assert_eq!(app.device_events, 0);
+
+        event_loop.send_window_event(window_id, WindowEvent::Focused(true));
+        event_loop.send_device_event(None, DeviceEvent::PointerMotion { delta: (1., 1.) });
+        event_loop.pump_app_events(None, &mut app);
+        assert_eq!(app.device_events, 1);
+        assert!(app.window.as_ref().unwrap().has_focus());
+    }
+
+    #[test]
+    fn dropping_a_window_destroys_it() {
+        let (mut event_loop, mut app) = start();
+        let window_id = app.window.take().unwrap().id();
+
+        assert!(event_loop.window_state(window_id).is_none());
+        event_loop.pump_app_events(None, &mut app);
+        assert_eq!(app.events, [WindowEvent::Destroyed]);
+    }
+}
@@ -0,0 +1,59 @@
+//! # Headless backend
+//!
+//! An in-memory backend without any display server, to test [`ApplicationHandler`]s.
+//!
+//! The test drives the [`EventLoop`] with [`pump_app_events()`][pump_app_events], injects events
+//! with its `send_*` methods, moves its clock forward with [`EventLoop::advance_time()`], and
+//! checks what the application requested from its windows with [`EventLoop::window_state()`].
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use winit_core::application::ApplicationHandler;
+//! use winit_core::event::WindowEvent;
+//! use winit_core::event_loop::ActiveEventLoop;
+//! use winit_core::event_loop::pump_events::EventLoopExtPumpEvents;
+//! use winit_core::window::{Window, WindowAttributes, WindowId};
+//! use winit_headless::EventLoop;
+//!
+//! #[derive(Default)]
+//! struct App {
+//!     window: Option<Box<dyn Window>>,
+//! }
+//!
+//! impl ApplicationHandler for App {
+//!     fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+//!         let attributes = WindowAttributes::default().with_title("Unfocused");
+//!         self.window = Some(event_loop.create_window(attributes).unwrap());
+//!     }
+//!
+//!     fn window_event(&mut self, _: &dyn ActiveEventLoop, _: WindowId, event: WindowEvent) {
+//!         if let WindowEvent::Focused(true) = event {
+//!             self.window.as_ref().unwrap().set_title("Focused");
+//!         }
+//!     }
+//! }
+//!
+//! let mut event_loop = EventLoop::new();
+//! let mut app = App::default();
+//! event_loop.pump_app_events(Some(Duration::ZERO), &mut app);
+//!
+//! let window_id = event_loop.window_ids()[0];
+//! event_loop.send_window_event(window_id, WindowEvent::Focused(true));
+//! event_loop.pump_app_events(Some(Duration::ZERO), &mut app);
+//!
+//! let state = event_loop.window_state(window_id).unwrap();
+//! assert!(state.focused);
+//! assert_eq!(state.title, "Focused");
+//! ```
+//!
+//! [`ApplicationHandler`]: winit_core::application::ApplicationHandler
+//! [pump_app_events]: winit_core::event_loop::pump_events::EventLoopExtPumpEvents::pump_app_events
+
+pub use self::event_loop::{ActiveEventLoop, EventLoop, EventLoopProxy, HeadlessCustomCursor};
+pub use self::monitor::{Monitor, MonitorHandle};
+pub use self::window::{Window, WindowState};
+
+pub mod event_loop;
+pub mod monitor;
+pub mod window;
@@ -0,0 +1,76 @@
+use std::borrow::Cow;
+
+use dpi::PhysicalPosition;
+use winit_core::monitor::{MonitorHandleProvider, VideoMode};
+
+/// The description of a monitor, passed to [`EventLoop::add_monitor()`].
+///
+/// [`EventLoop::add_monitor()`]: crate::EventLoop::add_monitor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    pub name: Option<String>,
+    pub position: PhysicalPosition<i32>,
+    pub scale_factor: f64,
+    /// The video mode in use, which is also listed in [`video_modes`][Self::video_modes].
+    pub current_video_mode: Option<VideoMode>,
+    pub video_modes: Vec<VideoMode>,
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Self {
+            name: None,
+            position: PhysicalPosition::new(0, 0),
+            scale_factor: 1.,
+            current_video_mode: None,
+            video_modes: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MonitorHandle {
+    id: u64,
+    monitor: Monitor,
+}
+
+impl MonitorHandle {
+    pub(crate) fn new(id: u64, monitor: Monitor) -> Self {
+        Self { id, monitor }
+    }
+
+    /// The description the monitor was added with.
+    pub fn monitor(&self) -> &Monitor {
+        &self.monitor
+    }
+}
+
+impl MonitorHandleProvider for MonitorHandle {
+    fn id(&self) -> u128 {
+        self.id as u128
+    }
+
+    fn native_id(&self) -> u64 {
+        self.id
+    }
+
+    fn name(&self) -> Option<Cow<'_, str>> {
+        self.monitor.name.as_deref().map(Cow::Borrowed)
+    }
+
+    fn position(&self) -> Option<PhysicalPosition<i32>> {
+        Some(self.monitor.position)
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.monitor.scale_factor
+    }
+
+    fn current_video_mode(&self) -> Option<VideoMode> {
+        self.monitor.current_video_mode
+    }
+
+    fn video_modes(&self) -> Box<dyn Iterator<Item = VideoMode>> {
+        Box::new(self.monitor.video_modes.clone().into_iter())
+    }
+}
@@ -0,0 +1,428 @@
+use std::sync::{Arc, Mutex};
+
+use dpi::{LogicalSize, PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
+use winit_core::cursor::Cursor;
+use winit_core::error::RequestError;
+use winit_core::event::WindowEvent;
+use winit_core::icon::Icon;
+use winit_core::monitor::{Fullscreen, MonitorHandle as CoreMonitorHandle};
+use winit_core::window::{
+    CursorGrabMode, ImeCapabilities, ImeRequest, ImeRequestError, ResizeDirection, Theme,
+    UserAttentionType, Window as CoreWindow, WindowAttributes, WindowButtons, WindowId,
+    WindowLevel,
+};
+
+use crate::event_loop::{Event, Shared};
+
+/// The size of the surface when [`WindowAttributes::surface_size`] isn't set.
+const DEFAULT_SURFACE_SIZE: LogicalSize<f64> = LogicalSize::new(800., 600.);
+
+/// Everything the application requested from a [`Window`].
+///
+/// Read with [`EventLoop::window_state()`], and changed by [`EventLoop::update_window()`] to act
+/// as the windowing system.
+///
+/// [`EventLoop::window_state()`]: crate::EventLoop::window_state
+/// [`EventLoop::update_window()`]: crate::EventLoop::update_window
+#[derive(Debug, Clone)]
+pub struct WindowState {
+    pub title: String,
+    pub scale_factor: f64,
+    pub outer_position: PhysicalPosition<i32>,
+    pub surface_size: PhysicalSize<u32>,
+    pub min_surface_size: Option<PhysicalSize<u32>>,
+    pub max_surface_size: Option<PhysicalSize<u32>>,
+    pub surface_resize_increments: Option<PhysicalSize<u32>>,
+    pub visible: bool,
+    pub resizable: bool,
+    pub enabled_buttons: WindowButtons,
+    pub minimized: bool,
+    pub maximized: bool,
+    pub fullscreen: Option<Fullscreen>,
+    pub decorated: bool,
+    pub transparent: bool,
+    pub blur: bool,
+    pub window_level: WindowLevel,
+    pub window_icon: Option<Icon>,
+    pub theme: Option<Theme>,
+    pub content_protected: bool,
+    /// Whether the window has the keyboard focus, as last reported with
+    /// [`WindowEvent::Focused`].
+    pub focused: bool,
+    /// Whether [`Window::focus_window()`][CoreWindow::focus_window] was called.
+    pub focus_requested: bool,
+    pub user_attention: Option<UserAttentionType>,
+    pub cursor: Cursor,
+    pub cursor_visible: bool,
+    pub cursor_grab: CursorGrabMode,
+    /// The position the cursor was last warped to.
+    pub cursor_position: Option<PhysicalPosition<f64>>,
+    pub cursor_hittest: bool,
+    /// The capabilities of the IME, while it's enabled.
+    pub ime_capabilities: Option<ImeCapabilities>,
+    /// The IME requests that were accepted, in order.
+    pub ime_requests: Vec<ImeRequest>,
+    /// The number of times [`Window::pre_present_notify()`][CoreWindow::pre_present_notify] was
+    /// called.
+    pub presents: usize,
+}
+
+impl WindowState {
+    fn new(attributes: &WindowAttributes, scale_factor: f64) -> Self {
+        let surface_size = attributes
+            .surface_size
+            .unwrap_or(DEFAULT_SURFACE_SIZE.into())
+            .to_physical(scale_factor);
+        let outer_position = attributes
+            .position
+            .map(|position| position.to_physical(scale_factor))
+            .unwrap_or_default();
+
+        Self {
+            title: attributes.title.clone(),
+            scale_factor,
+            outer_position,
+            surface_size,
+            min_surface_size: attributes
+                .min_surface_size
+                .map(|size| size.to_physical(scale_factor)),
+            max_surface_size: attributes
+                .max_surface_size
+                .map(|size| size.to_physical(scale_factor)),
+            surface_resize_increments: attributes
+                .surface_resize_increments
+                .map(|size| size.to_physical(scale_factor)),
+            visible: attributes.visible,
+            resizable: attributes.resizable,
+            enabled_buttons: attributes.enabled_buttons,
+            minimized: false,
+            maximized: attributes.maximized,
+            fullscreen: attributes.fullscreen.clone(),
+            decorated: attributes.decorations,
+            transparent: attributes.transparent,
+            blur: attributes.blur,
+            window_level: attributes.window_level,
+            window_icon: attributes.window_icon.clone(),
+            theme: attributes.preferred_theme,
+            content_protected: attributes.content_protected,
+            focused: false,
+            focus_requested: attributes.active,
+            user_attention: None,
+            cursor: attributes.cursor.clone(),
+            cursor_visible: true,
+            cursor_grab: CursorGrabMode::None,
+            cursor_position: None,
+            cursor_hittest: true,
+            ime_capabilities: None,
+            ime_requests: Vec::new(),
+            presents: 0,
+        }
+    }
+}
+
+/// A window living in memory.
+///
+/// The requests are applied right away to its [`WindowState`], without generating any event.
+#[derive(Debug)]
+pub struct Window {
+    id: WindowId,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Window {
+    pub(crate) fn new(shared: Arc<Mutex<Shared>>, attributes: WindowAttributes) -> Self {
+        let mut locked = shared.lock().unwrap();
+        let id = locked.next_window_id();
+        let scale_factor = locked.monitors.first().map_or(1., |monitor| monitor.scale_factor());
+        locked.windows.insert(id, WindowState::new(&attributes, scale_factor));
+
+        // Like on most platforms, the window is drawn once it's created.
+        locked.request_redraw(id);
+        drop(locked);
+
+        Self { id, shared }
+    }
+
+    fn with_state<R>(&self, f: impl FnOnce(&mut WindowState) -> R) -> R {
+        let mut shared = self.shared.lock().unwrap();
+        f(shared.windows.get_mut(&self.id).expect("the state lives as long as the window"))
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.windows.remove(&self.id);
+        shared.redraws.retain(|&window_id| window_id != self.id);
+        shared
+            .events
+            .push_back(Event::Window { window_id: self.id, event: WindowEvent::Destroyed });
+    }
+}
+
+impl CoreWindow for Window {
+    fn id(&self) -> WindowId {
+        self.id
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.with_state(|state| state.scale_factor)
+    }
+
+    fn request_redraw(&self) {
+        self.shared.lock().unwrap().request_redraw(self.id);
+    }
+
+    fn pre_present_notify(&self) {
+        self.with_state(|state| state.presents += 1);
+    }
+
+    fn reset_dead_keys(&self) {}
+
+    fn surface_position(&self) -> PhysicalPosition<i32> {
+        // There are no decorations.
+        PhysicalPosition::new(0, 0)
+    }
+
+    fn outer_position(&self) -> Result<PhysicalPosition<i32>, RequestError> {
+        Ok(self.with_state(|state| state.outer_position))
+    }
+
+    fn set_outer_position(&self, position: Position) {
+        self.with_state(|state| state.outer_position = position.to_physical(state.scale_factor));
+    }
+
+    fn surface_size(&self) -> PhysicalSize<u32> {
+        self.with_state(|state| state.surface_size)
+    }
+
+    fn request_surface_size(&self, size: Size) -> Option<PhysicalSize<u32>> {
+        self.with_state(|state| {
+            state.surface_size = size.to_physical(state.scale_factor);
+            Some(state.surface_size)
+        })
+    }
+
+    fn outer_size(&self) -> PhysicalSize<u32> {
+        self.surface_size()
+    }
+
+    fn safe_area(&self) -> PhysicalInsets<u32> {
+        PhysicalInsets::new(0, 0, 0, 0)
+    }
+
+    fn set_min_surface_size(&self, min_size: Option<Size>) {
+        self.with_state(|state| {
+            state.min_surface_size = min_size.map(|size| size.to_physical(state.scale_factor))
+        });
+    }
+
+    fn set_max_surface_size(&self, max_size: Option<Size>) {
+        self.with_state(|state| {
+            state.max_surface_size = max_size.map(|size| size.to_physical(state.scale_factor))
+        });
+    }
+
+    fn surface_resize_increments(&self) -> Option<PhysicalSize<u32>> {
+        self.with_state(|state| state.surface_resize_increments)
+    }
+
+    fn set_surface_resize_increments(&self, increments: Option<Size>) {
+        self.with_state(|state| {
+            state.surface_resize_increments =
+                increments.map(|size| size.to_physical(state.scale_factor))
+        });
+    }
+
+    fn set_title(&self, title: &str) {
+        self.with_state(|state| state.title = title.to_owned());
+    }
+
+    fn set_transparent(&self, transparent: bool) {
+        self.with_state(|state| state.transparent = transparent);
+    }
+
+    fn set_blur(&self, blur: bool) {
+        self.with_state(|state| state.blur = blur);
+    }
+
+    fn set_visible(&self, visible: bool) {
+        self.with_state(|state| state.visible = visible);
+    }
+
+    fn is_visible(&self) -> Option<bool> {
+        Some(self.with_state(|state| state.visible))
+    }
+
+    fn set_resizable(&self, resizable: bool) {
+        self.with_state(|state| state.resizable = resizable);
+    }
+
+    fn is_resizable(&self) -> bool {
+        self.with_state(|state| state.resizable)
+    }
+
+    fn set_enabled_buttons(&self, buttons: WindowButtons) {
+        self.with_state(|state| state.enabled_buttons = buttons);
+    }
+
+    fn enabled_buttons(&self) -> WindowButtons {
+        self.with_state(|state| state.enabled_buttons)
+    }
+
+    fn set_minimized(&self, minimized: bool) {
+        self.with_state(|state| state.minimized = minimized);
+    }
+
+    fn is_minimized(&self) -> Option<bool> {
+        Some(self.with_state(|state| state.minimized))
+    }
+
+    fn set_maximized(&self, maximized: bool) {
+        self.with_state(|state| state.maximized = maximized);
+    }
+
+    fn is_maximized(&self) -> bool {
+        self.with_state(|state| state.maximized)
+    }
+
+    fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.with_state(|state| state.fullscreen = fullscreen);
+    }
+
+    fn fullscreen(&self) -> Option<Fullscreen> {
+        self.with_state(|state| state.fullscreen.clone())
+    }
+
+    fn set_decorations(&self, decorations: bool) {
+        self.with_state(|state| state.decorated = decorations);
+    }
+
+    fn is_decorated(&self) -> bool {
+        self.with_state(|state| state.decorated)
+    }
+
+    fn set_window_level(&self, level: WindowLevel) {
+        self.with_state(|state| state.window_level = level);
+    }
+
+    fn set_window_icon(&self, window_icon: Option<Icon>) {
+        self.with_state(|state| state.window_icon = window_icon);
+    }
+
+    fn request_ime_update(&self, request: ImeRequest) -> Result<(), ImeRequestError> {
+        self.with_state(|state| {
+            match (&request, state.ime_capabilities) {
+                (ImeRequest::Enable(_), Some(_)) => return Err(ImeRequestError::AlreadyEnabled),
+                (ImeRequest::Enable(enable), None) => {
+                    state.ime_capabilities = Some(*enable.capabilities())
+                },
+                (ImeRequest::Update(_), None) => return Err(ImeRequestError::NotEnabled),
+                (ImeRequest::Update(_), Some(_)) => (),
+                (ImeRequest::Disable, _) => state.ime_capabilities = None,
+            }
+
+            state.ime_requests.push(request);
+            Ok(())
+        })
+    }
+
+    fn ime_capabilities(&self) -> Option<ImeCapabilities> {
+        self.with_state(|state| state.ime_capabilities)
+    }
+
+    fn focus_window(&self) {
+        self.with_state(|state| state.focus_requested = true);
+    }
+
+    fn has_focus(&self) -> bool {
+        self.with_state(|state| state.focused)
+    }
+
+    fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
+        self.with_state(|state| state.user_attention = request_type);
+    }
+
+    fn set_theme(&self, theme: Option<Theme>) {
+        self.with_state(|state| state.theme = theme);
+    }
+
+    fn theme(&self) -> Option<Theme> {
+        self.with_state(|state| state.theme)
+    }
+
+    fn set_content_protected(&self, protected: bool) {
+        self.with_state(|state| state.content_protected = protected);
+    }
+
+    fn title(&self) -> String {
+        self.with_state(|state| state.title.clone())
+    }
+
+    fn set_cursor(&self, cursor: Cursor) {
+        self.with_state(|state| state.cursor = cursor);
+    }
+
+    fn set_cursor_position(&self, position: Position) -> Result<(), RequestError> {
+        self.with_state(|state| {
+            state.cursor_position = Some(position.to_physical(state.scale_factor))
+        });
+        Ok(())
+    }
+
+    fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), RequestError> {
+        self.with_state(|state| state.cursor_grab = mode);
+        Ok(())
+    }
+
+    fn set_cursor_visible(&self, visible: bool) {
+        self.with_state(|state| state.cursor_visible = visible);
+    }
+
+    fn drag_window(&self) -> Result<(), RequestError> {
+        Ok(())
+    }
+
+    fn drag_resize_window(&self, _direction: ResizeDirection) -> Result<(), RequestError> {
+        Ok(())
+    }
+
+    fn show_window_menu(&self, _position: Position) {}
+
+    fn set_cursor_hittest(&self, hittest: bool) -> Result<(), RequestError> {
+        self.with_state(|state| state.cursor_hittest = hittest);
+        Ok(())
+    }
+
+    fn current_monitor(&self) -> Option<CoreMonitorHandle> {
+        self.primary_monitor()
+    }
+
+    fn available_monitors(&self) -> Box<dyn Iterator<Item = CoreMonitorHandle>> {
+        Box::new(self.shared.lock().unwrap().monitors.clone().into_iter())
+    }
+
+    fn primary_monitor(&self) -> Option<CoreMonitorHandle> {
+        self.shared.lock().unwrap().monitors.first().cloned()
+    }
+
+    fn rwh_06_display_handle(&self) -> &dyn rwh_06::HasDisplayHandle {
+        self
+    }
+
+    fn rwh_06_window_handle(&self) -> &dyn rwh_06::HasWindowHandle {
+        self
+    }
+}
+
+impl rwh_06::HasDisplayHandle for Window {
+    fn display_handle(&self) -> Result<rwh_06::DisplayHandle<'_>, rwh_06::HandleError> {
+        Err(rwh_06::HandleError::NotSupported)
+    }
+}
+
+impl rwh_06::HasWindowHandle for Window {
+    fn window_handle(&self) -> Result<rwh_06::WindowHandle<'_>, rwh_06::HandleError> {
+        Err(rwh_06::HandleError::NotSupported)
+    }
+}
@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use winit_core::application::ApplicationHandler;
+use winit_core::event::{ElementState, WindowEvent};
+use winit_core::event_loop::ActiveEventLoop;
+use winit_core::event_loop::pump_events::EventLoopExtPumpEvents;
+use winit_core::record::{
+    MonitorChange, Record, Recorder, ReplaySpeed, Replayer, Trace, TraceEntry, WindowRecorder,
+    WindowRequest,
+};
+use winit_core::window::{Window, WindowAttributes, WindowId};
+use winit_headless::{EventLoop, Monitor};
+
+struct App {
+    recorder: WindowRecorder,
+    window: Option<Box<dyn Window>>,
+    clicks: usize,
+}
+
+impl App {
+    fn new(recorder: WindowRecorder) -> Self {
+        Self { recorder, window: None, clicks: 0 }
+    }
+}
+
+impl ApplicationHandler for App {
+    fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+        let window = event_loop.create_window(WindowAttributes::default()).unwrap();
+        self.window = Some(self.recorder.wrap(window));
+    }
+
+    fn window_event(&mut self, _: &dyn ActiveEventLoop, _: WindowId, event: WindowEvent) {
+        let window = self.window.as_ref().unwrap();
+        match event {
+            WindowEvent::PointerButton { state: ElementState::Pressed, .. } => {
+                self.clicks += 1;
+                window.set_title(&format!("{} clicks", self.clicks));
+            },
+            WindowEvent::Focused(true) => {
+                // Unsupported by the headless backend, only the request is recorded.
+                let _ = window.set_keyboard_grab(true);
+            },
+            _ => (),
+        }
+    }
+}
+
+fn click() -> WindowEvent {
+    WindowEvent::PointerButton {
+        device_id: None,
+        state: ElementState::Pressed,
+        position: Default::default(),
+        primary: true,
+        button: winit_core::event::MouseButton::Left.into(),
+    }
+}
+
+#[test]
+fn replay_reproduces_requests() {
+    let mut event_loop = EventLoop::new();
+    let window_recorder = WindowRecorder::new();
+    let mut recorder = Recorder::new(App::new(window_recorder.clone()), window_recorder);
+    event_loop.pump_app_events(Some(Duration::ZERO), &mut recorder);
+
+    let window_id = event_loop.window_ids()[0];
+    event_loop.send_window_event(window_id, click());
+    event_loop.send_window_event(window_id, WindowEvent::Focused(true));
+    event_loop.send_window_event(window_id, WindowEvent::KeyboardGrabbed(true));
+    event_loop.send_window_event(window_id, click());
+    event_loop.pump_app_events(Some(Duration::ZERO), &mut recorder);
+
+    let trace = recorder.trace();
+    assert!(
+        matches!(trace.entries[0].record, Record::WindowCreated { window_id: id } if id == window_id)
+    );
+    assert!(trace.entries.iter().any(|entry| entry.record
+        == Record::WindowEvent { window_id, event: WindowEvent::KeyboardGrabbed(true) }));
+    assert_eq!(trace.window_requests(), [
+        (0, WindowRequest::SetTitle("1 clicks".into())),
+        (0, WindowRequest::SetKeyboardGrab(true)),
+        (0, WindowRequest::SetTitle("2 clicks".into())),
+    ]);
+
+    let mut event_loop = EventLoop::new();
+    let window_recorder = WindowRecorder::new();
+    let app = App::new(window_recorder.clone());
+    let mut replayer =
+        Replayer::new(app, window_recorder, trace.clone(), ReplaySpeed::AsFastAsPossible);
+    event_loop.pump_app_events(Some(Duration::ZERO), &mut replayer);
+
+    assert!(replayer.is_done());
+    assert_eq!(replayer.app().clicks, 2);
+    assert_eq!(replayer.trace().window_requests(), trace.window_requests());
+    let window_id = event_loop.window_ids()[0];
+    assert_eq!(event_loop.window_state(window_id).unwrap().title, "2 clicks");
+}
+
+#[test]
+fn trace_round_trips_through_serde() {
+    let mut event_loop = EventLoop::new();
+    let window_recorder = WindowRecorder::new();
+    let mut recorder = Recorder::new(App::new(window_recorder.clone()), window_recorder);
+    event_loop.pump_app_events(Some(Duration::ZERO), &mut recorder);
+
+    let window_id = event_loop.window_ids()[0];
+    let monitor = event_loop.add_monitor(Monitor::default());
+    event_loop.send_window_event(window_id, click());
+    event_loop.pump_app_events(Some(Duration::ZERO), &mut recorder);
+
+    let trace = recorder.trace();
+    assert!(trace.entries.iter().any(|entry| entry.record
+        == Record::MonitorEvent { change: MonitorChange::Added, monitor_id: monitor.id() }));
+
+    let json = serde_json::to_string(&trace).unwrap();
+    let deserialized: Trace = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, trace);
+
+    let mut event_loop = EventLoop::new();
+    let window_recorder = WindowRecorder::new();
+    let app = App::new(window_recorder.clone());
+    let mut replayer =
+        Replayer::new(app, window_recorder, deserialized, ReplaySpeed::AsFastAsPossible);
+    event_loop.pump_app_events(Some(Duration::ZERO), &mut replayer);
+
+    assert!(replayer.is_done());
+    assert_eq!(replayer.app().clicks, 1);
+    assert_eq!(replayer.trace().window_requests(), trace.window_requests());
+}
+
+#[test]
+fn original_speed_keeps_event_spacing() {
+    let window_id = WindowId::from_raw(1);
+    let entry = |millis, record| TraceEntry { time: Duration::from_millis(millis), record };
+    let trace = Trace {
+        entries: vec![
+            entry(0, Record::WindowCreated { window_id }),
+            entry(100, Record::WindowEvent { window_id, event: click() }),
+            entry(250, Record::WindowEvent { window_id, event: click() }),
+        ],
+    };
+
+    let mut event_loop = EventLoop::new();
+    let window_recorder = WindowRecorder::new();
+    let app = App::new(window_recorder.clone());
+    let mut replayer = Replayer::new(app, window_recorder, trace, ReplaySpeed::Original);
+    event_loop.pump_app_events(Some(Duration::ZERO), &mut replayer);
+    assert_eq!(replayer.app().clicks, 0);
+
+    // The replayer waits on the event loop's clock, which jumps to each event.
+    let start = event_loop.now();
+    event_loop.pump_app_events(None, &mut replayer);
+    assert_eq!(replayer.app().clicks, 1);
+    let first = event_loop.now();
+    assert!(first - start >= Duration::from_millis(100));
+
+    event_loop.pump_app_events(None, &mut replayer);
+    assert_eq!(replayer.app().clicks, 2);
+    assert_eq!(event_loop.now() - first, Duration::from_millis(150));
+    assert!(replayer.is_done());
+}
+
+#[test]
+fn owner_window_recorded_by_creation_order() {
+    struct Dialogs {
+        recorder: WindowRecorder,
+        windows: Vec<Box<dyn Window>>,
+    }
+
+    impl ApplicationHandler for Dialogs {
+        fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+            for _ in 0..2 {
+                let window = event_loop.create_window(WindowAttributes::default()).unwrap();
+                self.windows.push(self.recorder.wrap(window));
+            }
+
+            // The owner is identified through the wrapper.
+            let _ = self.windows[1].set_owner_window(Some(self.windows[0].id()), true);
+            let _ = self.windows[1].set_owner_window(None, false);
+        }
+
+        fn window_event(&mut self, _: &dyn ActiveEventLoop, _: WindowId, _: WindowEvent) {}
+    }
+
+    let mut event_loop = EventLoop::new();
+    let window_recorder = WindowRecorder::new();
+    let app = Dialogs { recorder: window_recorder.clone(), windows: Vec::new() };
+    let mut recorder = Recorder::new(app, window_recorder);
+    event_loop.pump_app_events(Some(Duration::ZERO), &mut recorder);
+
+    assert_eq!(recorder.trace().window_requests(), [
+        (1, WindowRequest::SetOwnerWindow { owner: Some(0), modal: true }),
+        (1, WindowRequest::SetOwnerWindow { owner: None, modal: false }),
+    ]);
+}
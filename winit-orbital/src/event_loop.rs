@@ -427,6 +427,8 @@ impl EventLoop {
                     device_id: None,
                     delta: event::MouseScrollDelta::LineDelta(x as f32, y as f32),
                     phase: event::TouchPhase::Moved,
+                    source: event::ScrollSource::Unknown,
+                    value120: None,
                 });
             },
             EventOption::Quit(QuitEvent {}) => {
@@ -0,0 +1,809 @@
+//! Handling of `wl_data_device`, which backs the clipboard and drag and drop.
+//!
+//! The transfer helpers are shared with the primary selection.
+
+use std::io::{ErrorKind, Read, Write};
+use std::path::PathBuf;
+
+use calloop::PostAction;
+use dpi::{LogicalPosition, PhysicalPosition};
+use sctk::data_device_manager::data_device::{DataDevice, DataDeviceData, DataDeviceHandler};
+use sctk::data_device_manager::data_offer::{DataOfferHandler, DragOffer, SelectionOffer};
+use sctk::data_device_manager::data_source::{CopyPasteSource, DataSourceHandler, DragSource};
+use sctk::data_device_manager::{ReadPipe, WritePipe};
+use sctk::reexports::client::backend::ObjectId;
+use sctk::reexports::client::protocol::wl_data_device::WlDataDevice;
+use sctk::reexports::client::protocol::wl_data_device_manager::DndAction;
+use sctk::reexports::client::protocol::wl_data_source::WlDataSource;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Connection, Proxy, QueueHandle};
+use tracing::warn;
+use winit_core::clipboard::{
+    Clipboard, ClipboardData, ClipboardError, ClipboardEvent, TEXT_MIME_TYPE, URI_LIST_MIME_TYPE,
+    is_text_mime_type,
+};
+use winit_core::drag::{DragAction, DragActions, DragResult};
+use winit_core::error::{NotSupportedError, RequestError};
+use winit_core::event::WindowEvent;
+use winit_core::event_loop::AsyncRequestSerial;
+use winit_core::window::WindowId;
+
+use crate::event_loop::ActiveEventLoop;
+use crate::make_wid;
+use crate::state::WinitState;
+
+/// MIME types under which text is offered, in order of preference when reading.
+const TEXT_MIME_TYPES: [&str; 6] =
+    [TEXT_MIME_TYPE, "text/plain;charset=UTF-8", "UTF8_STRING", "text/plain", "STRING", "TEXT"];
+
+/// The clipboard contents we're offering.
+#[derive(Debug, Default)]
+pub struct ClipboardState {
+    /// The source set as the selection, while we own it.
+    source: Option<CopyPasteSource>,
+
+    /// The contents offered through `source`.
+    contents: Vec<ClipboardData>,
+}
+
+/// A drag and drop request from a window, handled by the event loop.
+#[derive(Debug)]
+pub enum DragRequest {
+    /// Start a drag with `Window::start_drag`.
+    Start { serial: AsyncRequestSerial, contents: Vec<ClipboardData>, actions: DragActions },
+    /// Accept the drop with `Window::accept_drag`, or reject it when `None`.
+    Accept { accepted: Option<(String, DragAction)> },
+    /// Read the dragged data with `Window::request_drag_data`.
+    Data { serial: AsyncRequestSerial, mime_type: String },
+}
+
+/// A drag out of one of our windows.
+#[derive(Debug)]
+pub struct DragSourceState {
+    source: DragSource,
+    window_id: WindowId,
+    serial: AsyncRequestSerial,
+
+    /// The contents offered through `source`.
+    contents: Vec<ClipboardData>,
+
+    /// The action selected by the compositor.
+    action: Option<DragAction>,
+}
+
+/// A drag over one of our windows.
+#[derive(Debug)]
+pub struct DropState {
+    /// The window the drag is over.
+    window_id: WindowId,
+
+    /// The data device the drag comes from.
+    data_device: WlDataDevice,
+
+    /// The serial of the `enter` event.
+    serial: u32,
+
+    position: PhysicalPosition<f64>,
+
+    /// The dragged paths, once read.
+    paths: Vec<PathBuf>,
+
+    /// Whether the paths are being read, `DragEntered` is sent once they are.
+    reading_paths: bool,
+
+    /// The offered MIME type and action we accept the drop with.
+    accepted: Option<(String, DragAction)>,
+
+    /// Whether `DragEntered` was sent.
+    entered: bool,
+
+    /// Whether the data was dropped.
+    dropped: bool,
+
+    /// Whether `DragDropped` was sent.
+    drop_sent: bool,
+
+    /// The number of data requests in progress, the drop is finished once there are none left.
+    reads: usize,
+}
+
+impl DropState {
+    fn offer(&self) -> Option<DragOffer> {
+        self.data_device.data::<DataDeviceData>()?.drag_offer()
+    }
+}
+
+/// Tell the compositor whether `drop` is accepted.
+fn update_drop_acceptance(drop: &DropState) {
+    let Some(offer) = drop.offer() else { return };
+    match &drop.accepted {
+        Some((mime_type, action)) => {
+            // There's no link action on Wayland.
+            let action = match action {
+                DragAction::Copy => DndAction::Copy,
+                DragAction::Move => DndAction::Move,
+                DragAction::Link => DndAction::None,
+            };
+            offer.accept_mime_type(drop.serial, Some(mime_type.clone()));
+            offer.set_actions(action, action);
+        },
+        None => {
+            offer.accept_mime_type(drop.serial, None);
+            offer.set_actions(DndAction::None, DndAction::None);
+        },
+    }
+}
+
+fn drag_actions(actions: DndAction) -> DragActions {
+    let mut drag_actions = DragActions::empty();
+    if actions.contains(DndAction::Copy) {
+        drag_actions |= DragActions::COPY;
+    }
+    if actions.contains(DndAction::Move) {
+        drag_actions |= DragActions::MOVE;
+    }
+    drag_actions
+}
+
+fn dnd_actions(actions: DragActions) -> DndAction {
+    let mut dnd_actions = DndAction::None;
+    if actions.contains(DragActions::COPY) {
+        dnd_actions |= DndAction::Copy;
+    }
+    if actions.contains(DragActions::MOVE) {
+        dnd_actions |= DndAction::Move;
+    }
+    dnd_actions
+}
+
+/// The MIME types to offer `contents` under.
+pub(crate) fn source_mime_types(contents: &[ClipboardData]) -> Vec<&str> {
+    let mut mime_types: Vec<&str> = Vec::new();
+    for data in contents {
+        if data.is_text() {
+            mime_types.extend(TEXT_MIME_TYPES);
+        }
+        mime_types.push(&data.mime_type);
+    }
+    mime_types.sort_unstable();
+    mime_types.dedup();
+    mime_types
+}
+
+/// The offered MIME type to receive `mime_type` as.
+pub(crate) fn find_offered_mime_type(offered: &[String], mime_type: &str) -> Option<String> {
+    if is_text_mime_type(mime_type) {
+        TEXT_MIME_TYPES
+            .into_iter()
+            .find(|text| offered.iter().any(|offered| offered == text))
+            .map(str::to_owned)
+    } else {
+        offered.iter().find(|&offered| offered == mime_type).cloned()
+    }
+}
+
+impl WinitState {
+    /// Read `pipe` to its end and deliver the result as [`ClipboardEvent::Data`].
+    pub(crate) fn receive_clipboard_data(
+        &self,
+        pipe: ReadPipe,
+        serial: AsyncRequestSerial,
+        mime_type: String,
+    ) -> Result<(), RequestError> {
+        self.receive_data(pipe, mime_type, move |state, result| {
+            state.events_sink.push_clipboard_event(ClipboardEvent::Data { serial, result });
+        })
+    }
+
+    /// Read `pipe` to its end and pass the data, as `mime_type`, to `callback`.
+    fn receive_data<F>(
+        &self,
+        pipe: ReadPipe,
+        mime_type: String,
+        mut callback: F,
+    ) -> Result<(), RequestError>
+    where
+        F: FnMut(&mut WinitState, Result<ClipboardData, ClipboardError>) + 'static,
+    {
+        let mut data = Vec::new();
+        self.loop_handle
+            .insert_source(pipe, move |_, file, state| {
+                let mut buf = [0u8; 4096];
+                let result = match (&**file).read(&mut buf) {
+                    Ok(0) => Ok(ClipboardData::new(mime_type.clone(), std::mem::take(&mut data))),
+                    Ok(len) => {
+                        data.extend_from_slice(&buf[..len]);
+                        return PostAction::Continue;
+                    },
+                    Err(err)
+                        if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::Interrupted) =>
+                    {
+                        return PostAction::Continue;
+                    },
+                    Err(err) => Err(ClipboardError::TransferFailed(err.to_string())),
+                };
+
+                callback(state, result);
+                state.dispatched_events = true;
+                PostAction::Remove
+            })
+            .map_err(|err| os_error!(err.error))?;
+
+        Ok(())
+    }
+
+    /// Write the entry of `contents` matching `mime_type` to `pipe`.
+    pub(crate) fn send_clipboard_data(
+        &self,
+        contents: &[ClipboardData],
+        mime_type: &str,
+        pipe: WritePipe,
+    ) {
+        let data = match contents.iter().find(|data| data.mime_type == mime_type) {
+            Some(data) => data,
+            None if is_text_mime_type(mime_type) => {
+                match contents.iter().find(|data| data.is_text()) {
+                    Some(data) => data,
+                    None => return,
+                }
+            },
+            None => return,
+        };
+        let data = data.data.clone();
+
+        // The reader may be ourselves, so never block on the pipe.
+        if let Err(err) = rustix::io::ioctl_fionbio(&pipe, true) {
+            warn!("Failed to make clipboard pipe non-blocking: {err}");
+            return;
+        }
+
+        let mut written = 0;
+        let result = self.loop_handle.insert_source(pipe, move |_, file, _| {
+            match (&**file).write(&data[written..]) {
+                Ok(len) => written += len,
+                Err(err)
+                    if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::Interrupted) => {},
+                Err(_) => return PostAction::Remove,
+            }
+
+            if written == data.len() { PostAction::Remove } else { PostAction::Continue }
+        });
+        if let Err(err) = result {
+            warn!("Failed to send clipboard data: {}", err.error);
+        }
+    }
+
+    pub(crate) fn drag_request(
+        &mut self,
+        queue_handle: &QueueHandle<Self>,
+        window_id: WindowId,
+        request: DragRequest,
+    ) {
+        match request {
+            DragRequest::Start { serial, contents, actions } => {
+                self.start_drag(queue_handle, window_id, serial, contents, actions)
+            },
+            DragRequest::Accept { accepted } => {
+                let Some(drop) = self.drops.values_mut().find(|drop| drop.window_id == window_id)
+                else {
+                    return;
+                };
+                if drop.dropped {
+                    return;
+                }
+
+                let offer = drop.offer();
+                drop.accepted = accepted.and_then(|(mime_type, action)| {
+                    let offered = offer
+                        .as_ref()?
+                        .with_mime_types(|offered| find_offered_mime_type(offered, &mime_type));
+                    if offered.is_none() {
+                        warn!("Rejecting drop as `{mime_type}`, which isn't offered");
+                    }
+                    Some((offered?, action))
+                });
+                update_drop_acceptance(drop);
+            },
+            DragRequest::Data { serial, mime_type } => {
+                if let Err(err) = self.request_drop_data(window_id, serial, mime_type.clone()) {
+                    let event = WindowEvent::DragData {
+                        serial,
+                        result: Err(ClipboardError::TransferFailed(err.to_string())),
+                    };
+                    self.events_sink.push_window_event(event, window_id);
+                }
+            },
+        }
+    }
+
+    /// Start a drag from `window_id`, reporting a failure as a cancelled drag.
+    fn start_drag(
+        &mut self,
+        queue_handle: &QueueHandle<Self>,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        contents: Vec<ClipboardData>,
+        actions: DragActions,
+    ) {
+        // Starting a drag cancels the previous one.
+        if let Some(drag) = self.drag_source.take() {
+            self.finish_drag(drag, DragResult::Cancelled);
+        }
+
+        match self.create_drag_source(queue_handle, window_id, &contents, actions) {
+            Some(source) => {
+                self.drag_source =
+                    Some(DragSourceState { source, window_id, serial, contents, action: None });
+            },
+            None => {
+                let event =
+                    WindowEvent::DragSourceFinished { serial, result: DragResult::Cancelled };
+                self.events_sink.push_window_event(event, window_id);
+            },
+        }
+    }
+
+    fn create_drag_source(
+        &self,
+        queue_handle: &QueueHandle<Self>,
+        window_id: WindowId,
+        contents: &[ClipboardData],
+        actions: DragActions,
+    ) -> Option<DragSource> {
+        let manager = self.data_device_manager_state.as_ref()?;
+        let windows = self.windows.borrow();
+        let window = windows.get(&window_id)?.lock().unwrap();
+        let (seat, serial) = window.latest_pointer_button()?;
+        let data_device = self.seats.get(&seat.id())?.data_device.as_ref()?;
+
+        let source = manager.create_drag_and_drop_source(
+            queue_handle,
+            source_mime_types(contents),
+            dnd_actions(actions),
+        );
+        source.start_drag(data_device, window.window.wl_surface(), None, serial);
+
+        Some(source)
+    }
+
+    fn finish_drag(&mut self, drag: DragSourceState, result: DragResult) {
+        let event = WindowEvent::DragSourceFinished { serial: drag.serial, result };
+        self.events_sink.push_window_event(event, drag.window_id);
+    }
+
+    /// Read the data dragged over `window_id` as `mime_type`, delivered as
+    /// [`WindowEvent::DragData`].
+    fn request_drop_data(
+        &mut self,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        mime_type: String,
+    ) -> Result<(), RequestError> {
+        let drop = self.drops.values_mut().find(|drop| drop.window_id == window_id);
+        let offer = drop.as_ref().and_then(|drop| drop.offer());
+        let (Some(drop), Some(offer)) = (drop, offer) else {
+            let event = WindowEvent::DragData { serial, result: Err(ClipboardError::Empty) };
+            self.events_sink.push_window_event(event, window_id);
+            return Ok(());
+        };
+
+        let offered = offer.with_mime_types(|offered| find_offered_mime_type(offered, &mime_type));
+        let Some(offered) = offered else {
+            let result = Err(ClipboardError::MimeTypeUnavailable);
+            self.events_sink.push_window_event(WindowEvent::DragData { serial, result }, window_id);
+            return Ok(());
+        };
+
+        let pipe = offer.receive(offered).map_err(|err| os_error!(err))?;
+        drop.reads += 1;
+        let (data_device, enter_serial) = (drop.data_device.id(), drop.serial);
+        self.receive_data(pipe, mime_type, move |state, result| {
+            if let Some(drop) = state.drops.get_mut(&data_device) {
+                if drop.serial == enter_serial {
+                    drop.reads -= 1;
+                }
+            }
+            state
+                .events_sink
+                .push_window_event(WindowEvent::DragData { serial, result }, window_id);
+        })
+    }
+
+    /// Complete the drops whose data was read.
+    pub(crate) fn finish_drops(&mut self) {
+        self.drops.retain(|_, drop| {
+            if !drop.drop_sent || drop.reads > 0 {
+                return true;
+            }
+
+            if let Some(offer) = drop.offer() {
+                if drop.accepted.is_some() {
+                    offer.finish();
+                }
+                offer.destroy();
+            }
+            false
+        });
+    }
+
+    /// Send `DragEntered` once the paths of the drag are known, and `DragDropped` once it was
+    /// dropped as well.
+    fn send_drop_events(&mut self, data_device: &ObjectId) {
+        let Some(drop) = self.drops.get_mut(data_device) else { return };
+        if drop.reading_paths {
+            return;
+        }
+
+        if !drop.entered {
+            drop.entered = true;
+            let offer = drop.offer();
+            let event = WindowEvent::DragEntered {
+                paths: drop.paths.clone(),
+                mime_types: offer
+                    .as_ref()
+                    .map(|offer| offer.with_mime_types(|mime_types| mime_types.to_vec()))
+                    .unwrap_or_default(),
+                actions: offer
+                    .map_or(DragActions::empty(), |offer| drag_actions(offer.source_actions)),
+                position: drop.position,
+            };
+            self.events_sink.push_window_event(event, drop.window_id);
+        }
+
+        if drop.dropped && !drop.drop_sent {
+            drop.drop_sent = true;
+            let event =
+                WindowEvent::DragDropped { paths: drop.paths.clone(), position: drop.position };
+            self.events_sink.push_window_event(event, drop.window_id);
+        }
+    }
+
+    /// The physical position of the surface local `(x, y)` on `window_id`.
+    fn drop_position(&self, window_id: WindowId, x: f64, y: f64) -> PhysicalPosition<f64> {
+        let scale_factor = self
+            .windows
+            .borrow()
+            .get(&window_id)
+            .map_or(1., |window| window.lock().unwrap().scale_factor());
+        LogicalPosition::new(x, y).to_physical(scale_factor)
+    }
+
+    /// Start tracking the drag entering a window through `data_device`.
+    fn enter_drop(&mut self, data_device: &WlDataDevice, x: f64, y: f64, surface: &WlSurface) {
+        // The compositor replaces the offer of a drag entering again.
+        self.leave_drop(&data_device.id());
+
+        let window_id = make_wid(surface);
+        if !self.windows.get_mut().contains_key(&window_id) {
+            return;
+        }
+        let Some(offer) = data_device.data::<DataDeviceData>().and_then(DataDeviceData::drag_offer)
+        else {
+            return;
+        };
+
+        let mut drop = DropState {
+            window_id,
+            data_device: data_device.clone(),
+            serial: offer.serial,
+            position: self.drop_position(window_id, x, y),
+            paths: Vec::new(),
+            reading_paths: false,
+            accepted: None,
+            entered: false,
+            dropped: false,
+            drop_sent: false,
+            reads: 0,
+        };
+
+        // Files are accepted unless the application decides otherwise.
+        if offer.with_mime_types(|mime_types| mime_types.iter().any(|m| m == URI_LIST_MIME_TYPE)) {
+            let action = if offer.source_actions.contains(DndAction::Move)
+                && !offer.source_actions.contains(DndAction::Copy)
+            {
+                DragAction::Move
+            } else {
+                DragAction::Copy
+            };
+            drop.accepted = Some((URI_LIST_MIME_TYPE.to_owned(), action));
+
+            let (id, enter_serial) = (data_device.id(), offer.serial);
+            let result = offer
+                .receive(URI_LIST_MIME_TYPE.to_owned())
+                .map_err(|err| os_error!(err).into())
+                .and_then(|pipe| {
+                    self.receive_data(pipe, URI_LIST_MIME_TYPE.to_owned(), move |state, result| {
+                        let Some(drop) = state.drops.get_mut(&id) else { return };
+                        if drop.serial != enter_serial {
+                            return;
+                        }
+                        drop.reading_paths = false;
+                        drop.paths = result.map(|data| data.to_paths()).unwrap_or_default();
+                        state.send_drop_events(&id);
+                    })
+                });
+            match result {
+                Ok(()) => drop.reading_paths = true,
+                Err(err) => warn!("Failed to read the dragged paths: {err}"),
+            }
+        }
+
+        update_drop_acceptance(&drop);
+        self.drops.insert(data_device.id(), drop);
+        self.send_drop_events(&data_device.id());
+    }
+
+    /// Stop tracking the drag through `data_device`, unless it was dropped.
+    fn leave_drop(&mut self, data_device: &ObjectId) {
+        if self.drops.get(data_device).is_none_or(|drop| drop.dropped) {
+            return;
+        }
+
+        let drop = self.drops.remove(data_device).unwrap();
+        if drop.entered {
+            let event = WindowEvent::DragLeft { position: Some(drop.position) };
+            self.events_sink.push_window_event(event, drop.window_id);
+        }
+    }
+
+    /// Take the drag in progress if it uses `source`.
+    fn take_drag_source(&mut self, source: &WlDataSource) -> Option<DragSourceState> {
+        if self.drag_source.as_ref().map(|drag| drag.source.inner()) != Some(source) {
+            return None;
+        }
+        self.drag_source.take()
+    }
+
+    /// The data device of the seat that received the latest input, with the serial of that input.
+    fn latest_data_device(&self) -> Option<(&DataDevice, u32)> {
+        self.seats
+            .values()
+            .filter_map(|seat| Some((seat.data_device.as_ref()?, seat.latest_serial?)))
+            .max_by_key(|&(_, serial)| serial)
+    }
+
+    /// The current selection offer, preferring the seat with the latest input.
+    fn selection_offer(&self) -> Option<SelectionOffer> {
+        self.latest_data_device()
+            .map(|(data_device, _)| data_device)
+            .into_iter()
+            .chain(self.seats.values().filter_map(|seat| seat.data_device.as_ref()))
+            .find_map(|data_device| data_device.data().selection_offer())
+    }
+}
+
+impl Clipboard for ActiveEventLoop {
+    fn set_contents(&self, contents: Vec<ClipboardData>) -> Result<(), RequestError> {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+
+        let manager = state
+            .data_device_manager_state
+            .as_ref()
+            .ok_or(NotSupportedError::new("wl_data_device_manager is not available"))?;
+        let (data_device, serial) = state.latest_data_device().ok_or(RequestError::Ignored)?;
+
+        let source =
+            manager.create_copy_paste_source(&self.queue_handle, source_mime_types(&contents));
+        source.set_selection(data_device, serial);
+
+        state.clipboard.source = Some(source);
+        state.clipboard.contents = contents;
+
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), RequestError> {
+        let mut state = self.state.borrow_mut();
+
+        // Destroying the source unsets the selection.
+        state.clipboard.source = None;
+        state.clipboard.contents.clear();
+
+        Ok(())
+    }
+
+    fn request_mime_types(&self) -> Result<AsyncRequestSerial, RequestError> {
+        let mut state = self.state.borrow_mut();
+        let serial = AsyncRequestSerial::get();
+
+        let result = match state.selection_offer() {
+            Some(offer) => Ok(offer.with_mime_types(|mime_types| mime_types.to_vec())),
+            None => Err(ClipboardError::Empty),
+        };
+
+        state.events_sink.push_clipboard_event(ClipboardEvent::MimeTypes { serial, result });
+        self.event_loop_awakener.ping();
+
+        Ok(serial)
+    }
+
+    fn request_data(&self, mime_type: &str) -> Result<AsyncRequestSerial, RequestError> {
+        let mut state = self.state.borrow_mut();
+        let serial = AsyncRequestSerial::get();
+
+        let offer = match state.selection_offer() {
+            Some(offer) => offer,
+            None => {
+                let result = Err(ClipboardError::Empty);
+                state.events_sink.push_clipboard_event(ClipboardEvent::Data { serial, result });
+                self.event_loop_awakener.ping();
+                return Ok(serial);
+            },
+        };
+
+        let offered_mime_type =
+            match offer.with_mime_types(|offered| find_offered_mime_type(offered, mime_type)) {
+                Some(offered_mime_type) => offered_mime_type,
+                None => {
+                    let result = Err(ClipboardError::MimeTypeUnavailable);
+                    state.events_sink.push_clipboard_event(ClipboardEvent::Data { serial, result });
+                    self.event_loop_awakener.ping();
+                    return Ok(serial);
+                },
+            };
+
+        let pipe = offer.receive(offered_mime_type).map_err(|err| os_error!(err))?;
+        state.receive_clipboard_data(pipe, serial, mime_type.to_owned())?;
+
+        Ok(serial)
+    }
+}
+
+impl DataSourceHandler for WinitState {
+    fn accept_mime(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlDataSource,
+        _: Option<String>,
+    ) {
+    }
+
+    fn send_request(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &WlDataSource,
+        mime_type: String,
+        pipe: WritePipe,
+    ) {
+        if self.clipboard.source.as_ref().map(CopyPasteSource::inner) == Some(source) {
+            self.send_clipboard_data(&self.clipboard.contents, &mime_type, pipe);
+        } else if let Some(drag) =
+            self.drag_source.as_ref().filter(|drag| drag.source.inner() == source)
+        {
+            self.send_clipboard_data(&drag.contents, &mime_type, pipe);
+        }
+    }
+
+    fn cancelled(&mut self, _: &Connection, _: &QueueHandle<Self>, source: &WlDataSource) {
+        if self.clipboard.source.as_ref().map(CopyPasteSource::inner) == Some(source) {
+            self.clipboard.source = None;
+            self.clipboard.contents.clear();
+            self.events_sink.push_clipboard_event(ClipboardEvent::OwnershipLost);
+        } else if let Some(drag) = self.take_drag_source(source) {
+            self.finish_drag(drag, DragResult::Cancelled);
+        }
+    }
+
+    fn dnd_dropped(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataSource) {
+        // The target may still read the data until `dnd_finished`.
+    }
+
+    fn dnd_finished(&mut self, _: &Connection, _: &QueueHandle<Self>, source: &WlDataSource) {
+        if let Some(drag) = self.take_drag_source(source) {
+            let result = drag.action.map_or(DragResult::Cancelled, DragResult::Dropped);
+            self.finish_drag(drag, result);
+        }
+    }
+
+    fn action(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &WlDataSource,
+        action: DndAction,
+    ) {
+        let Some(drag) = self.drag_source.as_mut().filter(|drag| drag.source.inner() == source)
+        else {
+            return;
+        };
+
+        drag.action = if action.contains(DndAction::Move) {
+            Some(DragAction::Move)
+        } else if action.contains(DndAction::Copy) {
+            Some(DragAction::Copy)
+        } else {
+            None
+        };
+    }
+}
+
+impl DataDeviceHandler for WinitState {
+    fn enter(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        data_device: &WlDataDevice,
+        x: f64,
+        y: f64,
+        surface: &WlSurface,
+    ) {
+        self.enter_drop(data_device, x, y, surface);
+    }
+
+    fn leave(&mut self, _: &Connection, _: &QueueHandle<Self>, data_device: &WlDataDevice) {
+        self.leave_drop(&data_device.id());
+    }
+
+    fn motion(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        data_device: &WlDataDevice,
+        x: f64,
+        y: f64,
+    ) {
+        let Some(window_id) = self.drops.get(&data_device.id()).map(|drop| drop.window_id) else {
+            return;
+        };
+        let position = self.drop_position(window_id, x, y);
+
+        let drop = self.drops.get_mut(&data_device.id()).unwrap();
+        drop.position = position;
+        if drop.entered {
+            self.events_sink.push_window_event(WindowEvent::DragMoved { position }, window_id);
+        }
+    }
+
+    fn selection(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataDevice) {
+        // The offer is queried when the clipboard is read.
+    }
+
+    fn drop_performed(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        data_device: &WlDataDevice,
+    ) {
+        let id = data_device.id();
+        let Some(drop) = self.drops.get_mut(&id) else { return };
+
+        if drop.accepted.is_none() {
+            // Older compositors drop even when the drop was rejected.
+            self.leave_drop(&id);
+            if let Some(offer) =
+                data_device.data::<DataDeviceData>().and_then(DataDeviceData::drag_offer)
+            {
+                offer.destroy();
+            }
+            return;
+        }
+
+        drop.dropped = true;
+        self.send_drop_events(&id);
+    }
+}
+
+impl DataOfferHandler for WinitState {
+    fn source_actions(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &mut DragOffer,
+        _: DndAction,
+    ) {
+    }
+
+    fn selected_action(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &mut DragOffer,
+        _: DndAction,
+    ) {
+    }
+}
+
+sctk::delegate_data_device!(WinitState);
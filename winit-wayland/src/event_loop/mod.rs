@@ -18,15 +18,18 @@ use sctk::reexports::calloop_wayland_source::WaylandSource;
 use sctk::reexports::client::{Connection, QueueHandle, globals};
 use tracing::warn;
 use winit_core::application::ApplicationHandler;
+use winit_core::clipboard::{Clipboard, ClipboardEvent};
 use winit_core::cursor::{CustomCursor as CoreCustomCursor, CustomCursorSource};
 use winit_core::error::{EventLoopError, NotSupportedError, OsError, RequestError};
-use winit_core::event::{DeviceEvent, StartCause, SurfaceSizeWriter, WindowEvent};
+use winit_core::event::{
+    DeviceEvent, DeviceId, DeviceInfo, StartCause, SurfaceSizeWriter, WindowEvent,
+};
 use winit_core::event_loop::pump_events::PumpStatus;
 use winit_core::event_loop::{
     ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents,
     OwnedDisplayHandle as CoreOwnedDisplayHandle,
 };
-use winit_core::monitor::MonitorHandle as CoreMonitorHandle;
+use winit_core::monitor::{MonitorEvent, MonitorHandle as CoreMonitorHandle};
 use winit_core::window::Theme;
 
 use crate::types::cursor::WaylandCustomCursor;
@@ -46,9 +49,12 @@ use super::{WindowId, logical_to_physical_rounded};
 type WaylandDispatcher = calloop::Dispatcher<'static, WaylandSource<WinitState>, WinitState>;
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum Event {
     WindowEvent { window_id: WindowId, event: WindowEvent },
-    DeviceEvent { event: DeviceEvent },
+    DeviceEvent { event: DeviceEvent, device_id: Option<DeviceId> },
+    ClipboardEvent { event: ClipboardEvent },
+    MonitorEvent { event: MonitorEvent },
 }
 
 /// The Wayland event loop.
@@ -101,6 +107,18 @@ impl EventLoop {
         // races with the server.
         event_queue.roundtrip(&mut winit_state).map_err(|err| os_error!(err))?;
 
+        // The seats bound during the first roundtrip announce their tablets in a second one.
+        event_queue.roundtrip(&mut winit_state).map_err(|err| os_error!(err))?;
+
+        // The outputs and input devices announced so far are the initial ones, they weren't
+        // connected.
+        winit_state.events_sink.retain(|event| {
+            !matches!(
+                event,
+                Event::MonitorEvent { .. } | Event::DeviceEvent { event: DeviceEvent::Added, .. }
+            )
+        });
+
         // Register Wayland source.
         let wayland_source = WaylandSource::new(connection.clone(), event_queue);
         let wayland_dispatcher =
@@ -419,9 +437,13 @@ impl EventLoop {
                 Event::WindowEvent { window_id, event } => {
                     app.window_event(&self.active_event_loop, window_id, event)
                 },
-                Event::DeviceEvent { event } => {
-                    app.device_event(&self.active_event_loop, None, event)
+                Event::DeviceEvent { event, device_id } => {
+                    app.device_event(&self.active_event_loop, device_id, event)
+                },
+                Event::ClipboardEvent { event } => {
+                    app.clipboard_event(&self.active_event_loop, event)
                 },
+                Event::MonitorEvent { event } => app.monitor_event(&self.active_event_loop, event),
             }
         }
 
@@ -434,12 +456,46 @@ impl EventLoop {
                 Event::WindowEvent { window_id, event } => {
                     app.window_event(&self.active_event_loop, window_id, event)
                 },
-                Event::DeviceEvent { event } => {
-                    app.device_event(&self.active_event_loop, None, event)
+                Event::DeviceEvent { event, device_id } => {
+                    app.device_event(&self.active_event_loop, device_id, event)
                 },
+                Event::ClipboardEvent { event } => {
+                    app.clipboard_event(&self.active_event_loop, event)
+                },
+                Event::MonitorEvent { event } => app.monitor_event(&self.active_event_loop, event),
             }
         }
 
+        // Handle the drag and drop requests, which are made in response to the events above.
+        let queue_handle = self.active_event_loop.queue_handle.clone();
+        let pending_events = self.with_state(|state| {
+            let drag_requests: Vec<_> = state
+                .window_requests
+                .get_mut()
+                .iter()
+                .flat_map(|(window_id, requests)| {
+                    requests.take_drag_requests().into_iter().map(|request| (*window_id, request))
+                })
+                .collect();
+            for (window_id, request) in drag_requests {
+                state.drag_request(&queue_handle, window_id, request);
+            }
+
+            // Complete the drops once the data they need is read.
+            state.finish_drops();
+
+            !state.events_sink.is_empty()
+        });
+        if pending_events {
+            self.active_event_loop.event_loop_awakener.ping();
+        }
+
+        // Destroy the dropped windows.
+        let destroyed = self.with_state(|state| state.destroy_closed_windows());
+        for window_id in destroyed {
+            app.window_event(&self.active_event_loop, window_id, WindowEvent::Destroyed);
+        }
+
         // Collect the window ids
         self.with_state(|state| {
             window_ids.extend(state.window_requests.get_mut().keys());
@@ -448,10 +504,9 @@ impl EventLoop {
         for window_id in window_ids.iter() {
             let event = self.with_state(|state| {
                 let window_requests = state.window_requests.get_mut();
-                if window_requests.get(window_id).unwrap().take_closed() {
-                    mem::drop(window_requests.remove(window_id));
-                    mem::drop(state.windows.get_mut().remove(window_id));
-                    return Some(WindowEvent::Destroyed);
+                // The parents of popups which are still alive aren't destroyed yet.
+                if window_requests.get(window_id).unwrap().is_closed() {
+                    return None;
                 }
 
                 let mut window =
@@ -625,6 +680,10 @@ impl RootActiveEventLoop for ActiveEventLoop {
         self.exit.get().is_some()
     }
 
+    fn clipboard(&self) -> Option<&dyn Clipboard> {
+        self.state.borrow().data_device_manager_state.is_some().then_some(self as &dyn Clipboard)
+    }
+
     #[inline]
     fn listen_device_events(&self, _allowed: DeviceEvents) {}
 
@@ -671,6 +730,10 @@ impl RootActiveEventLoop for ActiveEventLoop {
         None
     }
 
+    fn devices(&self) -> Box<dyn Iterator<Item = DeviceInfo>> {
+        Box::new(self.state.borrow().devices().into_iter())
+    }
+
     fn owned_display_handle(&self) -> CoreOwnedDisplayHandle {
         CoreOwnedDisplayHandle::new(self.handle.clone())
     }
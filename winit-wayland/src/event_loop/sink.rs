@@ -2,7 +2,9 @@
 
 use std::vec::Drain;
 
-use winit_core::event::{DeviceEvent, WindowEvent};
+use winit_core::clipboard::ClipboardEvent;
+use winit_core::event::{DeviceEvent, DeviceId, WindowEvent};
+use winit_core::monitor::MonitorEvent;
 use winit_core::window::WindowId;
 
 use super::Event;
@@ -27,8 +29,8 @@ impl EventSink {
 
     /// Add new device event to a queue.
     #[inline]
-    pub fn push_device_event(&mut self, event: DeviceEvent) {
-        self.window_events.push(Event::DeviceEvent { event });
+    pub fn push_device_event(&mut self, event: DeviceEvent, device_id: Option<DeviceId>) {
+        self.window_events.push(Event::DeviceEvent { event, device_id });
     }
 
     /// Add new window event to a queue.
@@ -37,11 +39,29 @@ impl EventSink {
         self.window_events.push(Event::WindowEvent { event, window_id });
     }
 
+    /// Add new clipboard event to a queue.
+    #[inline]
+    pub fn push_clipboard_event(&mut self, event: ClipboardEvent) {
+        self.window_events.push(Event::ClipboardEvent { event });
+    }
+
+    /// Add new monitor event to a queue.
+    #[inline]
+    pub fn push_monitor_event(&mut self, event: MonitorEvent) {
+        self.window_events.push(Event::MonitorEvent { event });
+    }
+
     #[inline]
     pub fn append(&mut self, other: &mut Self) {
         self.window_events.append(&mut other.window_events);
     }
 
+    /// Keep only the events for which `f` returns `true`.
+    #[inline]
+    pub(crate) fn retain(&mut self, f: impl FnMut(&Event) -> bool) {
+        self.window_events.retain(f);
+    }
+
     #[inline]
     pub(crate) fn drain(&mut self) -> Drain<'_, Event> {
         self.window_events.drain(..)
@@ -25,7 +25,13 @@ use sctk::reexports::client::Proxy;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::shm::slot::{Buffer, CreateBufferError, SlotPool};
 use wayland_client::protocol::wl_shm::Format;
-use winit_core::event_loop::ActiveEventLoop as CoreActiveEventLoop;
+use winit_core::clipboard::ClipboardData;
+#[cfg(doc)]
+use winit_core::clipboard::ClipboardEvent;
+use winit_core::error::{NotSupportedError, RequestError};
+use winit_core::event::DeviceId;
+use winit_core::event_loop::{ActiveEventLoop as CoreActiveEventLoop, AsyncRequestSerial};
+use winit_core::monitor::MonitorHandle as CoreMonitorHandle;
 use winit_core::window::{
     ActivationToken, PlatformWindowAttributes, Window as CoreWindow, WindowId,
 };
@@ -34,8 +40,10 @@ macro_rules! os_error {
     ($error:expr) => {{ winit_core::error::OsError::new(line!(), file!(), $error) }};
 }
 
+mod data_device;
 mod event_loop;
 mod output;
+mod primary_selection;
 mod seat;
 mod state;
 mod types;
@@ -48,6 +56,38 @@ pub use self::window::Window;
 pub trait ActiveEventLoopExtWayland {
     /// True if the [`ActiveEventLoop`] uses Wayland.
     fn is_wayland(&self) -> bool;
+
+    /// Set the primary selection on behalf of `window`, offering `contents`.
+    ///
+    /// The selection is set on the seat focusing `window` with the serial of the latest input
+    /// event received from that seat, so this should be called in response to user input,
+    /// typically when text is selected. Ownership is lost when another client sets the
+    /// selection, which is reported with [`ClipboardEvent::PrimarySelectionOwnershipLost`].
+    ///
+    /// Returns [`RequestError::Ignored`] when no seat with input focuses `window`, and
+    /// [`RequestError::NotSupported`] when the compositor lacks
+    /// `zwp_primary_selection_device_manager_v1`.
+    fn set_primary_selection(
+        &self,
+        window: &dyn CoreWindow,
+        contents: Vec<ClipboardData>,
+    ) -> Result<(), RequestError>;
+
+    /// Unset the primary selection, if we own it.
+    fn clear_primary_selection(&self) -> Result<(), RequestError>;
+
+    /// Request the MIME types offered through the primary selection.
+    ///
+    /// The result is delivered with [`ClipboardEvent::MimeTypes`] carrying the returned serial.
+    fn request_primary_selection_mime_types(&self) -> Result<AsyncRequestSerial, RequestError>;
+
+    /// Request the contents of the primary selection as `mime_type`.
+    ///
+    /// The result is delivered with [`ClipboardEvent::Data`] carrying the returned serial.
+    fn request_primary_selection(
+        &self,
+        mime_type: &str,
+    ) -> Result<AsyncRequestSerial, RequestError>;
 }
 
 impl ActiveEventLoopExtWayland for dyn CoreActiveEventLoop + '_ {
@@ -55,6 +95,41 @@ impl ActiveEventLoopExtWayland for dyn CoreActiveEventLoop + '_ {
     fn is_wayland(&self) -> bool {
         self.cast_ref::<ActiveEventLoop>().is_some()
     }
+
+    fn set_primary_selection(
+        &self,
+        window: &dyn CoreWindow,
+        contents: Vec<ClipboardData>,
+    ) -> Result<(), RequestError> {
+        let event_loop = wayland_event_loop(self)?;
+        let window = window
+            .cast_ref::<Window>()
+            .ok_or(NotSupportedError::new("the window is not a Wayland window"))?;
+        event_loop.set_primary_selection(window.id(), contents)
+    }
+
+    fn clear_primary_selection(&self) -> Result<(), RequestError> {
+        wayland_event_loop(self)?.clear_primary_selection()
+    }
+
+    fn request_primary_selection_mime_types(&self) -> Result<AsyncRequestSerial, RequestError> {
+        wayland_event_loop(self)?.request_primary_selection_mime_types()
+    }
+
+    fn request_primary_selection(
+        &self,
+        mime_type: &str,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        wayland_event_loop(self)?.request_primary_selection(mime_type)
+    }
+}
+
+fn wayland_event_loop(
+    event_loop: &dyn CoreActiveEventLoop,
+) -> Result<&ActiveEventLoop, RequestError> {
+    event_loop
+        .cast_ref::<ActiveEventLoop>()
+        .ok_or_else(|| NotSupportedError::new("the event loop is not using Wayland").into())
 }
 
 /// Additional methods on [`EventLoop`] that are specific to Wayland.
@@ -81,6 +156,14 @@ pub trait EventLoopBuilderExtWayland {
 pub trait WindowExtWayland {
     /// Returns `xdg_toplevel` of the window or [`None`] if the window is X11 window.
     fn xdg_toplevel(&self) -> Option<NonNull<c_void>>;
+
+    /// Sets whether the commits of a child window are applied along with the ones of its
+    /// parent.
+    ///
+    /// See [`WindowAttributesWayland::with_synchronized_commits`] for details.
+    ///
+    /// Returns [`RequestError::NotSupported`] when the window isn't a child window.
+    fn set_synchronized_commits(&self, synchronized: bool) -> Result<(), RequestError>;
 }
 
 impl WindowExtWayland for dyn CoreWindow + '_ {
@@ -88,6 +171,13 @@ impl WindowExtWayland for dyn CoreWindow + '_ {
     fn xdg_toplevel(&self) -> Option<NonNull<c_void>> {
         self.cast_ref::<Window>()?.xdg_toplevel()
     }
+
+    #[inline]
+    fn set_synchronized_commits(&self, synchronized: bool) -> Result<(), RequestError> {
+        self.cast_ref::<Window>()
+            .ok_or(NotSupportedError::new("the window is not a Wayland window"))?
+            .set_synchronized_commits(synchronized)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -102,6 +192,8 @@ pub struct WindowAttributesWayland {
     pub(crate) name: Option<ApplicationName>,
     pub(crate) activation_token: Option<ActivationToken>,
     pub(crate) prefer_csd: bool,
+    pub(crate) layer_surface: Option<LayerSurfaceAttributes>,
+    pub(crate) synchronized_commits: bool,
 }
 
 impl WindowAttributesWayland {
@@ -135,6 +227,162 @@ impl WindowAttributesWayland {
         self.prefer_csd = prefer_csd;
         self
     }
+
+    /// Build the window as a layer surface, for panels, docks, launchers and overlays.
+    ///
+    /// The surface is placed by the compositor according to `layer_surface`, and doesn't have
+    /// decorations, a title or the states of a toplevel. A size dimension of `0` lets the
+    /// compositor pick it, which requires anchoring to both opposite edges on that axis.
+    ///
+    /// The window creation fails with [`RequestError::NotSupported`] when the compositor lacks
+    /// `zwlr_layer_shell_v1`.
+    #[inline]
+    pub fn with_layer_surface(mut self, layer_surface: LayerSurfaceAttributes) -> Self {
+        self.layer_surface = Some(layer_surface);
+        self
+    }
+
+    /// Build a child window whose commits are applied along with the ones of its parent.
+    ///
+    /// By default, a [child window] is desynchronized and presents its frames on its own, which
+    /// suits video and GL views. When synchronized, its frames are only shown with the next
+    /// frame of its parent, so both can be updated atomically, e.g. while resizing.
+    ///
+    /// This has no effect on other windows.
+    ///
+    /// [child window]: winit_core::window::WindowAttributes::with_parent_window
+    #[inline]
+    pub fn with_synchronized_commits(mut self, synchronized: bool) -> Self {
+        self.synchronized_commits = synchronized;
+        self
+    }
+}
+
+/// The layer a [layer surface] is stacked in.
+///
+/// [layer surface]: WindowAttributesWayland::with_layer_surface
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layer {
+    /// Below the windows, e.g. for wallpapers.
+    Background,
+    /// Below the windows, but above the background, e.g. for desktop widgets.
+    Bottom,
+    /// Above the windows, e.g. for panels.
+    #[default]
+    Top,
+    /// Above everything, including fullscreen windows, e.g. for lock screens and notifications.
+    Overlay,
+}
+
+bitflags::bitflags! {
+    /// The edges of the output a [layer surface] is anchored to.
+    ///
+    /// Anchoring to two adjacent edges places the surface in that corner, anchoring to two
+    /// opposite edges centers it on that axis, or stretches it when its size is `0` on that axis.
+    ///
+    /// [layer surface]: WindowAttributesWayland::with_layer_surface
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct LayerAnchor: u32 {
+        const TOP = 1 << 0;
+        const BOTTOM = 1 << 1;
+        const LEFT = 1 << 2;
+        const RIGHT = 1 << 3;
+    }
+}
+
+/// How a [layer surface] receives the keyboard focus.
+///
+/// [layer surface]: WindowAttributesWayland::with_layer_surface
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyboardInteractivity {
+    /// The surface never gets the keyboard focus.
+    #[default]
+    None,
+    /// The surface takes the keyboard focus exclusively while it's in the top or overlay layer.
+    Exclusive,
+    /// The surface gets the keyboard focus like regular windows, e.g. when it's clicked.
+    OnDemand,
+}
+
+/// The attributes of a [layer surface].
+///
+/// [layer surface]: WindowAttributesWayland::with_layer_surface
+#[derive(Debug, Clone)]
+pub struct LayerSurfaceAttributes {
+    pub(crate) layer: Layer,
+    pub(crate) anchor: LayerAnchor,
+    pub(crate) exclusive_zone: i32,
+    pub(crate) margin: (i32, i32, i32, i32),
+    pub(crate) keyboard_interactivity: KeyboardInteractivity,
+    pub(crate) namespace: String,
+    pub(crate) output: Option<CoreMonitorHandle>,
+}
+
+impl LayerSurfaceAttributes {
+    /// A surface in `layer`, centered on the output, and not anchored to any edge.
+    pub fn new(layer: Layer) -> Self {
+        Self {
+            layer,
+            anchor: LayerAnchor::empty(),
+            exclusive_zone: 0,
+            margin: (0, 0, 0, 0),
+            keyboard_interactivity: KeyboardInteractivity::None,
+            namespace: String::new(),
+            output: None,
+        }
+    }
+
+    /// Anchor the surface to the given edges of the output.
+    #[inline]
+    pub fn with_anchor(mut self, anchor: LayerAnchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Reserve space along the anchored edge, in logical pixels, so windows don't overlap the
+    /// surface, like panels do.
+    ///
+    /// With `0`, the surface is moved to not overlap the exclusive zones of other surfaces, and
+    /// with `-1` it isn't moved at all. The default is `0`.
+    #[inline]
+    pub fn with_exclusive_zone(mut self, exclusive_zone: i32) -> Self {
+        self.exclusive_zone = exclusive_zone;
+        self
+    }
+
+    /// The distance to the anchored edges, in logical pixels.
+    #[inline]
+    pub fn with_margin(mut self, top: i32, right: i32, bottom: i32, left: i32) -> Self {
+        self.margin = (top, right, bottom, left);
+        self
+    }
+
+    /// How the surface receives the keyboard focus.
+    ///
+    /// The default is [`KeyboardInteractivity::None`].
+    #[inline]
+    pub fn with_keyboard_interactivity(
+        mut self,
+        keyboard_interactivity: KeyboardInteractivity,
+    ) -> Self {
+        self.keyboard_interactivity = keyboard_interactivity;
+        self
+    }
+
+    /// The purpose of the surface, such as `"panel"` or `"notifications"`, which compositors
+    /// may use to apply their rules.
+    #[inline]
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// The monitor to show the surface on, `None` letting the compositor choose.
+    #[inline]
+    pub fn with_output(mut self, output: Option<CoreMonitorHandle>) -> Self {
+        self.output = output;
+        self
+    }
 }
 
 impl PlatformWindowAttributes for WindowAttributesWayland {
@@ -149,6 +397,12 @@ fn make_wid(surface: &WlSurface) -> WindowId {
     WindowId::from_raw(surface.id().as_ptr() as usize)
 }
 
+/// Get the DeviceId out of an input device proxy.
+#[inline]
+fn make_did(proxy: &impl Proxy) -> DeviceId {
+    DeviceId::from_raw(proxy.id().protocol_id() as i64)
+}
+
 /// The default routine does floor, but we need round on Wayland.
 fn logical_to_physical_rounded(size: LogicalSize<u32>, scale_factor: f64) -> PhysicalSize<u32> {
     let width = size.width as f64 * scale_factor;
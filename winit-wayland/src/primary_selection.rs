@@ -0,0 +1,191 @@
+//! Handling of `zwp_primary_selection_device_v1`, which backs the primary selection.
+
+use sctk::data_device_manager::WritePipe;
+use sctk::primary_selection::device::{PrimarySelectionDevice, PrimarySelectionDeviceHandler};
+use sctk::primary_selection::PrimarySelectionManagerState;
+use sctk::primary_selection::offer::PrimarySelectionOffer;
+use sctk::primary_selection::selection::{PrimarySelectionSource, PrimarySelectionSourceHandler};
+use sctk::reexports::client::{Connection, QueueHandle};
+use sctk::reexports::protocols::wp::primary_selection::zv1::client::zwp_primary_selection_device_v1::ZwpPrimarySelectionDeviceV1;
+use sctk::reexports::protocols::wp::primary_selection::zv1::client::zwp_primary_selection_source_v1::ZwpPrimarySelectionSourceV1;
+use winit_core::clipboard::{ClipboardData, ClipboardError, ClipboardEvent};
+use winit_core::error::{NotSupportedError, RequestError};
+use winit_core::event_loop::AsyncRequestSerial;
+use winit_core::window::WindowId;
+
+use crate::data_device::{find_offered_mime_type, source_mime_types};
+use crate::event_loop::ActiveEventLoop;
+use crate::state::WinitState;
+
+/// The primary selection contents we're offering.
+#[derive(Debug, Default)]
+pub struct PrimarySelectionState {
+    /// The source set as the primary selection, while we own it.
+    source: Option<PrimarySelectionSource>,
+
+    /// The contents offered through `source`.
+    contents: Vec<ClipboardData>,
+}
+
+impl WinitState {
+    fn primary_selection_manager(&self) -> Result<&PrimarySelectionManagerState, RequestError> {
+        self.primary_selection_manager_state.as_ref().ok_or_else(|| {
+            NotSupportedError::new("zwp_primary_selection_device_manager_v1 is not available")
+                .into()
+        })
+    }
+
+    /// The primary selection device of the seat focusing `window_id` that received the latest
+    /// input, with the serial of that input.
+    fn window_primary_selection_device(
+        &self,
+        window_id: WindowId,
+    ) -> Option<(&PrimarySelectionDevice, u32)> {
+        let windows = self.windows.borrow();
+        let window = windows.get(&window_id)?.lock().unwrap();
+        self.seats
+            .iter()
+            .filter(|(seat_id, _)| window.has_seat_focus(seat_id))
+            .filter_map(|(_, seat)| {
+                Some((seat.primary_selection_device.as_ref()?, seat.latest_serial?))
+            })
+            .max_by_key(|&(_, serial)| serial)
+    }
+
+    /// The current primary selection offer, preferring the seat with the latest input.
+    fn primary_selection_offer(&self) -> Option<PrimarySelectionOffer> {
+        let mut devices: Vec<_> = self
+            .seats
+            .values()
+            .filter_map(|seat| Some((seat.primary_selection_device.as_ref()?, seat.latest_serial)))
+            .collect();
+        devices.sort_by_key(|&(_, serial)| std::cmp::Reverse(serial));
+        devices.into_iter().find_map(|(device, _)| device.data().selection_offer())
+    }
+}
+
+impl ActiveEventLoop {
+    pub(crate) fn set_primary_selection(
+        &self,
+        window_id: WindowId,
+        contents: Vec<ClipboardData>,
+    ) -> Result<(), RequestError> {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+
+        let manager = state.primary_selection_manager()?;
+        let (device, serial) =
+            state.window_primary_selection_device(window_id).ok_or(RequestError::Ignored)?;
+
+        let source =
+            manager.create_selection_source(&self.queue_handle, source_mime_types(&contents));
+        source.set_selection(device, serial);
+
+        state.primary_selection.source = Some(source);
+        state.primary_selection.contents = contents;
+
+        Ok(())
+    }
+
+    pub(crate) fn clear_primary_selection(&self) -> Result<(), RequestError> {
+        let mut state = self.state.borrow_mut();
+
+        // Destroying the source unsets the selection.
+        state.primary_selection.source = None;
+        state.primary_selection.contents.clear();
+
+        Ok(())
+    }
+
+    pub(crate) fn request_primary_selection_mime_types(
+        &self,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let mut state = self.state.borrow_mut();
+        state.primary_selection_manager()?;
+
+        let serial = AsyncRequestSerial::get();
+        let result = match state.primary_selection_offer() {
+            Some(offer) => Ok(offer.with_mime_types(|mime_types| mime_types.to_vec())),
+            None => Err(ClipboardError::Empty),
+        };
+
+        state.events_sink.push_clipboard_event(ClipboardEvent::MimeTypes { serial, result });
+        self.event_loop_awakener.ping();
+
+        Ok(serial)
+    }
+
+    pub(crate) fn request_primary_selection(
+        &self,
+        mime_type: &str,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let mut state = self.state.borrow_mut();
+        state.primary_selection_manager()?;
+
+        let serial = AsyncRequestSerial::get();
+        let offered_mime_type =
+            state.primary_selection_offer().ok_or(ClipboardError::Empty).and_then(|offer| {
+                offer
+                    .with_mime_types(|offered| find_offered_mime_type(offered, mime_type))
+                    .map(|offered_mime_type| (offer, offered_mime_type))
+                    .ok_or(ClipboardError::MimeTypeUnavailable)
+            });
+        let (offer, offered_mime_type) = match offered_mime_type {
+            Ok(offered) => offered,
+            Err(err) => {
+                let result = Err(err);
+                state.events_sink.push_clipboard_event(ClipboardEvent::Data { serial, result });
+                self.event_loop_awakener.ping();
+                return Ok(serial);
+            },
+        };
+
+        let pipe = offer.receive(offered_mime_type).map_err(|err| os_error!(err))?;
+        state.receive_clipboard_data(pipe, serial, mime_type.to_owned())?;
+
+        Ok(serial)
+    }
+}
+
+impl PrimarySelectionSourceHandler for WinitState {
+    fn send_request(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &ZwpPrimarySelectionSourceV1,
+        mime_type: String,
+        pipe: WritePipe,
+    ) {
+        if self.primary_selection.source.as_ref().map(PrimarySelectionSource::inner) == Some(source)
+        {
+            self.send_clipboard_data(&self.primary_selection.contents, &mime_type, pipe);
+        }
+    }
+
+    fn cancelled(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &ZwpPrimarySelectionSourceV1,
+    ) {
+        if self.primary_selection.source.as_ref().map(PrimarySelectionSource::inner) == Some(source)
+        {
+            self.primary_selection.source = None;
+            self.primary_selection.contents.clear();
+            self.events_sink.push_clipboard_event(ClipboardEvent::PrimarySelectionOwnershipLost);
+        }
+    }
+}
+
+impl PrimarySelectionDeviceHandler for WinitState {
+    fn selection(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &ZwpPrimarySelectionDeviceV1,
+    ) {
+        // The offer is queried when the primary selection is read.
+    }
+}
+
+sctk::delegate_primary_selection!(WinitState);
@@ -13,7 +13,7 @@ use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle, WEnum};
 use tracing::warn;
 use winit_common::xkb::Context;
 use winit_core::event::{ElementState, WindowEvent};
-use winit_core::keyboard::ModifiersState;
+use winit_core::keyboard::{KeyboardLayouts, ModifiersState};
 
 use crate::WindowId;
 use crate::event_loop::sink::EventSink;
@@ -52,6 +52,16 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     WlKeymapFormat::XkbV1 => {
                         let context = &mut keyboard_state.xkb_context;
                         context.set_keymap_from_fd(fd, size as usize);
+
+                        let window_id = *data.window_id.lock().unwrap();
+                        if let Some(window_id) = window_id {
+                            if let Some(layouts) = keyboard_state.changed_layouts(false) {
+                                state.events_sink.push_window_event(
+                                    WindowEvent::KeyboardLayoutChanged(layouts),
+                                    window_id,
+                                );
+                            }
+                        }
                     },
                     _ => unreachable!(),
                 },
@@ -59,7 +69,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     warn!("unknown keymap format 0x{:x}", value)
                 },
             },
-            WlKeyboardEvent::Enter { surface, .. } => {
+            WlKeyboardEvent::Enter { serial, surface, .. } => {
                 let window_id = crate::make_wid(&surface);
 
                 // Mark the window as focused.
@@ -67,7 +77,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     Some(window) => {
                         let mut window = window.lock().unwrap();
                         let was_unfocused = !window.has_focus();
-                        window.add_seat_focus(data.seat.id());
+                        window.add_seat_focus(&data.seat);
                         was_unfocused
                     },
                     None => return,
@@ -80,12 +90,19 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                 }
 
                 *data.window_id.lock().unwrap() = Some(window_id);
+                seat_state.latest_serial = Some(serial);
 
                 // The keyboard focus is considered as general focus.
                 if was_unfocused {
                     state.events_sink.push_window_event(WindowEvent::Focused(true), window_id);
                 }
 
+                if let Some(layouts) = keyboard_state.changed_layouts(true) {
+                    state
+                        .events_sink
+                        .push_window_event(WindowEvent::KeyboardLayoutChanged(layouts), window_id);
+                }
+
                 // HACK: this is just for GNOME not fixing their ordering issue of modifiers.
                 if std::mem::take(&mut seat_state.modifiers_pending) {
                     state.events_sink.push_window_event(
@@ -129,9 +146,11 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     state.events_sink.push_window_event(WindowEvent::Focused(false), window_id);
                 }
             },
-            WlKeyboardEvent::Key { key, state: WEnum::Value(key_state), .. }
+            WlKeyboardEvent::Key { serial, key, state: WEnum::Value(key_state), .. }
                 if matches!(key_state, WlKeyState::Repeated | WlKeyState::Pressed) =>
             {
+                seat_state.latest_serial = Some(serial);
+
                 let key = key + 8;
                 key_input(
                     keyboard_state,
@@ -251,6 +270,12 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     WindowEvent::ModifiersChanged(seat_state.modifiers.into()),
                     window_id,
                 );
+
+                if let Some(layouts) = keyboard_state.changed_layouts(false) {
+                    state
+                        .events_sink
+                        .push_window_event(WindowEvent::KeyboardLayoutChanged(layouts), window_id);
+                }
             },
             WlKeyboardEvent::RepeatInfo { rate, delay } => {
                 keyboard_state.repeat_info = if rate == 0 {
@@ -291,6 +316,9 @@ pub struct KeyboardState {
 
     /// The current repeat raw key.
     pub current_repeat: Option<u32>,
+
+    /// The latest keyboard layouts sent to the focused window.
+    pub layouts: Option<KeyboardLayouts>,
 }
 
 impl KeyboardState {
@@ -302,7 +330,19 @@ impl KeyboardState {
             repeat_info: RepeatInfo::default(),
             repeat_token: None,
             current_repeat: None,
+            layouts: None,
+        }
+    }
+
+    /// The keyboard layouts, when they changed since they were last sent or `force` is `true`.
+    fn changed_layouts(&mut self, force: bool) -> Option<KeyboardLayouts> {
+        let layouts = self.xkb_context.keyboard_layouts()?;
+        if self.layouts.as_ref() == Some(&layouts) && !force {
+            return None;
         }
+
+        self.layouts = Some(layouts.clone());
+        Some(layouts)
     }
 }
 
@@ -3,6 +3,8 @@
 use std::sync::Arc;
 
 use foldhash::HashMap;
+use sctk::data_device_manager::data_device::DataDevice;
+use sctk::primary_selection::device::PrimarySelectionDevice;
 use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_touch::WlTouch;
@@ -14,9 +16,10 @@ use sctk::seat::{Capability as SeatCapability, SeatHandler, SeatState};
 use tracing::warn;
 use wayland_protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gesture_pinch_v1::ZwpPointerGesturePinchV1;
 use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_seat_v2::ZwpTabletSeatV2;
-use winit_core::event::WindowEvent;
+use winit_core::event::{DeviceEvent, DeviceId, DeviceInfo, DeviceKind, WindowEvent};
 use winit_core::keyboard::ModifiersState;
 
+use crate::make_did;
 use crate::state::WinitState;
 
 mod keyboard;
@@ -68,12 +71,37 @@ pub struct WinitSeatState {
 
     /// Whether we have pending modifiers.
     modifiers_pending: bool,
+
+    /// The data device bound on the seat.
+    pub data_device: Option<DataDevice>,
+
+    /// The primary selection device bound on the seat.
+    pub primary_selection_device: Option<PrimarySelectionDevice>,
+
+    /// The serial of the latest keyboard, pointer button, or touch down event.
+    pub latest_serial: Option<u32>,
 }
 
 impl WinitSeatState {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// The keyboard, pointer, and touch devices bound on the seat, with their kind.
+    fn devices(&self) -> impl Iterator<Item = (DeviceId, DeviceKind)> + '_ {
+        let keyboard = self
+            .keyboard_state
+            .as_ref()
+            .map(|keyboard_state| (make_did(&keyboard_state.keyboard), DeviceKind::Keyboard));
+        let pointer =
+            self.pointer.as_ref().map(|pointer| (make_did(pointer.pointer()), DeviceKind::Mouse));
+        let touch = self.touch.as_ref().map(|touch| (make_did(touch), DeviceKind::Touchscreen));
+        keyboard.into_iter().chain(pointer).chain(touch)
+    }
+
+    fn device_ids(&self) -> impl Iterator<Item = DeviceId> + '_ {
+        self.devices().map(|(device_id, _)| device_id)
+    }
 }
 
 impl SeatHandler for WinitState {
@@ -99,9 +127,13 @@ impl SeatHandler for WinitState {
         match capability {
             SeatCapability::Touch if seat_state.touch.is_none() => {
                 seat_state.touch = self.seat_state.get_touch(queue_handle, &seat).ok();
+                if let Some(touch) = seat_state.touch.as_ref() {
+                    self.events_sink.push_device_event(DeviceEvent::Added, Some(make_did(touch)));
+                }
             },
             SeatCapability::Keyboard if seat_state.keyboard_state.is_none() => {
                 let keyboard = seat.get_keyboard(queue_handle, KeyboardData::new(seat.clone()));
+                self.events_sink.push_device_event(DeviceEvent::Added, Some(make_did(&keyboard)));
                 seat_state.keyboard_state =
                     Some(KeyboardState::new(keyboard, self.loop_handle.clone()));
             },
@@ -124,6 +156,10 @@ impl SeatHandler for WinitState {
                         pointer_data,
                     )
                     .expect("failed to create pointer with present capability.");
+                self.events_sink.push_device_event(
+                    DeviceEvent::Added,
+                    Some(make_did(themed_pointer.pointer())),
+                );
 
                 seat_state.relative_pointer = self.relative_pointer.as_ref().map(|manager| {
                     manager.get_relative_pointer(
@@ -161,6 +197,26 @@ impl SeatHandler for WinitState {
             )));
         }
 
+        if let Some(data_device_manager_state) = seat_state
+            .data_device
+            .is_none()
+            .then_some(self.data_device_manager_state.as_ref())
+            .flatten()
+        {
+            seat_state.data_device =
+                Some(data_device_manager_state.get_data_device(queue_handle, &seat));
+        }
+
+        if let Some(primary_selection_manager_state) = seat_state
+            .primary_selection_device
+            .is_none()
+            .then_some(self.primary_selection_manager_state.as_ref())
+            .flatten()
+        {
+            seat_state.primary_selection_device =
+                Some(primary_selection_manager_state.get_selection_device(queue_handle, &seat));
+        }
+
         if let Some(tablet_state) =
             seat_state.tablet.is_none().then_some(self.tablet_state.as_ref()).flatten()
         {
@@ -188,13 +244,13 @@ impl SeatHandler for WinitState {
         }
 
         // NOTE: figure out when this should actually be destroyed.
-        if let Some(tablet) = seat_state.tablet.take() {
-            tablet.destroy();
-        }
+        let tablet = seat_state.tablet.take();
 
         match capability {
             SeatCapability::Touch => {
                 if let Some(touch) = seat_state.touch.take() {
+                    self.events_sink
+                        .push_device_event(DeviceEvent::Removed, Some(make_did(&touch)));
                     if touch.version() >= 3 {
                         touch.release();
                     }
@@ -210,6 +266,8 @@ impl SeatHandler for WinitState {
                 }
 
                 if let Some(pointer) = seat_state.pointer.take() {
+                    self.events_sink
+                        .push_device_event(DeviceEvent::Removed, Some(make_did(pointer.pointer())));
                     let pointer_data = pointer.pointer().winit_data();
 
                     // Remove the cursor from the mapping.
@@ -226,11 +284,19 @@ impl SeatHandler for WinitState {
                 }
             },
             SeatCapability::Keyboard => {
-                seat_state.keyboard_state = None;
+                if let Some(keyboard_state) = seat_state.keyboard_state.take() {
+                    let device_id = make_did(&keyboard_state.keyboard);
+                    self.events_sink.push_device_event(DeviceEvent::Removed, Some(device_id));
+                }
                 self.on_keyboard_destroy(&seat.id());
             },
             _ => (),
         }
+
+        if let Some(tablet) = tablet {
+            tablet.destroy();
+            self.remove_tablets(&seat.id());
+        }
     }
 
     fn new_seat(
@@ -248,12 +314,44 @@ impl SeatHandler for WinitState {
         _queue_handle: &QueueHandle<Self>,
         seat: WlSeat,
     ) {
-        let _ = self.seats.remove(&seat.id());
+        if let Some(seat_state) = self.seats.remove(&seat.id()) {
+            for device_id in seat_state.device_ids() {
+                self.events_sink.push_device_event(DeviceEvent::Removed, Some(device_id));
+            }
+        }
+        self.remove_tablets(&seat.id());
         self.on_keyboard_destroy(&seat.id());
     }
 }
 
 impl WinitState {
+    /// The input devices of all the seats.
+    pub fn devices(&self) -> Vec<DeviceInfo> {
+        let mut devices = Vec::new();
+        for seat in self.seat_state.seats() {
+            let seat_name = self.seat_state.info(&seat).and_then(|info| info.name);
+            if let Some(seat_state) = self.seats.get(&seat.id()) {
+                devices.extend(seat_state.devices().map(|(id, kind)| DeviceInfo {
+                    id,
+                    name: None,
+                    kind,
+                    seat: seat_name.clone(),
+                }));
+            }
+
+            devices.extend(self.tablets.values().filter(|device| device.seat == seat.id()).map(
+                |device| DeviceInfo {
+                    id: make_did(device.tablet()),
+                    name: device.name.clone(),
+                    kind: DeviceKind::Tablet,
+                    seat: seat_name.clone(),
+                },
+            ));
+        }
+
+        devices
+    }
+
     fn on_keyboard_destroy(&mut self, seat: &ObjectId) {
         for (window_id, window) in self.windows.get_mut() {
             let mut window = window.lock().unwrap();
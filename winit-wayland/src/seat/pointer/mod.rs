@@ -7,7 +7,7 @@ use std::time::Duration;
 use tracing::warn;
 
 use sctk::reexports::client::delegate_dispatch;
-use sctk::reexports::client::protocol::wl_pointer::WlPointer;
+use sctk::reexports::client::protocol::wl_pointer::{AxisSource, WlPointer};
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Connection, Proxy, QueueHandle, Dispatch};
@@ -29,7 +29,8 @@ use sctk::seat::SeatState;
 
 use dpi::{LogicalPosition, PhysicalPosition};
 use winit_core::event::{
-    ElementState, MouseButton, MouseScrollDelta, PointerKind, PointerSource, TouchPhase,
+    ElementState, MouseButton, MouseScrollDelta, PointerKind, PointerSource, ScrollSource,
+    TouchPhase,
     WindowEvent, ButtonSource,
 };
 
@@ -48,7 +49,7 @@ impl PointerHandler for WinitState {
         events: &[PointerEvent],
     ) {
         let seat = pointer.winit_data().seat();
-        let seat_state = match self.seats.get(&seat.id()) {
+        let seat_state = match self.seats.get_mut(&seat.id()) {
             Some(seat_state) => seat_state,
             None => {
                 warn!("Received pointer event without seat");
@@ -67,8 +68,11 @@ impl PointerHandler for WinitState {
         for event in events {
             let surface = &event.surface;
 
-            // The parent surface.
+            // The parent surface, unless the surface is a child window.
             let parent_surface = match event.surface.data::<SurfaceData>() {
+                Some(_) if self.windows.get_mut().contains_key(&crate::make_wid(surface)) => {
+                    surface
+                },
                 Some(data) => data.parent_surface().unwrap_or(surface),
                 None => continue,
             };
@@ -173,6 +177,7 @@ impl PointerHandler for WinitState {
                 | ref kind @ PointerEventKind::Release { button, serial, .. } => {
                     // Update the last button serial.
                     pointer.winit_data().inner.lock().unwrap().latest_button_serial = serial;
+                    seat_state.latest_serial = Some(serial);
 
                     let button = wayland_button_to_winit(button);
                     let state = if matches!(kind, PointerEventKind::Press { .. }) {
@@ -191,7 +196,7 @@ impl PointerHandler for WinitState {
                         window_id,
                     );
                 },
-                PointerEventKind::Axis { horizontal, vertical, .. } => {
+                PointerEventKind::Axis { horizontal, vertical, source, .. } => {
                     // Get the current phase.
                     let mut pointer_data = pointer.winit_data().inner.lock().unwrap();
 
@@ -216,6 +221,23 @@ impl PointerHandler for WinitState {
                     // Update the phase.
                     pointer_data.phase = phase;
 
+                    let source = match source {
+                        Some(AxisSource::Wheel) => ScrollSource::Wheel,
+                        Some(AxisSource::Finger) => ScrollSource::Finger,
+                        Some(AxisSource::Continuous) => ScrollSource::Continuous,
+                        Some(AxisSource::WheelTilt) => ScrollSource::WheelTilt,
+                        _ => ScrollSource::Unknown,
+                    };
+
+                    // NOTE: Wayland sign convention is the inverse of winit.
+                    let value120 = if has_value120_scroll {
+                        Some((-horizontal.value120, -vertical.value120))
+                    } else if has_discrete_scroll {
+                        Some((-horizontal.discrete * 120, -vertical.discrete * 120))
+                    } else {
+                        None
+                    };
+
                     // Mice events have both pixel and discrete delta's at the same time. So prefer
                     // the discrete values if they are present.
                     let delta = if has_value120_scroll {
@@ -239,7 +261,7 @@ impl PointerHandler for WinitState {
                     };
 
                     self.events_sink.push_window_event(
-                        WindowEvent::MouseWheel { device_id: None, delta, phase },
+                        WindowEvent::MouseWheel { device_id: None, delta, phase, source, value120 },
                         window_id,
                     )
                 },
@@ -2,7 +2,6 @@ use std::ops::Deref;
 use std::sync::Mutex;
 
 use dpi::{LogicalPosition, PhysicalPosition};
-use sctk::compositor::SurfaceData;
 use sctk::globals::GlobalData;
 use sctk::reexports::client::globals::{BindError, GlobalList};
 use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle, delegate_dispatch};
@@ -87,14 +86,13 @@ impl Dispatch<ZwpPointerGesturePinchV1, PointerGestureData, WinitState> for Poin
                     return;
                 }
 
-                // Don't handle events from a subsurface.
-                if surface.data::<SurfaceData>().is_none_or(|data| data.parent_surface().is_some())
-                {
+                let window_id = crate::make_wid(&surface);
+
+                // Don't handle events from the decorations.
+                if !state.windows.get_mut().contains_key(&window_id) {
                     return;
                 }
 
-                let window_id = crate::make_wid(&surface);
-
                 pointer_gesture_data.window_id = Some(window_id);
                 pointer_gesture_data.previous_pinch = 1.;
 
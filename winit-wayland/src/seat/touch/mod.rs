@@ -19,7 +19,7 @@ impl TouchHandler for WinitState {
         _: &Connection,
         _: &QueueHandle<Self>,
         touch: &WlTouch,
-        _: u32,
+        serial: u32,
         _: u32,
         surface: WlSurface,
         id: i32,
@@ -38,6 +38,7 @@ impl TouchHandler for WinitState {
                 return;
             },
         };
+        seat_state.latest_serial = Some(serial);
 
         // Update the state of the point.
         let location = LogicalPosition::<f64>::from(position);
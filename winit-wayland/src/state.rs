@@ -4,7 +4,9 @@ use std::sync::{Arc, Mutex};
 
 use foldhash::HashMap;
 use sctk::compositor::{CompositorHandler, CompositorState};
+use sctk::data_device_manager::DataDeviceManagerState;
 use sctk::output::{OutputHandler, OutputState};
+use sctk::primary_selection::PrimarySelectionManagerState;
 use sctk::reexports::calloop::LoopHandle;
 use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::globals::GlobalList;
@@ -15,25 +17,37 @@ use sctk::registry::{ProvidesRegistryState, RegistryState};
 use sctk::seat::SeatState;
 use sctk::seat::pointer::ThemedPointer;
 use sctk::shell::WaylandSurface;
+use sctk::shell::wlr_layer::{LayerShell, LayerShellHandler, LayerSurface, LayerSurfaceConfigure};
 use sctk::shell::xdg::XdgShell;
+use sctk::shell::xdg::popup::{Popup, PopupConfigure, PopupHandler};
 use sctk::shell::xdg::window::{Window, WindowConfigure, WindowHandler};
 use sctk::shm::slot::SlotPool;
 use sctk::shm::{Shm, ShmHandler};
 use sctk::subcompositor::SubcompositorState;
 use winit_core::error::OsError;
+use winit_core::event::WindowEvent;
+use winit_core::monitor::{MonitorEvent, MonitorHandle as CoreMonitorHandle};
 
 use crate::WindowId;
+use crate::data_device::{ClipboardState, DragSourceState, DropState};
 use crate::event_loop::sink::EventSink;
 use crate::output::MonitorHandle;
+use crate::primary_selection::PrimarySelectionState;
 use crate::seat::{
     PointerConstraintsState, PointerGesturesState, RelativePointerState, TextInputState,
     WinitPointerData, WinitPointerDataExt, WinitSeatState,
 };
 use crate::types::kwin_blur::KWinBlurManager;
+use crate::types::wp_content_type::ContentTypeManager;
 use crate::types::wp_fractional_scaling::FractionalScalingManager;
-use crate::types::wp_tablet_input_v2::TabletManager;
+use crate::types::wp_idle_inhibit::IdleInhibitManager;
+use crate::types::wp_keyboard_shortcuts_inhibit::KeyboardShortcutsInhibitManager;
+use crate::types::wp_presentation::PresentationState;
+use crate::types::wp_tablet_input_v2::{TabletDevice, TabletManager};
+use crate::types::wp_tearing_control::TearingControlManager;
 use crate::types::wp_viewporter::ViewporterState;
 use crate::types::xdg_activation::XdgActivationState;
+use crate::types::xdg_dialog::XdgDialogManager;
 use crate::types::xdg_toplevel_icon_manager::XdgToplevelIconManagerState;
 use crate::window::{WindowRequests, WindowState};
 
@@ -61,9 +75,15 @@ pub struct WinitState {
     /// The XDG shell that is used for windows.
     pub xdg_shell: XdgShell,
 
+    /// The wlr layer shell that is used for layer surfaces.
+    pub layer_shell: Option<LayerShell>,
+
     /// The currently present windows.
     pub windows: RefCell<HashMap<WindowId, Arc<Mutex<WindowState>>>>,
 
+    /// The toplevels of the present windows, to tie dialogs to their owner.
+    pub toplevels: Arc<Mutex<HashMap<WindowId, Window>>>,
+
     /// The requests from the `Window` to EventLoop, such as close operations and redraw requests.
     pub window_requests: RefCell<HashMap<WindowId, Arc<WindowRequests>>>,
 
@@ -89,6 +109,24 @@ pub struct WinitState {
     /// event loop run.
     pub events_sink: EventSink,
 
+    /// The data device manager, used for the clipboard.
+    pub data_device_manager_state: Option<DataDeviceManagerState>,
+
+    /// The clipboard contents we're offering.
+    pub clipboard: ClipboardState,
+
+    /// The drag out of one of our windows, while it's in progress.
+    pub drag_source: Option<DragSourceState>,
+
+    /// The drags over our windows, by data device.
+    pub drops: HashMap<ObjectId, DropState>,
+
+    /// The primary selection device manager.
+    pub primary_selection_manager_state: Option<PrimarySelectionManagerState>,
+
+    /// The primary selection contents we're offering.
+    pub primary_selection: PrimarySelectionState,
+
     /// Xdg activation.
     pub xdg_activation: Option<XdgActivationState>,
 
@@ -104,6 +142,9 @@ pub struct WinitState {
     /// Tablet manager.
     pub tablet_state: Option<TabletManager>,
 
+    /// The tablets announced on our seats.
+    pub tablets: HashMap<ObjectId, TabletDevice>,
+
     /// Pointer constraints to handle pointer locking and confining.
     pub pointer_constraints: Option<Arc<PointerConstraintsState>>,
 
@@ -119,6 +160,24 @@ pub struct WinitState {
     /// KWin blur manager.
     pub kwin_blur_manager: Option<KWinBlurManager>,
 
+    /// Idle inhibit manager.
+    pub idle_inhibit_manager: Option<IdleInhibitManager>,
+
+    /// Content type manager.
+    pub content_type_manager: Option<ContentTypeManager>,
+
+    /// Tearing control manager.
+    pub tearing_control_manager: Option<TearingControlManager>,
+
+    /// Keyboard shortcuts inhibit manager.
+    pub keyboard_shortcuts_inhibit_manager: Option<KeyboardShortcutsInhibitManager>,
+
+    /// Xdg dialog manager.
+    pub xdg_dialog_manager: Option<XdgDialogManager>,
+
+    /// Presentation time to report when frames are presented.
+    pub presentation: Option<PresentationState>,
+
     /// Loop handle to re-register event sources, such as keyboard repeat.
     pub loop_handle: LoopHandle<'static, Self>,
 
@@ -180,6 +239,17 @@ impl WinitState {
             shm,
 
             xdg_shell: XdgShell::bind(globals, queue_handle).map_err(|err| os_error!(err))?,
+            layer_shell: LayerShell::bind(globals, queue_handle).ok(),
+            data_device_manager_state: DataDeviceManagerState::bind(globals, queue_handle).ok(),
+            clipboard: Default::default(),
+            drag_source: None,
+            drops: Default::default(),
+            primary_selection_manager_state: PrimarySelectionManagerState::bind(
+                globals,
+                queue_handle,
+            )
+            .ok(),
+            primary_selection: Default::default(),
             xdg_activation: XdgActivationState::bind(globals, queue_handle).ok(),
             xdg_toplevel_icon_manager: XdgToplevelIconManagerState::bind(globals, queue_handle)
                 .ok(),
@@ -187,18 +257,30 @@ impl WinitState {
             image_pool,
 
             windows: Default::default(),
+            toplevels: Default::default(),
             window_requests: Default::default(),
             window_compositor_updates: Vec::new(),
             window_events_sink: Default::default(),
             viewporter_state,
             fractional_scaling_manager,
             kwin_blur_manager: KWinBlurManager::new(globals, queue_handle).ok(),
+            idle_inhibit_manager: IdleInhibitManager::new(globals, queue_handle).ok(),
+            content_type_manager: ContentTypeManager::new(globals, queue_handle).ok(),
+            tearing_control_manager: TearingControlManager::new(globals, queue_handle).ok(),
+            keyboard_shortcuts_inhibit_manager: KeyboardShortcutsInhibitManager::new(
+                globals,
+                queue_handle,
+            )
+            .ok(),
+            xdg_dialog_manager: XdgDialogManager::new(globals, queue_handle).ok(),
+            presentation: PresentationState::bind(globals, queue_handle).ok(),
 
             seats,
             text_input_state: TextInputState::new(globals, queue_handle).ok(),
 
             relative_pointer: RelativePointerState::new(globals, queue_handle).ok(),
             tablet_state: TabletManager::new(globals, queue_handle).ok(),
+            tablets: Default::default(),
             pointer_constraints: PointerConstraintsState::new(globals, queue_handle)
                 .map(Arc::new)
                 .ok(),
@@ -268,6 +350,35 @@ impl WinitState {
 
         updates[pos].close_window = true;
     }
+
+    /// Destroy the windows which were dropped, returning their ids.
+    ///
+    /// A popup must be destroyed before its parent, so a parent is kept until its popups are
+    /// dropped too.
+    pub fn destroy_closed_windows(&mut self) -> Vec<WindowId> {
+        let windows = self.windows.get_mut();
+        let window_requests = self.window_requests.get_mut();
+
+        let mut destroyed = Vec::new();
+        loop {
+            let closed = window_requests
+                .iter()
+                .filter(|(_, requests)| requests.is_closed())
+                .map(|(&window_id, _)| window_id)
+                .find(|&window_id| {
+                    !windows
+                        .values()
+                        .any(|window| window.lock().unwrap().popup_parent() == Some(window_id))
+                });
+            let Some(window_id) = closed else { break };
+
+            drop(window_requests.remove(&window_id));
+            drop(windows.remove(&window_id));
+            destroyed.push(window_id);
+        }
+
+        destroyed
+    }
 }
 
 impl ShmHandler for WinitState {
@@ -291,7 +402,74 @@ impl WindowHandler for WinitState {
         _serial: u32,
     ) {
         let window_id = super::make_wid(window.wl_surface());
+        let resized = self
+            .windows
+            .get_mut()
+            .get_mut(&window_id)
+            .expect("got configure for dead window.")
+            .lock()
+            .unwrap()
+            .configure(configure, &self.shm, &self.subcompositor_state);
+        self.window_configured(window_id, resized);
+    }
+}
 
+impl PopupHandler for WinitState {
+    fn configure(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        popup: &Popup,
+        configure: PopupConfigure,
+    ) {
+        let window_id = super::make_wid(popup.wl_surface());
+        let resized = self
+            .windows
+            .get_mut()
+            .get_mut(&window_id)
+            .expect("got configure for dead popup.")
+            .lock()
+            .unwrap()
+            .configure_popup(configure);
+        self.window_configured(window_id, resized);
+    }
+
+    fn done(&mut self, _: &Connection, _: &QueueHandle<Self>, popup: &Popup) {
+        let window_id = super::make_wid(popup.wl_surface());
+        self.events_sink.push_window_event(WindowEvent::PopupDismissed, window_id);
+    }
+}
+
+impl LayerShellHandler for WinitState {
+    fn closed(&mut self, _: &Connection, _: &QueueHandle<Self>, layer: &LayerSurface) {
+        let window_id = super::make_wid(layer.wl_surface());
+        Self::queue_close(&mut self.window_compositor_updates, window_id);
+    }
+
+    fn configure(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        layer: &LayerSurface,
+        configure: LayerSurfaceConfigure,
+        _serial: u32,
+    ) {
+        let window_id = super::make_wid(layer.wl_surface());
+        let resized = self
+            .windows
+            .get_mut()
+            .get_mut(&window_id)
+            .expect("got configure for dead layer surface.")
+            .lock()
+            .unwrap()
+            .configure_layer(configure);
+        self.window_configured(window_id, resized);
+    }
+}
+
+impl WinitState {
+    /// Queue the updates after the window was configured.
+    fn window_configured(&mut self, window_id: WindowId, resized: bool) {
         let pos = if let Some(pos) =
             self.window_compositor_updates.iter().position(|update| update.window_id == window_id)
         {
@@ -301,15 +479,7 @@ impl WindowHandler for WinitState {
             self.window_compositor_updates.len() - 1
         };
 
-        // Populate the configure to the window.
-        self.window_compositor_updates[pos].resized |= self
-            .windows
-            .get_mut()
-            .get_mut(&window_id)
-            .expect("got configure for dead window.")
-            .lock()
-            .unwrap()
-            .configure(configure, &self.shm, &self.subcompositor_state);
+        self.window_compositor_updates[pos].resized |= resized;
 
         // NOTE: configure demands wl_surface::commit, however winit doesn't commit on behalf of the
         // users, since it can break a lot of things, thus it'll ask users to redraw instead.
@@ -331,17 +501,22 @@ impl OutputHandler for WinitState {
     }
 
     fn new_output(&mut self, _: &Connection, _: &QueueHandle<Self>, output: WlOutput) {
-        self.monitors.lock().unwrap().push(MonitorHandle::new(output));
+        let monitor = MonitorHandle::new(output);
+        self.monitors.lock().unwrap().push(monitor.clone());
+        self.events_sink
+            .push_monitor_event(MonitorEvent::Added(CoreMonitorHandle(Arc::new(monitor))));
     }
 
     fn update_output(&mut self, _: &Connection, _: &QueueHandle<Self>, updated: WlOutput) {
         let mut monitors = self.monitors.lock().unwrap();
         let updated = MonitorHandle::new(updated);
         if let Some(pos) = monitors.iter().position(|output| output == &updated) {
-            monitors[pos] = updated
+            monitors[pos] = updated.clone()
         } else {
-            monitors.push(updated)
+            monitors.push(updated.clone())
         }
+        self.events_sink
+            .push_monitor_event(MonitorEvent::Changed(CoreMonitorHandle(Arc::new(updated))));
     }
 
     fn output_destroyed(&mut self, _: &Connection, _: &QueueHandle<Self>, removed: WlOutput) {
@@ -350,6 +525,8 @@ impl OutputHandler for WinitState {
         if let Some(pos) = monitors.iter().position(|output| output == &removed) {
             monitors.remove(pos);
         }
+        self.events_sink
+            .push_monitor_event(MonitorEvent::Removed(CoreMonitorHandle(Arc::new(removed))));
     }
 }
 
@@ -452,3 +629,5 @@ sctk::delegate_registry!(WinitState);
 sctk::delegate_shm!(WinitState);
 sctk::delegate_xdg_shell!(WinitState);
 sctk::delegate_xdg_window!(WinitState);
+sctk::delegate_xdg_popup!(WinitState);
+sctk::delegate_layer!(WinitState);
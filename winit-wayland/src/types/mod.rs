@@ -2,8 +2,14 @@
 
 pub mod cursor;
 pub mod kwin_blur;
+pub mod wp_content_type;
 pub mod wp_fractional_scaling;
+pub mod wp_idle_inhibit;
+pub mod wp_keyboard_shortcuts_inhibit;
+pub mod wp_presentation;
 pub mod wp_tablet_input_v2;
+pub mod wp_tearing_control;
 pub mod wp_viewporter;
 pub mod xdg_activation;
+pub mod xdg_dialog;
 pub mod xdg_toplevel_icon_manager;
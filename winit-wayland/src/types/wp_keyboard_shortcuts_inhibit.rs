@@ -0,0 +1,87 @@
+//! Handling of the keyboard shortcuts inhibit protocol, used to grab the keyboard.
+
+use sctk::globals::GlobalData;
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle, delegate_dispatch};
+use sctk::reexports::protocols::wp::keyboard_shortcuts_inhibit::zv1::client::zwp_keyboard_shortcuts_inhibit_manager_v1::ZwpKeyboardShortcutsInhibitManagerV1;
+use sctk::reexports::protocols::wp::keyboard_shortcuts_inhibit::zv1::client::zwp_keyboard_shortcuts_inhibitor_v1::{
+    Event as InhibitorEvent, ZwpKeyboardShortcutsInhibitorV1,
+};
+use winit_core::event::WindowEvent;
+use winit_core::window::WindowId;
+
+use crate::state::WinitState;
+
+/// Keyboard shortcuts inhibit manager.
+#[derive(Debug, Clone)]
+pub struct KeyboardShortcutsInhibitManager {
+    manager: ZwpKeyboardShortcutsInhibitManagerV1,
+}
+
+impl KeyboardShortcutsInhibitManager {
+    pub fn new(
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Result<Self, BindError> {
+        let manager = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        Ok(Self { manager })
+    }
+
+    pub fn inhibit(
+        &self,
+        surface: &WlSurface,
+        seat: &WlSeat,
+        window_id: WindowId,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> ZwpKeyboardShortcutsInhibitorV1 {
+        self.manager.inhibit_shortcuts(surface, seat, queue_handle, window_id)
+    }
+}
+
+impl Dispatch<ZwpKeyboardShortcutsInhibitManagerV1, GlobalData, WinitState>
+    for KeyboardShortcutsInhibitManager
+{
+    fn event(
+        _: &mut WinitState,
+        _: &ZwpKeyboardShortcutsInhibitManagerV1,
+        _: <ZwpKeyboardShortcutsInhibitManagerV1 as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        unreachable!("no events defined for zwp_keyboard_shortcuts_inhibit_manager_v1");
+    }
+}
+
+impl Dispatch<ZwpKeyboardShortcutsInhibitorV1, WindowId, WinitState>
+    for KeyboardShortcutsInhibitManager
+{
+    fn event(
+        state: &mut WinitState,
+        inhibitor: &ZwpKeyboardShortcutsInhibitorV1,
+        event: <ZwpKeyboardShortcutsInhibitorV1 as Proxy>::Event,
+        window_id: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        let active = match event {
+            InhibitorEvent::Active => true,
+            InhibitorEvent::Inactive => false,
+            _ => return,
+        };
+
+        let changed = match state.windows.get_mut().get(window_id) {
+            Some(window) => window.lock().unwrap().set_shortcuts_inhibited(&inhibitor.id(), active),
+            None => return,
+        };
+
+        if changed {
+            state.events_sink.push_window_event(WindowEvent::KeyboardGrabbed(active), *window_id);
+        }
+    }
+}
+
+delegate_dispatch!(WinitState: [ZwpKeyboardShortcutsInhibitManagerV1: GlobalData] => KeyboardShortcutsInhibitManager);
+delegate_dispatch!(WinitState: [ZwpKeyboardShortcutsInhibitorV1: WindowId] => KeyboardShortcutsInhibitManager);
@@ -0,0 +1,132 @@
+//! Handling of wp-presentation, which reports when frames are presented.
+
+use std::time::Duration;
+
+use sctk::globals::GlobalData;
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle, delegate_dispatch};
+use sctk::reexports::protocols::wp::presentation_time::client::wp_presentation::{
+    Event as PresentationEvent, WpPresentation,
+};
+use sctk::reexports::protocols::wp::presentation_time::client::wp_presentation_feedback::{
+    Event as FeedbackEvent, WpPresentationFeedback,
+};
+use winit_core::event::{FramePresentation, WindowEvent};
+use winit_core::window::WindowId;
+
+use crate::state::WinitState;
+
+#[derive(Debug)]
+pub struct PresentationState {
+    presentation: WpPresentation,
+    /// The clock of the timestamps, announced by the compositor right after binding.
+    clock: libc::clockid_t,
+}
+
+impl PresentationState {
+    pub fn bind(
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Result<Self, BindError> {
+        let presentation = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        Ok(Self { presentation, clock: libc::CLOCK_MONOTONIC })
+    }
+
+    pub fn global(&self) -> &WpPresentation {
+        &self.presentation
+    }
+}
+
+impl Dispatch<WpPresentation, GlobalData, WinitState> for PresentationState {
+    fn event(
+        state: &mut WinitState,
+        _: &WpPresentation,
+        event: <WpPresentation as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        let PresentationEvent::ClockId { clk_id } = event else {
+            return;
+        };
+
+        if let Some(presentation) = state.presentation.as_mut() {
+            presentation.clock = clk_id as libc::clockid_t;
+        }
+    }
+}
+
+impl Dispatch<WpPresentationFeedback, WindowId, WinitState> for PresentationState {
+    fn event(
+        state: &mut WinitState,
+        _: &WpPresentationFeedback,
+        event: <WpPresentationFeedback as Proxy>::Event,
+        window_id: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        // The feedback is destroyed by the compositor after `presented` or `discarded`.
+        let FeedbackEvent::Presented {
+            tv_sec_hi, tv_sec_lo, tv_nsec, refresh, seq_hi, seq_lo, ..
+        } = event
+        else {
+            return;
+        };
+
+        let secs = (u64::from(tv_sec_hi) << 32) | u64::from(tv_sec_lo);
+        let sequence = (u64::from(seq_hi) << 32) | u64::from(seq_lo);
+        let clock = state.presentation.as_ref().map_or(libc::CLOCK_MONOTONIC, |p| p.clock);
+        let presentation = FramePresentation {
+            timestamp: to_monotonic(Duration::new(secs, tv_nsec), clock),
+            // Both are zero when unknown.
+            refresh_interval: (refresh != 0).then(|| Duration::from_nanos(refresh.into())),
+            sequence: (sequence != 0).then_some(sequence),
+        };
+        state.events_sink.push_window_event(WindowEvent::FramePresented(presentation), *window_id);
+    }
+}
+
+/// Convert a `timestamp` of `clock` to `CLOCK_MONOTONIC`, by the current offset between both.
+fn to_monotonic(timestamp: Duration, clock: libc::clockid_t) -> Duration {
+    if clock == libc::CLOCK_MONOTONIC {
+        return timestamp;
+    }
+
+    match (now(libc::CLOCK_MONOTONIC), now(clock)) {
+        (Some(monotonic), Some(now)) if now <= monotonic => timestamp + (monotonic - now),
+        (Some(monotonic), Some(now)) => timestamp.saturating_sub(now - monotonic),
+        _ => timestamp,
+    }
+}
+
+fn now(clock: libc::clockid_t) -> Option<Duration> {
+    let mut time = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    if unsafe { libc::clock_gettime(clock, &mut time) } != 0 {
+        return None;
+    }
+
+    Some(Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+}
+
+delegate_dispatch!(WinitState: [WpPresentation: GlobalData] => PresentationState);
+delegate_dispatch!(WinitState: [WpPresentationFeedback: WindowId] => PresentationState);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn monotonic_timestamps_are_kept() {
+        let timestamp = Duration::new(12, 345);
+        assert_eq!(to_monotonic(timestamp, libc::CLOCK_MONOTONIC), timestamp);
+    }
+
+    #[test]
+    fn other_clocks_are_converted_to_monotonic() {
+        let realtime = now(libc::CLOCK_REALTIME).unwrap();
+        let converted = to_monotonic(realtime, libc::CLOCK_REALTIME);
+        let monotonic = now(libc::CLOCK_MONOTONIC).unwrap();
+        assert!(converted <= monotonic);
+        assert!(monotonic - converted < Duration::from_secs(1));
+    }
+}
@@ -3,8 +3,8 @@
 use std::sync::Mutex;
 
 use dpi::LogicalPosition;
-use sctk::compositor::SurfaceData;
 use sctk::globals::GlobalData;
+use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::backend::smallvec::SmallVec;
 use sctk::reexports::client::globals::{BindError, GlobalList};
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
@@ -20,12 +20,13 @@ use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_seat_v2::{
 use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_tool_v2::{
     ButtonState, Event as ToolEvent, Type as ToolType, ZwpTabletToolV2,
 };
-use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_v2::ZwpTabletV2;
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_v2::{self, ZwpTabletV2};
 use winit_core::event::{
-    ButtonSource, ElementState, Force, PointerKind, PointerSource, TabletToolButton,
+    ButtonSource, DeviceEvent, ElementState, Force, PointerKind, PointerSource, TabletToolButton,
     TabletToolData as CoreTabletToolData, TabletToolKind, TabletToolTilt, WindowEvent,
 };
 
+use crate::make_did;
 use crate::state::WinitState;
 
 /// KWin blur manager.
@@ -49,7 +50,41 @@ impl TabletManager {
         seat: &WlSeat,
         queue_handle: &QueueHandle<WinitState>,
     ) -> ZwpTabletSeatV2 {
-        self.manager.get_tablet_seat(seat, queue_handle, ())
+        self.manager.get_tablet_seat(seat, queue_handle, seat.id())
+    }
+}
+
+/// A tablet announced on one of our seats.
+#[derive(Debug)]
+pub struct TabletDevice {
+    tablet: ZwpTabletV2,
+
+    /// The seat the tablet is attached to.
+    pub seat: ObjectId,
+
+    /// The name of the tablet, once the compositor sent it.
+    pub name: Option<String>,
+}
+
+impl TabletDevice {
+    pub fn tablet(&self) -> &ZwpTabletV2 {
+        &self.tablet
+    }
+}
+
+impl WinitState {
+    /// Forget the tablets of `seat`, after its tablet seat was destroyed.
+    pub fn remove_tablets(&mut self, seat: &ObjectId) {
+        let events_sink = &mut self.events_sink;
+        self.tablets.retain(|_, device| {
+            if &device.seat != seat {
+                return true;
+            }
+
+            events_sink.push_device_event(DeviceEvent::Removed, Some(make_did(&device.tablet)));
+            device.tablet.destroy();
+            false
+        });
     }
 }
 impl Dispatch<ZwpTabletManagerV2, GlobalData, WinitState> for TabletManager {
@@ -78,7 +113,7 @@ impl Dispatch<ZwpTabletManagerV2, (), WinitState> for TabletManager {
     }
 }
 
-impl Dispatch<ZwpTabletSeatV2, (), WinitState> for TabletManager {
+impl Dispatch<ZwpTabletSeatV2, ObjectId, WinitState> for TabletManager {
     event_created_child!(WinitState, ZwpTabletSeatV2, [
         zwp_tablet_seat_v2::EVT_TABLET_ADDED_OPCODE => (ZwpTabletV2, Default::default()),
         zwp_tablet_seat_v2::EVT_TOOL_ADDED_OPCODE => (ZwpTabletToolV2, Default::default()),
@@ -86,13 +121,17 @@ impl Dispatch<ZwpTabletSeatV2, (), WinitState> for TabletManager {
     ]);
 
     fn event(
-        _: &mut WinitState,
+        state: &mut WinitState,
         _: &ZwpTabletSeatV2,
-        _: <ZwpTabletSeatV2 as Proxy>::Event,
-        _: &(),
+        event: <ZwpTabletSeatV2 as Proxy>::Event,
+        seat: &ObjectId,
         _: &Connection,
         _: &QueueHandle<WinitState>,
     ) {
+        if let zwp_tablet_seat_v2::Event::TabletAdded { id } = event {
+            let device = TabletDevice { tablet: id, seat: seat.clone(), name: None };
+            state.tablets.insert(device.tablet.id(), device);
+        }
     }
 }
 
@@ -187,23 +226,10 @@ impl Dispatch<ZwpTabletToolV2, TabletToolData, WinitState> for TabletManager {
                         data.surface = Some(surface.clone());
                     }
 
-                    // Handle events only for top-level surface.
-                    let surface = match data
-                        .surface
-                        .as_ref()
-                        .map(|surface| (surface, surface.data::<SurfaceData>()))
-                    {
-                        Some((surface, Some(surface_data)))
-                            if surface_data.parent_surface().is_none() =>
-                        {
-                            surface
-                        },
-                        _ => continue,
-                    };
-
+                    let Some(surface) = data.surface.as_ref() else { continue };
                     let window_id = crate::make_wid(surface);
 
-                    // Ensure that window exists.
+                    // Ensure that window exists, which skips the decorations.
                     let window = match state.windows.get_mut().get_mut(&window_id) {
                         Some(window) => window.lock().unwrap(),
                         None => continue,
@@ -313,13 +339,30 @@ pub(crate) enum TabletEvent {
 
 impl Dispatch<ZwpTabletV2, (), WinitState> for TabletManager {
     fn event(
-        _: &mut WinitState,
-        _: &ZwpTabletV2,
-        _: <ZwpTabletV2 as Proxy>::Event,
+        state: &mut WinitState,
+        tablet: &ZwpTabletV2,
+        event: <ZwpTabletV2 as Proxy>::Event,
         _: &(),
         _: &Connection,
         _: &QueueHandle<WinitState>,
     ) {
+        let device = match state.tablets.get_mut(&tablet.id()) {
+            Some(device) => device,
+            None => return,
+        };
+
+        match event {
+            zwp_tablet_v2::Event::Name { name } => device.name = Some(name),
+            zwp_tablet_v2::Event::Done => {
+                state.events_sink.push_device_event(DeviceEvent::Added, Some(make_did(tablet)));
+            },
+            zwp_tablet_v2::Event::Removed => {
+                state.events_sink.push_device_event(DeviceEvent::Removed, Some(make_did(tablet)));
+                state.tablets.remove(&tablet.id());
+                tablet.destroy();
+            },
+            _ => (),
+        }
     }
 }
 
@@ -337,7 +380,7 @@ impl Dispatch<ZwpTabletPadV2, (), WinitState> for TabletManager {
 
 delegate_dispatch!(WinitState: [ZwpTabletManagerV2: GlobalData] => TabletManager);
 delegate_dispatch!(WinitState: [ZwpTabletManagerV2: ()] => TabletManager);
-delegate_dispatch!(WinitState: [ZwpTabletSeatV2: ()] => TabletManager);
+delegate_dispatch!(WinitState: [ZwpTabletSeatV2: ObjectId] => TabletManager);
 delegate_dispatch!(WinitState: [ZwpTabletV2: ()] => TabletManager);
 delegate_dispatch!(WinitState: [ZwpTabletToolV2: TabletToolData] => TabletManager);
 delegate_dispatch!(WinitState: [ZwpTabletPadV2: ()] => TabletManager);
@@ -0,0 +1,63 @@
+//! Handling of the tearing control protocol, which allows asynchronous presentation.
+
+use sctk::globals::GlobalData;
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle, delegate_dispatch};
+use sctk::reexports::protocols::wp::tearing_control::v1::client::wp_tearing_control_manager_v1::WpTearingControlManagerV1;
+use sctk::reexports::protocols::wp::tearing_control::v1::client::wp_tearing_control_v1::WpTearingControlV1;
+
+use crate::state::WinitState;
+
+/// Tearing control manager.
+#[derive(Debug, Clone)]
+pub struct TearingControlManager {
+    manager: WpTearingControlManagerV1,
+}
+
+impl TearingControlManager {
+    pub fn new(
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Result<Self, BindError> {
+        let manager = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        Ok(Self { manager })
+    }
+
+    pub fn tearing_control(
+        &self,
+        surface: &WlSurface,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> WpTearingControlV1 {
+        self.manager.get_tearing_control(surface, queue_handle, ())
+    }
+}
+
+impl Dispatch<WpTearingControlManagerV1, GlobalData, WinitState> for TearingControlManager {
+    fn event(
+        _: &mut WinitState,
+        _: &WpTearingControlManagerV1,
+        _: <WpTearingControlManagerV1 as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        unreachable!("no events defined for wp_tearing_control_manager_v1");
+    }
+}
+
+impl Dispatch<WpTearingControlV1, (), WinitState> for TearingControlManager {
+    fn event(
+        _: &mut WinitState,
+        _: &WpTearingControlV1,
+        _: <WpTearingControlV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        unreachable!("no events defined for wp_tearing_control_v1");
+    }
+}
+
+delegate_dispatch!(WinitState: [WpTearingControlManagerV1: GlobalData] => TearingControlManager);
+delegate_dispatch!(WinitState: [WpTearingControlV1: ()] => TearingControlManager);
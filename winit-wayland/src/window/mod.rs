@@ -1,46 +1,58 @@
 //! The Wayland window.
 
 use std::ffi::c_void;
+use std::mem;
 use std::ptr::NonNull;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use dpi::{LogicalSize, PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
+use foldhash::HashMap;
 use sctk::compositor::{CompositorState, Region, SurfaceData};
 use sctk::reexports::client::protocol::wl_display::WlDisplay;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Proxy, QueueHandle};
+use sctk::reexports::protocols::wp::presentation_time::client::wp_presentation::WpPresentation;
 use sctk::reexports::protocols::xdg::activation::v1::client::xdg_activation_v1::XdgActivationV1;
-use sctk::shell::WaylandSurface;
-use sctk::shell::xdg::window::{Window as SctkWindow, WindowDecorations};
+use sctk::shell::wlr_layer::{
+    Anchor, KeyboardInteractivity as SctkKeyboardInteractivity, Layer as SctkLayer, LayerShell,
+    LayerSurface,
+};
+use sctk::shell::xdg::popup::Popup;
+use sctk::shell::xdg::window::{Window as XdgWindow, WindowDecorations};
 use tracing::warn;
+use winit_core::clipboard::ClipboardData;
 use winit_core::cursor::Cursor;
+use winit_core::drag::{DragAction, DragActions};
 use winit_core::error::{NotSupportedError, RequestError};
 use winit_core::event::{Ime, WindowEvent};
 use winit_core::event_loop::AsyncRequestSerial;
-use winit_core::monitor::{Fullscreen, MonitorHandle as CoreMonitorHandle};
+use winit_core::monitor::{Fullscreen, MonitorHandle as CoreMonitorHandle, MonitorHandleProvider};
 use winit_core::window::{
-    CursorGrabMode, ImeCapabilities, ImeRequest, ImeRequestError, ResizeDirection, Theme,
-    UserAttentionType, Window as CoreWindow, WindowAttributes, WindowButtons, WindowId,
+    ContentType, CursorGrabMode, ImeCapabilities, ImeRequest, ImeRequestError, ResizeDirection,
+    Theme, UserAttentionType, Window as CoreWindow, WindowAttributes, WindowButtons, WindowId,
     WindowLevel,
 };
 
 use super::ActiveEventLoop;
+use super::data_device::DragRequest;
 use super::event_loop::sink::EventSink;
 use super::output::MonitorHandle;
 use super::state::WinitState;
 use super::types::xdg_activation::XdgActivationTokenData;
-use crate::{WindowAttributesWayland, output};
+use crate::{
+    KeyboardInteractivity, Layer, LayerSurfaceAttributes, WindowAttributesWayland, output,
+};
 
 pub(crate) mod state;
 
-pub use state::WindowState;
+pub use state::{WindowRole, WindowState};
 
 /// The Wayland window.
 #[derive(Debug)]
 pub struct Window {
-    /// Reference to the underlying SCTK window.
-    window: SctkWindow,
+    /// Reference to the underlying SCTK window or popup.
+    window: WindowRole,
 
     /// Window id.
     window_id: WindowId,
@@ -58,6 +70,9 @@ pub struct Window {
     /// Xdg activation to request user attention.
     xdg_activation: Option<XdgActivationV1>,
 
+    /// Presentation time to request the feedback of frames.
+    presentation: Option<WpPresentation>,
+
     /// The state of the requested attention from the `xdg_activation`.
     attention_requested: Arc<AtomicBool>,
 
@@ -75,6 +90,13 @@ pub struct Window {
 
     /// The event sink to deliver synthetic events.
     window_events_sink: Arc<Mutex<EventSink>>,
+
+    /// Window requests of the parent of a child window, which applies the position of the child
+    /// when it's redrawn.
+    parent_requests: Option<Arc<WindowRequests>>,
+
+    /// The toplevels of the windows of the event loop, to find the owner of a dialog.
+    toplevels: Arc<Mutex<HashMap<WindowId, XdgWindow>>>,
 }
 
 impl Window {
@@ -87,13 +109,13 @@ impl Window {
 
         let monitors = state.monitors.clone();
 
-        let surface = state.compositor_state.create_surface(&queue_handle);
         let compositor = state.compositor_state.clone();
         let xdg_activation =
             state.xdg_activation.as_ref().map(|activation_state| activation_state.global().clone());
+        let presentation = state.presentation.as_ref().map(|state| state.global().clone());
         let display = event_loop_window_target.handle.connection.display();
 
-        let size: Size = attributes.surface_size.unwrap_or(LogicalSize::new(800., 600.).into());
+        let mut size: Size = attributes.surface_size.unwrap_or(LogicalSize::new(800., 600.).into());
 
         // We prefer server side decorations, however to not have decorations we ask for client
         // side decorations instead.
@@ -103,15 +125,134 @@ impl Window {
             WindowDecorations::RequestClient
         };
 
-        let window =
-            state.xdg_shell.create_window(surface.clone(), default_decorations, &queue_handle);
-
-        let WindowAttributesWayland { name: app_name, activation_token, prefer_csd } = *attributes
+        let WindowAttributesWayland {
+            name: app_name,
+            activation_token,
+            prefer_csd,
+            layer_surface,
+            synchronized_commits,
+        } = *attributes
             .platform
             .take()
             .and_then(|p| p.cast::<WindowAttributesWayland>().ok())
             .unwrap_or_default();
 
+        // Create the child window as a subsurface of its parent, popups have their own parent.
+        let child = match attributes.parent_window().filter(|_| attributes.popup.is_none()) {
+            Some(rwh_06::RawWindowHandle::Wayland(handle)) => {
+                let windows = state.windows.borrow();
+                let parent_id = WindowId::from_raw(handle.surface.as_ptr() as usize);
+                let parent = windows.get(&parent_id).ok_or_else(|| {
+                    NotSupportedError::new("the parent window is not a window of this event loop")
+                })?;
+                let subcompositor = state
+                    .subcompositor_state
+                    .as_ref()
+                    .ok_or_else(|| NotSupportedError::new("wl_subcompositor is not available"))?;
+                let parent = parent.lock().unwrap();
+                let child = state::ChildSurface::new(
+                    subcompositor,
+                    parent.window.wl_surface().clone(),
+                    &queue_handle,
+                );
+                child.set_synchronized(synchronized_commits);
+                let placement =
+                    state::ChildPlacement::new(attributes.position, parent.scale_factor());
+                let parent_requests = state.window_requests.borrow().get(&parent_id).cloned();
+                Some((child, placement, parent_requests))
+            },
+            Some(_) => {
+                return Err(
+                    NotSupportedError::new("the parent window is not a Wayland window").into()
+                );
+            },
+            None => None,
+        };
+
+        let surface = match &child {
+            Some((child, ..)) => child.wl_surface().clone(),
+            None => state.compositor_state.create_surface(&queue_handle),
+        };
+
+        // Place the popup relative to its parent.
+        let popup = match attributes.popup {
+            Some(popup_attributes) => {
+                let windows = state.windows.borrow();
+                let parent = windows.get(&popup_attributes.parent).ok_or_else(|| {
+                    NotSupportedError::new("the parent of the popup is not a Wayland window")
+                })?;
+                let parent = parent.lock().unwrap();
+                let placement = state::PopupPlacement::new(
+                    &state.xdg_shell,
+                    popup_attributes,
+                    &parent,
+                    size.to_logical(parent.scale_factor()),
+                );
+                let positioner = placement.positioner().map_err(|err| os_error!(err))?;
+                let popup = Popup::from_surface(
+                    parent.window.xdg_surface(),
+                    &positioner,
+                    &queue_handle,
+                    surface.clone(),
+                    &state.xdg_shell,
+                )
+                .map_err(|err| os_error!(err))?;
+                if let WindowRole::Layer(layer) = &parent.window {
+                    layer.get_popup(popup.xdg_popup());
+                }
+
+                // The grab must use the serial of the input that opened the popup.
+                if popup_attributes.grab {
+                    match parent.latest_pointer_button() {
+                        Some((seat, serial)) => popup.xdg_popup().grab(&seat, serial),
+                        None => warn!("No input to grab the popup with"),
+                    }
+                }
+
+                Some((popup, placement))
+            },
+            None => None,
+        };
+
+        let mut layer_stretch = None;
+        let (window, popup_placement, child_placement) = match (popup, child, layer_surface) {
+            (Some((popup, placement)), ..) => (WindowRole::Popup(popup), Some(placement), None),
+            (None, Some((child, placement, parent_requests)), _) => {
+                (WindowRole::Child(child), None, Some((placement, parent_requests)))
+            },
+            (None, None, Some(layer_attributes)) => {
+                let layer_shell = state.layer_shell.as_ref().ok_or_else(|| {
+                    NotSupportedError::new("zwlr_layer_shell_v1 is not available")
+                })?;
+                // The surface doesn't know its output yet, use the scale of the one it's put on.
+                let scale_factor = match &layer_attributes.output {
+                    Some(output) => output.scale_factor(),
+                    None => monitors.lock().unwrap().first().map_or(1., |m| m.scale_factor()),
+                };
+                let logical_size = size.to_logical(scale_factor);
+                let stretch = state::LayerStretch::new(layer_attributes.anchor, logical_size);
+                let layer = create_layer_surface(
+                    layer_shell,
+                    &queue_handle,
+                    surface.clone(),
+                    layer_attributes,
+                    stretch.size(logical_size),
+                );
+                // Keep the size for the initial configure.
+                size = logical_size.into();
+                layer_stretch = Some(stretch);
+                (WindowRole::Layer(layer), None, None)
+            },
+            (None, None, None) => {
+                let window = state.xdg_shell.create_window(
+                    surface.clone(),
+                    default_decorations,
+                    &queue_handle,
+                );
+                (WindowRole::Toplevel(window), None, None)
+            },
+        };
+
         let mut window_state = WindowState::new(
             event_loop_window_target.handle.clone(),
             &event_loop_window_target.queue_handle,
@@ -122,6 +263,19 @@ impl Window {
             prefer_csd,
         );
 
+        if let Some(placement) = popup_placement {
+            window_state.set_popup_placement(placement);
+        }
+
+        if let Some(stretch) = layer_stretch {
+            window_state.set_layer_stretch(stretch);
+        }
+
+        let parent_requests = child_placement.and_then(|(placement, parent_requests)| {
+            window_state.configure_child(placement);
+            parent_requests
+        });
+
         window_state.set_window_icon(attributes.window_icon);
 
         // Set transparency hint.
@@ -129,14 +283,37 @@ impl Window {
 
         window_state.set_blur(attributes.blur);
 
+        // Set the presentation hints, which are ignored without the protocols.
+        if attributes.content_type != ContentType::None {
+            let _ = window_state.set_content_type(attributes.content_type);
+        }
+        if attributes.allow_tearing {
+            let _ = window_state.set_allow_tearing(true);
+        }
+
         // Set the decorations hint.
         window_state.set_decorate(attributes.decorations);
 
         // Set the app_id.
-        if let Some(name) = app_name.map(|name| name.general) {
+        if let (Some(name), Some(window)) = (app_name.map(|name| name.general), window.toplevel()) {
             window.set_app_id(name);
         }
 
+        // Tie the dialog to its owner.
+        if let (Some(owner), Some(window)) = (attributes.owner_window, window.toplevel()) {
+            let toplevels = state.toplevels.lock().unwrap();
+            let owner = toplevels.get(&owner).ok_or_else(|| {
+                NotSupportedError::new("the owner window is not a toplevel of this event loop")
+            })?;
+            window.set_parent(Some(owner));
+
+            if attributes.modal {
+                if let Err(err) = window_state.set_modal(true) {
+                    warn!("Failed to make the dialog modal: {err}");
+                }
+            }
+        }
+
         // Set the window title.
         window_state.set_title(attributes.title);
 
@@ -151,18 +328,19 @@ impl Window {
         window_state.set_resizable(attributes.resizable);
 
         // Set startup mode.
-        match attributes.fullscreen {
-            Some(Fullscreen::Exclusive(..)) => {
+        match (attributes.fullscreen, window.toplevel()) {
+            (_, None) => (),
+            (Some(Fullscreen::Exclusive(..)), _) => {
                 warn!("`Fullscreen::Exclusive` is ignored on Wayland");
             },
-            Some(Fullscreen::Borderless(monitor)) => {
+            (Some(Fullscreen::Borderless(monitor)), Some(window)) => {
                 let output = monitor.as_ref().and_then(|monitor| {
                     monitor.cast_ref::<output::MonitorHandle>().map(|handle| &handle.proxy)
                 });
 
                 window.set_fullscreen(output)
             },
-            _ if attributes.maximized => window.set_maximized(),
+            (_, Some(window)) if attributes.maximized => window.set_maximized(),
             _ => (),
         };
 
@@ -177,16 +355,21 @@ impl Window {
         }
 
         // XXX Do initial commit.
-        window.commit();
+        window.wl_surface().commit();
 
         // Add the window and window requests into the state.
         let window_state = Arc::new(Mutex::new(window_state));
         let window_id = super::make_wid(&surface);
         state.windows.get_mut().insert(window_id, window_state.clone());
+        if let Some(toplevel) = window.toplevel() {
+            state.toplevels.lock().unwrap().insert(window_id, toplevel.clone());
+        }
+        let toplevels = state.toplevels.clone();
 
         let window_requests = WindowRequests {
             redraw_requested: AtomicBool::new(true),
             closed: AtomicBool::new(false),
+            drag_requests: Mutex::new(Vec::new()),
         };
         let window_requests = Arc::new(window_requests);
         state.window_requests.get_mut().insert(window_id, window_requests.clone());
@@ -209,6 +392,11 @@ impl Window {
         let event_loop_awakener = event_loop_window_target.event_loop_awakener.clone();
         event_loop_awakener.ping();
 
+        // The parent shows the child once it's redrawn.
+        if let Some(parent_requests) = parent_requests.as_ref() {
+            parent_requests.request_redraw(&event_loop_awakener);
+        }
+
         Ok(Self {
             window,
             display,
@@ -218,18 +406,71 @@ impl Window {
             window_state,
             queue_handle,
             xdg_activation,
+            presentation,
             attention_requested: Arc::new(AtomicBool::new(false)),
             event_loop_awakener,
             window_requests,
             window_events_sink,
+            parent_requests,
+            toplevels,
         })
     }
 
     pub(crate) fn xdg_toplevel(&self) -> Option<NonNull<c_void>> {
-        NonNull::new(self.window.xdg_toplevel().id().as_ptr().cast())
+        NonNull::new(self.window.toplevel()?.xdg_toplevel().id().as_ptr().cast())
+    }
+
+    pub(crate) fn set_synchronized_commits(&self, synchronized: bool) -> Result<(), RequestError> {
+        match &self.window {
+            WindowRole::Child(child) => {
+                child.set_synchronized(synchronized);
+                Ok(())
+            },
+            _ => Err(NotSupportedError::new("the window is not a child window").into()),
+        }
     }
 }
 
+/// Create the layer surface, with its state set for the initial commit.
+fn create_layer_surface(
+    layer_shell: &LayerShell,
+    queue_handle: &QueueHandle<WinitState>,
+    surface: WlSurface,
+    attributes: LayerSurfaceAttributes,
+    (width, height): (u32, u32),
+) -> LayerSurface {
+    let layer = match attributes.layer {
+        Layer::Background => SctkLayer::Background,
+        Layer::Bottom => SctkLayer::Bottom,
+        Layer::Top => SctkLayer::Top,
+        Layer::Overlay => SctkLayer::Overlay,
+    };
+    let output = attributes.output.as_ref().and_then(|monitor| {
+        monitor.cast_ref::<output::MonitorHandle>().map(|handle| &handle.proxy)
+    });
+    let layer_surface = layer_shell.create_layer_surface(
+        queue_handle,
+        surface,
+        layer,
+        Some(attributes.namespace),
+        output,
+    );
+
+    // The compositor picks the size along the axes the surface is stretched on.
+    layer_surface.set_size(width, height);
+    layer_surface.set_anchor(Anchor::from_bits_truncate(attributes.anchor.bits()));
+    layer_surface.set_exclusive_zone(attributes.exclusive_zone);
+    let (top, right, bottom, left) = attributes.margin;
+    layer_surface.set_margin(top, right, bottom, left);
+    layer_surface.set_keyboard_interactivity(match attributes.keyboard_interactivity {
+        KeyboardInteractivity::None => SctkKeyboardInteractivity::None,
+        KeyboardInteractivity::Exclusive => SctkKeyboardInteractivity::Exclusive,
+        KeyboardInteractivity::OnDemand => SctkKeyboardInteractivity::OnDemand,
+    });
+
+    layer_surface
+}
+
 impl Window {
     pub fn request_activation_token(&self) -> Result<AsyncRequestSerial, RequestError> {
         let xdg_activation = match self.xdg_activation.as_ref() {
@@ -251,10 +492,16 @@ impl Window {
     pub fn surface(&self) -> &WlSurface {
         self.window.wl_surface()
     }
+
+    fn request_drag(&self, request: DragRequest) {
+        self.window_requests.drag_requests.lock().unwrap().push(request);
+        self.event_loop_awakener.ping();
+    }
 }
 
 impl Drop for Window {
     fn drop(&mut self) {
+        self.toplevels.lock().unwrap().remove(&self.window_id);
         self.window_requests.closed.store(true, Ordering::Relaxed);
         self.event_loop_awakener.ping();
     }
@@ -288,18 +535,7 @@ impl CoreWindow for Window {
     }
 
     fn request_redraw(&self) {
-        // NOTE: try to not wake up the loop when the event was already scheduled and not yet
-        // processed by the loop, because if at this point the value was `true` it could only
-        // mean that the loop still haven't dispatched the value to the client and will do
-        // eventually, resetting it to `false`.
-        if self
-            .window_requests
-            .redraw_requested
-            .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
-            .is_ok()
-        {
-            self.event_loop_awakener.ping();
-        }
+        self.window_requests.request_redraw(&self.event_loop_awakener);
     }
 
     #[inline]
@@ -309,6 +545,11 @@ impl CoreWindow for Window {
 
     fn pre_present_notify(&self) {
         self.window_state.lock().unwrap().request_frame_callback();
+
+        // The feedback is for the next commit, which presents the frame.
+        if let Some(presentation) = self.presentation.as_ref() {
+            presentation.feedback(self.surface(), &self.queue_handle, self.window_id);
+        }
     }
 
     fn reset_dead_keys(&self) {
@@ -320,12 +561,30 @@ impl CoreWindow for Window {
     }
 
     fn outer_position(&self) -> Result<PhysicalPosition<i32>, RequestError> {
-        Err(NotSupportedError::new("window position information is not available on Wayland")
-            .into())
+        let window_state = self.window_state.lock().unwrap();
+        match window_state.child_position() {
+            Some(position) => Ok(position.to_physical(window_state.scale_factor())),
+            None => Err(NotSupportedError::new(
+                "window position information is not available on Wayland",
+            )
+            .into()),
+        }
     }
 
-    fn set_outer_position(&self, _position: Position) {
-        // Not possible.
+    fn set_outer_position(&self, position: Position) {
+        // Only possible for child windows.
+        if !matches!(self.window, WindowRole::Child(_)) {
+            return;
+        }
+
+        let mut window_state = self.window_state.lock().unwrap();
+        let position = position.to_logical(window_state.scale_factor());
+        window_state.set_child_position(position);
+        drop(window_state);
+
+        if let Some(parent_requests) = self.parent_requests.as_ref() {
+            parent_requests.request_redraw(&self.event_loop_awakener);
+        }
     }
 
     fn surface_size(&self) -> PhysicalSize<u32> {
@@ -422,7 +681,9 @@ impl CoreWindow for Window {
             return;
         }
 
-        self.window.set_minimized();
+        if let Some(window) = self.window.toplevel() {
+            window.set_minimized();
+        }
     }
 
     fn is_minimized(&self) -> Option<bool> {
@@ -431,7 +692,8 @@ impl CoreWindow for Window {
     }
 
     fn set_maximized(&self, maximized: bool) {
-        if maximized { self.window.set_maximized() } else { self.window.unset_maximized() }
+        let Some(window) = self.window.toplevel() else { return };
+        if maximized { window.set_maximized() } else { window.unset_maximized() }
     }
 
     fn is_maximized(&self) -> bool {
@@ -445,6 +707,7 @@ impl CoreWindow for Window {
     }
 
     fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        let Some(window) = self.window.toplevel() else { return };
         match fullscreen {
             Some(Fullscreen::Exclusive(..)) => {
                 warn!("`Fullscreen::Exclusive` is ignored on Wayland");
@@ -454,9 +717,9 @@ impl CoreWindow for Window {
                     monitor.cast_ref::<output::MonitorHandle>().map(|handle| &handle.proxy)
                 });
 
-                self.window.set_fullscreen(output)
+                window.set_fullscreen(output)
             },
-            None => self.window.unset_fullscreen(),
+            None => window.unset_fullscreen(),
         }
     }
 
@@ -478,6 +741,18 @@ impl CoreWindow for Window {
         }
     }
 
+    fn set_content_type(&self, content_type: ContentType) -> Result<(), RequestError> {
+        self.window_state.lock().unwrap().set_content_type(content_type)?;
+        self.request_redraw();
+        Ok(())
+    }
+
+    fn set_allow_tearing(&self, allow_tearing: bool) -> Result<(), RequestError> {
+        self.window_state.lock().unwrap().set_allow_tearing(allow_tearing)?;
+        self.request_redraw();
+        Ok(())
+    }
+
     #[inline]
     fn scale_factor(&self) -> f64 {
         self.window_state.lock().unwrap().scale_factor()
@@ -500,6 +775,33 @@ impl CoreWindow for Window {
 
     fn set_window_level(&self, _level: WindowLevel) {}
 
+    fn set_owner_window(&self, owner: Option<WindowId>, modal: bool) -> Result<(), RequestError> {
+        let window = self
+            .window
+            .toplevel()
+            .ok_or_else(|| NotSupportedError::new("only toplevels can have an owner"))?;
+        let toplevels = self.toplevels.lock().unwrap();
+        let owner = match owner {
+            Some(owner) => Some(toplevels.get(&owner).ok_or_else(|| {
+                NotSupportedError::new("the owner window is not a toplevel of this event loop")
+            })?),
+            None => None,
+        };
+
+        // Check the protocol before changing anything.
+        let mut window_state = self.window_state.lock().unwrap();
+        let modal = owner.is_some() && modal;
+        if modal && !window_state.supports_modal() {
+            return Err(NotSupportedError::new("xdg_wm_dialog_v1 is not available").into());
+        }
+
+        window.set_parent(owner);
+        window_state.set_modal(modal)?;
+        drop(window_state);
+        self.request_redraw();
+        Ok(())
+    }
+
     fn set_window_icon(&self, window_icon: Option<winit_core::icon::Icon>) {
         self.window_state.lock().unwrap().set_window_icon(window_icon)
     }
@@ -564,6 +866,24 @@ impl CoreWindow for Window {
 
     fn set_content_protected(&self, _protected: bool) {}
 
+    fn set_keyboard_grab(&self, grab: bool) -> Result<(), RequestError> {
+        let released = self.window_state.lock().unwrap().set_keyboard_grab(grab)?;
+
+        if released {
+            let event = WindowEvent::KeyboardGrabbed(false);
+            self.window_events_sink.lock().unwrap().push_window_event(event, self.window_id);
+            self.event_loop_awakener.ping();
+        }
+
+        Ok(())
+    }
+
+    fn set_idle_inhibited(&self, inhibited: bool) -> Result<(), RequestError> {
+        self.window_state.lock().unwrap().set_idle_inhibited(inhibited)?;
+        self.request_redraw();
+        Ok(())
+    }
+
     fn set_cursor(&self, cursor: Cursor) {
         let window_state = &mut self.window_state.lock().unwrap();
 
@@ -596,6 +916,32 @@ impl CoreWindow for Window {
         self.window_state.lock().unwrap().drag_window()
     }
 
+    fn start_drag(
+        &self,
+        contents: Vec<ClipboardData>,
+        actions: DragActions,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = AsyncRequestSerial::get();
+        self.request_drag(DragRequest::Start { serial, contents, actions });
+        Ok(serial)
+    }
+
+    fn accept_drag(&self, mime_type: &str, action: DragAction) -> Result<(), RequestError> {
+        self.request_drag(DragRequest::Accept { accepted: Some((mime_type.to_owned(), action)) });
+        Ok(())
+    }
+
+    fn reject_drag(&self) -> Result<(), RequestError> {
+        self.request_drag(DragRequest::Accept { accepted: None });
+        Ok(())
+    }
+
+    fn request_drag_data(&self, mime_type: &str) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = AsyncRequestSerial::get();
+        self.request_drag(DragRequest::Data { serial, mime_type: mime_type.to_owned() });
+        Ok(serial)
+    }
+
     fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), RequestError> {
         self.window_state.lock().unwrap().drag_resize_window(direction)
     }
@@ -663,14 +1009,36 @@ pub struct WindowRequests {
 
     /// Redraw Requested.
     pub redraw_requested: AtomicBool,
+
+    /// Drag and drop requests.
+    pub drag_requests: Mutex<Vec<DragRequest>>,
 }
 
 impl WindowRequests {
-    pub fn take_closed(&self) -> bool {
-        self.closed.swap(false, Ordering::Relaxed)
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    /// Request a redraw, waking up the event loop to deliver it.
+    pub fn request_redraw(&self, event_loop_awakener: &calloop::ping::Ping) {
+        // NOTE: try to not wake up the loop when the event was already scheduled and not yet
+        // processed by the loop, because if at this point the value was `true` it could only
+        // mean that the loop still haven't dispatched the value to the client and will do
+        // eventually, resetting it to `false`.
+        if self
+            .redraw_requested
+            .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            event_loop_awakener.ping();
+        }
     }
 
     pub fn take_redraw_requested(&self) -> bool {
         self.redraw_requested.swap(false, Ordering::Relaxed)
     }
+
+    pub fn take_drag_requests(&self) -> Vec<DragRequest> {
+        mem::take(&mut *self.drag_requests.lock().unwrap())
+    }
 }
@@ -4,26 +4,44 @@ use std::num::NonZeroU32;
 use std::sync::{Arc, Mutex, Weak};
 use std::time::Duration;
 
-use dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Size};
-use foldhash::HashSet;
+use dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size};
+use foldhash::{HashMap, HashSet};
 use sctk::compositor::{CompositorState, Region, SurfaceData, SurfaceDataExt};
-use sctk::globals::GlobalData;
+use sctk::error::GlobalError;
+use sctk::globals::{GlobalData, ProvidesBoundGlobal};
 use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_shm::WlShm;
+use sctk::reexports::client::protocol::wl_subsurface::WlSubsurface;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Proxy, QueueHandle};
 use sctk::reexports::csd_frame::{
     DecorationsFrame, FrameAction, FrameClick, ResizeEdge, WindowState as XdgWindowState,
 };
+use sctk::reexports::protocols::wp::content_type::v1::client::wp_content_type_v1::{
+    Type as WpContentType, WpContentTypeV1,
+};
 use sctk::reexports::protocols::wp::fractional_scale::v1::client::wp_fractional_scale_v1::WpFractionalScaleV1;
+use sctk::reexports::protocols::wp::idle_inhibit::zv1::client::zwp_idle_inhibitor_v1::ZwpIdleInhibitorV1;
+use sctk::reexports::protocols::wp::keyboard_shortcuts_inhibit::zv1::client::zwp_keyboard_shortcuts_inhibitor_v1::ZwpKeyboardShortcutsInhibitorV1;
 use sctk::reexports::protocols::wp::text_input::zv3::client::zwp_text_input_v3::ZwpTextInputV3;
+use sctk::reexports::protocols::wp::tearing_control::v1::client::wp_tearing_control_v1::{
+    PresentationHint, WpTearingControlV1,
+};
 use sctk::reexports::protocols::wp::viewporter::client::wp_viewport::WpViewport;
+use sctk::reexports::protocols::xdg::dialog::v1::client::xdg_dialog_v1::XdgDialogV1;
+use sctk::reexports::protocols::xdg::shell::client::xdg_positioner::{
+    Anchor as XdgAnchor, ConstraintAdjustment as XdgConstraintAdjustment, Gravity as XdgGravity,
+};
+use sctk::reexports::protocols::xdg::shell::client::xdg_surface::XdgSurface as XdgSurfaceProxy;
 use sctk::reexports::protocols::xdg::shell::client::xdg_toplevel::ResizeEdge as XdgResizeEdge;
+use sctk::reexports::protocols::xdg::shell::client::xdg_wm_base::XdgWmBase;
 use sctk::seat::pointer::{PointerDataExt, ThemedPointer};
 use sctk::shell::WaylandSurface;
-use sctk::shell::xdg::XdgSurface;
+use sctk::shell::wlr_layer::{LayerSurface, LayerSurfaceConfigure};
+use sctk::shell::xdg::popup::{Popup, PopupConfigure};
 use sctk::shell::xdg::window::{DecorationMode, Window, WindowConfigure};
+use sctk::shell::xdg::{XdgPositioner, XdgShell, XdgSurface};
 use sctk::shm::Shm;
 use sctk::shm::slot::SlotPool;
 use sctk::subcompositor::SubcompositorState;
@@ -33,11 +51,11 @@ use wayland_protocols_plasma::blur::client::org_kde_kwin_blur::OrgKdeKwinBlur;
 use winit_core::cursor::{CursorIcon, CustomCursor as CoreCustomCursor};
 use winit_core::error::{NotSupportedError, RequestError};
 use winit_core::window::{
-    CursorGrabMode, ImeCapabilities, ImeRequest, ImeRequestError, ResizeDirection, Theme, WindowId,
+    ContentType, CursorGrabMode, ImeCapabilities, ImeRequest, ImeRequestError, PopupAnchor,
+    PopupAttributes, PopupGravity, ResizeDirection, Theme, WindowId,
 };
 
 use crate::event_loop::OwnedDisplayHandle;
-use crate::logical_to_physical_rounded;
 use crate::seat::{
     PointerConstraintsState, TextInputClientState, WinitPointerData, WinitPointerDataExt,
     ZwpTextInputV3Ext,
@@ -45,7 +63,13 @@ use crate::seat::{
 use crate::state::{WindowCompositorUpdate, WinitState};
 use crate::types::cursor::{CustomCursor, SelectedCursor, WaylandCustomCursor};
 use crate::types::kwin_blur::KWinBlurManager;
+use crate::types::wp_content_type::ContentTypeManager;
+use crate::types::wp_idle_inhibit::IdleInhibitManager;
+use crate::types::wp_keyboard_shortcuts_inhibit::KeyboardShortcutsInhibitManager;
+use crate::types::wp_tearing_control::TearingControlManager;
+use crate::types::xdg_dialog::XdgDialogManager;
 use crate::types::xdg_toplevel_icon_manager::ToplevelIcon;
+use crate::{LayerAnchor, logical_to_physical_rounded, make_wid};
 
 #[cfg(feature = "sctk-adwaita")]
 pub type WinitFrame = sctk_adwaita::AdwaitaFrame<WinitState>;
@@ -67,6 +91,21 @@ pub struct WindowState {
     /// The last received configure.
     pub last_configure: Option<WindowConfigure>,
 
+    /// The last received configure of a popup.
+    last_popup_configure: Option<PopupConfigure>,
+
+    /// The last received configure of a layer surface.
+    last_layer_configure: Option<LayerSurfaceConfigure>,
+
+    /// The axes the layer surface is stretched on.
+    layer_stretch: LayerStretch,
+
+    /// The placement of a popup, to reposition it when it's resized.
+    popup_placement: Option<PopupPlacement>,
+
+    /// The position of a child window, relative to its parent surface.
+    child_position: LogicalPosition<i32>,
+
     /// The pointers observed on the window.
     pub pointers: Vec<Weak<ThemedPointer<WinitPointerData>>>,
 
@@ -102,7 +141,15 @@ pub struct WindowState {
     // NOTE: we can't use simple counter, since it's racy when seat getting destroyed and new
     // is created, since add/removed stuff could be delivered a bit out of order.
     /// Seats that has keyboard focus on that window.
-    seat_focus: HashSet<ObjectId>,
+    seat_focus: HashMap<ObjectId, WlSeat>,
+
+    /// Whether the keyboard grab was requested.
+    keyboard_grab: bool,
+    shortcuts_inhibit_manager: Option<KeyboardShortcutsInhibitManager>,
+    /// The shortcuts inhibitors of the seats which focused the window during the grab.
+    shortcuts_inhibitors: HashMap<ObjectId, ZwpKeyboardShortcutsInhibitorV1>,
+    /// The inhibitors the compositor reported as active.
+    shortcuts_inhibited: HashSet<ObjectId>,
 
     /// The scale factor of the window.
     scale_factor: f64,
@@ -157,14 +204,22 @@ pub struct WindowState {
     fractional_scale: Option<WpFractionalScaleV1>,
     blur: Option<OrgKdeKwinBlur>,
     blur_manager: Option<KWinBlurManager>,
+    idle_inhibitor: Option<ZwpIdleInhibitorV1>,
+    content_type: Option<WpContentTypeV1>,
+    content_type_manager: Option<ContentTypeManager>,
+    tearing_control: Option<WpTearingControlV1>,
+    tearing_control_manager: Option<TearingControlManager>,
+    idle_inhibit_manager: Option<IdleInhibitManager>,
+    dialog: Option<XdgDialogV1>,
+    dialog_manager: Option<XdgDialogManager>,
 
     /// Whether the client side decorations have pending move operations.
     ///
     /// The value is the serial of the event triggered moved.
     has_pending_move: Option<u32>,
 
-    /// The underlying SCTK window.
-    pub window: Window,
+    /// The underlying SCTK window or popup.
+    pub window: WindowRole,
 
     // NOTE: The spec says that destroying parent(`window` in our case), will unmap the
     // subsurfaces. Thus to achieve atomic unmap of the client, drop the decorations
@@ -181,7 +236,7 @@ impl WindowState {
         queue_handle: &QueueHandle<WinitState>,
         winit_state: &WinitState,
         initial_size: Size,
-        window: Window,
+        window: WindowRole,
         theme: Option<Theme>,
         prefer_csd: bool,
     ) -> Self {
@@ -206,6 +261,14 @@ impl WindowState {
             xdg_toplevel_icon_manager,
             blur: None,
             blur_manager: winit_state.kwin_blur_manager.clone(),
+            idle_inhibitor: None,
+            content_type: None,
+            content_type_manager: winit_state.content_type_manager.clone(),
+            tearing_control: None,
+            tearing_control_manager: winit_state.tearing_control_manager.clone(),
+            idle_inhibit_manager: winit_state.idle_inhibit_manager.clone(),
+            dialog: None,
+            dialog_manager: winit_state.xdg_dialog_manager.clone(),
             compositor,
             handle,
             csd_fails: false,
@@ -218,9 +281,18 @@ impl WindowState {
             frame: None,
             frame_callback_state: FrameCallbackState::None,
             seat_focus: Default::default(),
+            keyboard_grab: false,
+            shortcuts_inhibit_manager: winit_state.keyboard_shortcuts_inhibit_manager.clone(),
+            shortcuts_inhibitors: Default::default(),
+            shortcuts_inhibited: Default::default(),
             has_pending_move: None,
             text_input_state: None,
             last_configure: None,
+            last_popup_configure: None,
+            last_layer_configure: None,
+            layer_stretch: LayerStretch::default(),
+            popup_placement: None,
+            child_position: LogicalPosition::new(0, 0),
             max_surface_size: None,
             min_surface_size: MIN_WINDOW_SIZE,
             pointer_constraints,
@@ -294,13 +366,14 @@ impl WindowState {
             self.stateless_size = self.size;
         }
 
-        if let Some(subcompositor) = subcompositor.as_ref().filter(|_| {
+        let subcompositor = subcompositor.as_ref().filter(|_| {
             configure.decoration_mode == DecorationMode::Client
                 && self.frame.is_none()
                 && !self.csd_fails
-        }) {
+        });
+        if let (Some(subcompositor), Some(window)) = (subcompositor, self.window.toplevel()) {
             match WinitFrame::new(
-                &self.window,
+                window,
                 shm,
                 #[cfg(feature = "sctk-adwaita")]
                 self.compositor.clone(),
@@ -385,6 +458,65 @@ impl WindowState {
         }
     }
 
+    /// Apply the configure of a popup, returning whether it was resized.
+    pub fn configure_popup(&mut self, configure: PopupConfigure) -> bool {
+        if let Some(initial_size) = self.initial_size.take() {
+            self.size = initial_size.to_logical(self.scale_factor());
+            self.stateless_size = self.size;
+        }
+
+        let new_size = match (u32::try_from(configure.width), u32::try_from(configure.height)) {
+            (Ok(width), Ok(height)) if width > 0 && height > 0 => LogicalSize::new(width, height),
+            _ => self.size,
+        };
+
+        let initial = self.last_popup_configure.replace(configure).is_none();
+        if initial || new_size != self.surface_size() {
+            self.resize(new_size);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Apply the configure of a layer surface, returning whether it was resized.
+    pub fn configure_layer(&mut self, configure: LayerSurfaceConfigure) -> bool {
+        if let Some(initial_size) = self.initial_size.take() {
+            self.size = initial_size.to_logical(self.scale_factor());
+            self.stateless_size = self.size;
+        }
+
+        // A zero dimension is left for us to pick.
+        let (width, height) = configure.new_size;
+        let new_size = LogicalSize::new(
+            if width == 0 { self.size.width } else { width },
+            if height == 0 { self.size.height } else { height },
+        );
+
+        let initial = self.last_layer_configure.replace(configure).is_none();
+        if initial || new_size != self.surface_size() {
+            self.resize(new_size);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Set the axes the layer surface is stretched on.
+    pub fn set_layer_stretch(&mut self, stretch: LayerStretch) {
+        self.layer_stretch = stretch;
+    }
+
+    /// Set up a child window, which doesn't get configured by the compositor.
+    pub fn configure_child(&mut self, placement: ChildPlacement) {
+        if let Some(initial_size) = self.initial_size.take() {
+            self.stateless_size = placement.size(initial_size);
+            self.resize(self.stateless_size);
+        }
+
+        self.set_child_position(placement.position);
+    }
+
     /// Compute the bounds for the surface size of the surface.
     fn surface_size_bounds(
         &self,
@@ -413,7 +545,12 @@ impl WindowState {
 
     /// Start interacting drag resize.
     pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), RequestError> {
-        let xdg_toplevel = self.window.xdg_toplevel();
+        let Some(window) = self.window.toplevel() else {
+            return Err(
+                NotSupportedError::new("only toplevels can be resized interactively").into()
+            );
+        };
+        let xdg_toplevel = window.xdg_toplevel();
 
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
@@ -427,7 +564,10 @@ impl WindowState {
 
     /// Start the window drag.
     pub fn drag_window(&self) -> Result<(), RequestError> {
-        let xdg_toplevel = self.window.xdg_toplevel();
+        let Some(window) = self.window.toplevel() else {
+            return Err(NotSupportedError::new("only toplevels can be moved interactively").into());
+        };
+        let xdg_toplevel = window.xdg_toplevel();
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
             let serial = data.latest_button_serial();
@@ -438,6 +578,19 @@ impl WindowState {
         Ok(())
     }
 
+    /// The seat of the pointer that pressed a button on the window last, with the serial of
+    /// that press.
+    pub fn latest_pointer_button(&self) -> Option<(WlSeat, u32)> {
+        let mut latest: Option<(WlSeat, u32)> = None;
+        self.apply_on_pointer(|_, data| {
+            let serial = data.latest_button_serial();
+            if latest.as_ref().is_none_or(|&(_, latest)| serial > latest) {
+                latest = Some((data.seat().clone(), serial));
+            }
+        });
+        latest
+    }
+
     /// Tells whether the window should be closed.
     #[allow(clippy::too_many_arguments)]
     pub fn frame_click(
@@ -450,10 +603,11 @@ impl WindowState {
         window_id: WindowId,
         updates: &mut Vec<WindowCompositorUpdate>,
     ) -> Option<bool> {
+        let window = self.window.toplevel()?;
         match self.frame.as_mut()?.on_click(timestamp, click, pressed)? {
-            FrameAction::Minimize => self.window.set_minimized(),
-            FrameAction::Maximize => self.window.set_maximized(),
-            FrameAction::UnMaximize => self.window.unset_maximized(),
+            FrameAction::Minimize => window.set_minimized(),
+            FrameAction::Maximize => window.set_maximized(),
+            FrameAction::UnMaximize => window.unset_maximized(),
             FrameAction::Close => WinitState::queue_close(updates, window_id),
             FrameAction::Move => self.has_pending_move = Some(serial),
             FrameAction::Resize(edge) => {
@@ -469,9 +623,9 @@ impl WindowState {
                     ResizeEdge::BottomRight => XdgResizeEdge::BottomRight,
                     _ => return None,
                 };
-                self.window.resize(seat, serial, edge);
+                window.resize(seat, serial, edge);
             },
-            FrameAction::ShowMenu(x, y) => self.window.show_window_menu(seat, serial, (x, y)),
+            FrameAction::ShowMenu(x, y) => window.show_window_menu(seat, serial, (x, y)),
             _ => (),
         };
 
@@ -500,8 +654,10 @@ impl WindowState {
             let cursor = frame.click_point_moved(timestamp, &surface.id(), x, y);
             // If we have a cursor change, that means that cursor is over the decorations,
             // so try to apply move.
-            if let Some(serial) = cursor.is_some().then_some(serial).flatten() {
-                self.window.move_(seat, serial);
+            if let (Some(serial), Some(window)) =
+                (cursor.is_some().then_some(serial).flatten(), self.window.toplevel())
+            {
+                window.move_(seat, serial);
                 None
             } else {
                 cursor
@@ -569,6 +725,29 @@ impl WindowState {
     #[inline]
     pub fn is_configured(&self) -> bool {
         self.last_configure.is_some()
+            || self.last_popup_configure.is_some()
+            || self.last_layer_configure.is_some()
+            || matches!(self.window, WindowRole::Child(_))
+    }
+
+    /// The position of a child window, relative to its parent surface.
+    pub fn child_position(&self) -> Option<LogicalPosition<i32>> {
+        matches!(self.window, WindowRole::Child(_)).then_some(self.child_position)
+    }
+
+    /// Move a child window, relative to its parent surface.
+    pub fn set_child_position(&mut self, position: LogicalPosition<i32>) {
+        if let WindowRole::Child(child) = &self.window {
+            self.child_position = position;
+            // The position is part of the state of the parent, applied on its next commit.
+            child.subsurface().set_position(position.x, position.y);
+        }
+    }
+
+    /// The origin of the window geometry, relative to the surface.
+    pub fn window_geometry_origin(&self) -> LogicalPosition<i32> {
+        let (x, y) = self.frame.as_ref().map(|frame| frame.location()).unwrap_or((0, 0));
+        LogicalPosition::new(x, y)
     }
 
     #[inline]
@@ -657,7 +836,19 @@ impl WindowState {
 
     /// Try to resize the window when the user can do so.
     pub fn request_surface_size(&mut self, surface_size: Size) -> PhysicalSize<u32> {
-        if self.last_configure.as_ref().map(Self::is_stateless).unwrap_or(true) {
+        if let WindowRole::Popup(popup) = &self.window {
+            // The compositor places the popup again with the new size, and configures it.
+            let size = surface_size.to_logical(self.scale_factor());
+            if let Some(placement) = self.popup_placement.as_mut() {
+                placement.size = size;
+                placement.reposition(popup);
+            }
+        } else if let WindowRole::Layer(layer) = &self.window {
+            // The size is applied with the configure answering the next commit.
+            let (width, height) =
+                self.layer_stretch.size(surface_size.to_logical(self.scale_factor()));
+            layer.set_size(width, height);
+        } else if self.last_configure.as_ref().map(Self::is_stateless).unwrap_or(true) {
             self.resize(surface_size.to_logical(self.scale_factor()))
         }
 
@@ -692,12 +883,14 @@ impl WindowState {
         self.reload_transparency_hint();
 
         // Set the window geometry.
-        self.window.xdg_surface().set_window_geometry(
-            x,
-            y,
-            outer_size.width as i32,
-            outer_size.height as i32,
-        );
+        if let Some(xdg_surface) = self.window.xdg_surface() {
+            xdg_surface.set_window_geometry(
+                x,
+                y,
+                outer_size.width as i32,
+                outer_size.height as i32,
+            );
+        }
 
         // Update the target viewport, this is used if and only if fractional scaling is in use.
         if let Some(viewport) = self.viewport.as_ref() {
@@ -800,7 +993,9 @@ impl WindowState {
             .unwrap_or(size);
 
         self.min_surface_size = size;
-        self.window.set_min_size(Some(size.into()));
+        if let Some(window) = self.window.toplevel() {
+            window.set_min_size(Some(size.into()));
+        }
     }
 
     /// Set maximum inner window size.
@@ -813,7 +1008,9 @@ impl WindowState {
         });
 
         self.max_surface_size = size;
-        self.window.set_max_size(size.map(Into::into));
+        if let Some(window) = self.window.toplevel() {
+            window.set_max_size(size.map(Into::into));
+        }
     }
 
     /// Set the CSD theme.
@@ -910,11 +1107,12 @@ impl WindowState {
     }
 
     pub fn show_window_menu(&self, position: LogicalPosition<u32>) {
+        let Some(window) = self.window.toplevel() else { return };
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
             let serial = data.latest_button_serial();
             let seat = data.seat();
-            self.window.show_window_menu(seat, serial, position.into());
+            window.show_window_menu(seat, serial, position.into());
         });
     }
 
@@ -966,15 +1164,16 @@ impl WindowState {
 
         self.decorate = decorate;
 
+        let Some(window) = self.window.toplevel() else { return };
         match self.last_configure.as_ref().map(|configure| configure.decoration_mode) {
             Some(DecorationMode::Server) if !self.decorate => {
                 // To disable decorations we should request client and hide the frame.
-                self.window.request_decoration_mode(Some(DecorationMode::Client))
+                window.request_decoration_mode(Some(DecorationMode::Client))
             },
             _ if self.decorate && self.prefer_csd => {
-                self.window.request_decoration_mode(Some(DecorationMode::Client))
+                window.request_decoration_mode(Some(DecorationMode::Client))
             },
-            _ if self.decorate => self.window.request_decoration_mode(Some(DecorationMode::Server)),
+            _ if self.decorate => window.request_decoration_mode(Some(DecorationMode::Server)),
             _ => (),
         }
 
@@ -987,8 +1186,17 @@ impl WindowState {
 
     /// Add seat focus for the window.
     #[inline]
-    pub fn add_seat_focus(&mut self, seat: ObjectId) {
-        self.seat_focus.insert(seat);
+    pub fn add_seat_focus(&mut self, seat: &WlSeat) {
+        self.seat_focus.insert(seat.id(), seat.clone());
+        if self.keyboard_grab {
+            self.inhibit_shortcuts(seat);
+        }
+    }
+
+    /// Whether the given seat has focus on the window.
+    #[inline]
+    pub fn has_seat_focus(&self, seat: &ObjectId) -> bool {
+        self.seat_focus.contains_key(seat)
     }
 
     /// Remove seat focus from the window.
@@ -997,6 +1205,59 @@ impl WindowState {
         self.seat_focus.remove(seat);
     }
 
+    /// Grab the keyboard by inhibiting the compositor shortcuts for the seats focusing the window.
+    ///
+    /// Returns `true` when an active grab was released.
+    pub fn set_keyboard_grab(&mut self, grab: bool) -> Result<bool, RequestError> {
+        if self.shortcuts_inhibit_manager.is_none() {
+            return Err(NotSupportedError::new(
+                "zwp_keyboard_shortcuts_inhibit_manager_v1 is not available",
+            )
+            .into());
+        }
+
+        self.keyboard_grab = grab;
+        if grab {
+            for seat in self.seat_focus.values().cloned().collect::<Vec<_>>() {
+                self.inhibit_shortcuts(&seat);
+            }
+            return Ok(false);
+        }
+
+        for (_, inhibitor) in self.shortcuts_inhibitors.drain() {
+            inhibitor.destroy();
+        }
+        let was_active = !self.shortcuts_inhibited.is_empty();
+        self.shortcuts_inhibited.clear();
+
+        Ok(was_active)
+    }
+
+    fn inhibit_shortcuts(&mut self, seat: &WlSeat) {
+        let Some(manager) = self.shortcuts_inhibit_manager.as_ref() else { return };
+        if self.shortcuts_inhibitors.contains_key(&seat.id()) {
+            return;
+        }
+
+        let surface = self.window.wl_surface();
+        let inhibitor = manager.inhibit(surface, seat, make_wid(surface), &self.queue_handle);
+        self.shortcuts_inhibitors.insert(seat.id(), inhibitor);
+    }
+
+    /// Update whether the compositor activated the shortcuts inhibitor.
+    ///
+    /// Returns `true` when the keyboard grab became active or inactive.
+    pub fn set_shortcuts_inhibited(&mut self, inhibitor: &ObjectId, active: bool) -> bool {
+        let was_active = !self.shortcuts_inhibited.is_empty();
+        if !active {
+            self.shortcuts_inhibited.remove(inhibitor);
+        } else if self.shortcuts_inhibitors.values().any(|proxy| proxy.id() == *inhibitor) {
+            self.shortcuts_inhibited.insert(inhibitor.clone());
+        }
+
+        was_active == self.shortcuts_inhibited.is_empty()
+    }
+
     /// Atomically update input method state.
     ///
     /// Returns `None` if an input method state haven't changed. Alternatively `Some(true)` and
@@ -1055,7 +1316,7 @@ impl WindowState {
 
         // NOTE: When fractional scaling is not used update the buffer scale.
         if self.fractional_scale.is_none() {
-            let _ = self.window.set_buffer_scale(self.scale_factor as _);
+            self.window.set_buffer_scale(self.scale_factor as _);
         }
 
         if let Some(frame) = self.frame.as_mut() {
@@ -1080,6 +1341,95 @@ impl WindowState {
         }
     }
 
+    /// Hint the kind of content of the window.
+    pub fn set_content_type(&mut self, content_type: ContentType) -> Result<(), RequestError> {
+        let manager = self
+            .content_type_manager
+            .as_ref()
+            .ok_or_else(|| NotSupportedError::new("wp_content_type_v1 is not available"))?;
+
+        let surface_content_type = self.content_type.get_or_insert_with(|| {
+            manager.content_type(self.window.wl_surface(), &self.queue_handle)
+        });
+        surface_content_type.set_content_type(match content_type {
+            ContentType::None => WpContentType::None,
+            ContentType::Photo => WpContentType::Photo,
+            ContentType::Video => WpContentType::Video,
+            ContentType::Game => WpContentType::Game,
+        });
+
+        Ok(())
+    }
+
+    /// Allow the compositor to present the frames of the window asynchronously.
+    pub fn set_allow_tearing(&mut self, allow_tearing: bool) -> Result<(), RequestError> {
+        let manager = self
+            .tearing_control_manager
+            .as_ref()
+            .ok_or_else(|| NotSupportedError::new("wp_tearing_control_v1 is not available"))?;
+
+        let tearing_control = self.tearing_control.get_or_insert_with(|| {
+            manager.tearing_control(self.window.wl_surface(), &self.queue_handle)
+        });
+        tearing_control.set_presentation_hint(if allow_tearing {
+            PresentationHint::Async
+        } else {
+            PresentationHint::Vsync
+        });
+
+        Ok(())
+    }
+
+    /// Whether the toplevel can be made a modal dialog, which requires `xdg_wm_dialog_v1`.
+    pub fn supports_modal(&self) -> bool {
+        self.dialog_manager.is_some()
+    }
+
+    /// Set whether the toplevel is a modal dialog of its parent.
+    pub fn set_modal(&mut self, modal: bool) -> Result<(), RequestError> {
+        let Some(toplevel) = self.window.toplevel() else {
+            return Err(NotSupportedError::new("only toplevels can be modal").into());
+        };
+
+        if !modal && self.dialog.is_none() {
+            return Ok(());
+        }
+
+        let manager = self
+            .dialog_manager
+            .as_ref()
+            .ok_or_else(|| NotSupportedError::new("xdg_wm_dialog_v1 is not available"))?;
+
+        let dialog = self
+            .dialog
+            .get_or_insert_with(|| manager.dialog(toplevel.xdg_toplevel(), &self.queue_handle));
+        if modal {
+            dialog.set_modal();
+        } else {
+            dialog.unset_modal();
+        }
+
+        Ok(())
+    }
+
+    /// Prevent the screen from going idle while the window is visible.
+    pub fn set_idle_inhibited(&mut self, inhibited: bool) -> Result<(), RequestError> {
+        let manager = self.idle_inhibit_manager.as_ref().ok_or_else(|| {
+            NotSupportedError::new("zwp_idle_inhibit_manager_v1 is not available")
+        })?;
+
+        if inhibited && self.idle_inhibitor.is_none() {
+            self.idle_inhibitor =
+                Some(manager.inhibit(self.window.wl_surface(), &self.queue_handle));
+        } else if !inhibited {
+            if let Some(inhibitor) = self.idle_inhibitor.take() {
+                inhibitor.destroy();
+            }
+        }
+
+        Ok(())
+    }
+
     /// Set the window title to a new value.
     ///
     /// This will automatically truncate the title to something meaningful.
@@ -1099,12 +1449,15 @@ impl WindowState {
             frame.set_title(&title);
         }
 
-        self.window.set_title(&title);
+        if let Some(window) = self.window.toplevel() {
+            window.set_title(&title);
+        }
         self.title = title;
     }
 
     /// Set the window's icon
     pub fn set_window_icon(&mut self, window_icon: Option<winit_core::icon::Icon>) {
+        let Some(window) = self.window.toplevel() else { return };
         let xdg_toplevel_icon_manager = match self.xdg_toplevel_icon_manager.as_ref() {
             Some(xdg_toplevel_icon_manager) => xdg_toplevel_icon_manager,
             None => {
@@ -1134,7 +1487,7 @@ impl WindowState {
             None => (None, None),
         };
 
-        xdg_toplevel_icon_manager.set_icon(self.window.xdg_toplevel(), xdg_toplevel_icon.as_ref());
+        xdg_toplevel_icon_manager.set_icon(window.xdg_toplevel(), xdg_toplevel_icon.as_ref());
         self.toplevel_icon = toplevel_icon;
 
         if let Some(xdg_toplevel_icon) = xdg_toplevel_icon {
@@ -1170,6 +1523,17 @@ impl WindowState {
     pub fn title(&self) -> &str {
         &self.title
     }
+
+    /// The parent of the popup.
+    #[inline]
+    pub fn popup_parent(&self) -> Option<WindowId> {
+        self.popup_placement.as_ref().map(|placement| placement.attributes.parent)
+    }
+
+    /// Set the placement of the popup, used when it's resized.
+    pub fn set_popup_placement(&mut self, placement: PopupPlacement) {
+        self.popup_placement = Some(placement);
+    }
 }
 
 impl Drop for WindowState {
@@ -1178,6 +1542,26 @@ impl Drop for WindowState {
             blur.release();
         }
 
+        if let Some(inhibitor) = self.idle_inhibitor.take() {
+            inhibitor.destroy();
+        }
+
+        if let Some(content_type) = self.content_type.take() {
+            content_type.destroy();
+        }
+
+        if let Some(dialog) = self.dialog.take() {
+            dialog.destroy();
+        }
+
+        if let Some(tearing_control) = self.tearing_control.take() {
+            tearing_control.destroy();
+        }
+
+        for (_, inhibitor) in self.shortcuts_inhibitors.drain() {
+            inhibitor.destroy();
+        }
+
         if let Some(fs) = self.fractional_scale.take() {
             fs.destroy();
         }
@@ -1187,7 +1571,223 @@ impl Drop for WindowState {
         }
 
         // NOTE: the wl_surface used by the window is being cleaned up when
-        // dropping SCTK `Window`.
+        // dropping SCTK `Window` or the `ChildSurface`.
+    }
+}
+
+/// The role of the window surface.
+#[derive(Debug, Clone)]
+pub enum WindowRole {
+    Toplevel(Window),
+    Popup(Popup),
+    Layer(LayerSurface),
+    Child(ChildSurface),
+}
+
+impl WindowRole {
+    pub fn wl_surface(&self) -> &WlSurface {
+        match self {
+            Self::Toplevel(window) => window.wl_surface(),
+            Self::Popup(popup) => popup.wl_surface(),
+            Self::Layer(layer) => layer.wl_surface(),
+            Self::Child(child) => child.wl_surface(),
+        }
+    }
+
+    /// The `xdg_surface`, `None` for layer surfaces and child windows.
+    pub fn xdg_surface(&self) -> Option<&XdgSurfaceProxy> {
+        match self {
+            Self::Toplevel(window) => Some(window.xdg_surface()),
+            Self::Popup(popup) => Some(popup.xdg_surface()),
+            Self::Layer(_) | Self::Child(_) => None,
+        }
+    }
+
+    /// The toplevel, `None` for popups, layer surfaces and child windows.
+    pub fn toplevel(&self) -> Option<&Window> {
+        match self {
+            Self::Toplevel(window) => Some(window),
+            Self::Popup(_) | Self::Layer(_) | Self::Child(_) => None,
+        }
+    }
+
+    fn set_buffer_scale(&self, scale: i32) {
+        let surface = self.wl_surface();
+        // The buffer scale is available since version 3.
+        if surface.version() >= 3 {
+            surface.set_buffer_scale(scale);
+        }
+    }
+}
+
+/// A child window, which is a subsurface of its parent window.
+#[derive(Debug, Clone)]
+pub struct ChildSurface {
+    inner: Arc<ChildSurfaceInner>,
+}
+
+#[derive(Debug)]
+struct ChildSurfaceInner {
+    subsurface: WlSubsurface,
+    surface: WlSurface,
+}
+
+impl ChildSurface {
+    pub fn new(
+        subcompositor: &SubcompositorState,
+        parent: WlSurface,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Self {
+        let (subsurface, surface) = subcompositor.create_subsurface(parent, queue_handle);
+        Self { inner: Arc::new(ChildSurfaceInner { subsurface, surface }) }
+    }
+
+    pub fn wl_surface(&self) -> &WlSurface {
+        &self.inner.surface
+    }
+
+    pub fn subsurface(&self) -> &WlSubsurface {
+        &self.inner.subsurface
+    }
+
+    /// Whether the commits of the child are applied along with the ones of its parent.
+    pub fn set_synchronized(&self, synchronized: bool) {
+        if synchronized {
+            self.inner.subsurface.set_sync();
+        } else {
+            self.inner.subsurface.set_desync();
+        }
+    }
+}
+
+impl Drop for ChildSurfaceInner {
+    fn drop(&mut self) {
+        self.subsurface.destroy();
+        self.surface.destroy();
+    }
+}
+
+/// The initial placement of a child window, in the logical coordinates of its parent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChildPlacement {
+    pub position: LogicalPosition<i32>,
+    pub parent_scale_factor: f64,
+}
+
+impl ChildPlacement {
+    pub fn new(position: Option<Position>, parent_scale_factor: f64) -> Self {
+        let position = position.map(|position| position.to_logical(parent_scale_factor));
+        Self { position: position.unwrap_or_default(), parent_scale_factor }
+    }
+
+    /// The size of the child until it enters an output, where it's shown with the scale of its
+    /// parent.
+    pub fn size(&self, size: Size) -> LogicalSize<u32> {
+        size.to_logical(self.parent_scale_factor)
+    }
+}
+
+/// The axes a layer surface is stretched on, across the output between the anchored edges.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LayerStretch {
+    pub horizontal: bool,
+    pub vertical: bool,
+}
+
+impl LayerStretch {
+    /// Stretch along the axes with a zero `size` which are anchored to both edges.
+    pub fn new(anchor: LayerAnchor, size: LogicalSize<u32>) -> Self {
+        Self {
+            horizontal: size.width == 0 && anchor.contains(LayerAnchor::LEFT | LayerAnchor::RIGHT),
+            vertical: size.height == 0 && anchor.contains(LayerAnchor::TOP | LayerAnchor::BOTTOM),
+        }
+    }
+
+    /// The size to request, which is zero along the stretched axes.
+    ///
+    /// The other axes can't be zero.
+    pub fn size(self, size: LogicalSize<u32>) -> (u32, u32) {
+        (
+            if self.horizontal { 0 } else { size.width.max(1) },
+            if self.vertical { 0 } else { size.height.max(1) },
+        )
+    }
+}
+
+/// The placement of a popup, in the window geometry of its parent.
+#[derive(Debug, Clone)]
+pub struct PopupPlacement {
+    pub attributes: PopupAttributes,
+    pub anchor_position: LogicalPosition<i32>,
+    pub anchor_size: LogicalSize<i32>,
+    pub size: LogicalSize<u32>,
+    /// The token of the last reposition request.
+    token: u32,
+    wm_base: WmBase,
+}
+
+impl PopupPlacement {
+    pub fn new(
+        xdg_shell: &XdgShell,
+        attributes: PopupAttributes,
+        parent: &WindowState,
+        size: LogicalSize<u32>,
+    ) -> Self {
+        let scale_factor = parent.scale_factor();
+        let origin = parent.window_geometry_origin();
+        let anchor_position: LogicalPosition<i32> =
+            attributes.anchor_position.to_logical(scale_factor);
+        let anchor_position =
+            LogicalPosition::new(anchor_position.x - origin.x, anchor_position.y - origin.y);
+        let anchor_size = attributes.anchor_size.to_logical(scale_factor);
+        let wm_base = WmBase(xdg_shell.xdg_wm_base().clone());
+        Self { attributes, anchor_position, anchor_size, size, token: 0, wm_base }
+    }
+
+    /// Create a positioner with the placement.
+    pub fn positioner(&self) -> Result<XdgPositioner, GlobalError> {
+        let positioner = XdgPositioner::new(&self.wm_base)?;
+        positioner.set_size(self.size.width.max(1) as i32, self.size.height.max(1) as i32);
+        // The anchor rectangle can't be empty.
+        positioner.set_anchor_rect(
+            self.anchor_position.x,
+            self.anchor_position.y,
+            self.anchor_size.width.max(1),
+            self.anchor_size.height.max(1),
+        );
+        positioner.set_anchor(popup_anchor_to_xdg(self.attributes.anchor));
+        positioner.set_gravity(popup_gravity_to_xdg(self.attributes.gravity));
+        positioner.set_constraint_adjustment(XdgConstraintAdjustment::from_bits_truncate(
+            self.attributes.constraint_adjustment.bits(),
+        ));
+        // Follow the parent when it moves or resizes.
+        if positioner.version() >= 3 {
+            positioner.set_reactive();
+        }
+
+        Ok(positioner)
+    }
+
+    /// Ask the compositor to place the popup again.
+    fn reposition(&mut self, popup: &Popup) {
+        // Repositioning is available since version 3.
+        if popup.xdg_popup().version() < 3 {
+            return;
+        }
+
+        let Ok(positioner) = self.positioner() else { return };
+        self.token = self.token.wrapping_add(1);
+        popup.reposition(&positioner, self.token);
+    }
+}
+
+/// The `xdg_wm_base` global, to create positioners from the windows.
+#[derive(Debug, Clone)]
+struct WmBase(XdgWmBase);
+
+impl ProvidesBoundGlobal<XdgWmBase, { XdgShell::API_VERSION_MAX }> for WmBase {
+    fn bound_global(&self) -> Result<XdgWmBase, GlobalError> {
+        Ok(self.0.clone())
     }
 }
 
@@ -1232,6 +1832,34 @@ fn resize_direction_to_xdg(direction: ResizeDirection) -> XdgResizeEdge {
     }
 }
 
+fn popup_anchor_to_xdg(anchor: PopupAnchor) -> XdgAnchor {
+    match anchor {
+        PopupAnchor::Center => XdgAnchor::None,
+        PopupAnchor::Top => XdgAnchor::Top,
+        PopupAnchor::Bottom => XdgAnchor::Bottom,
+        PopupAnchor::Left => XdgAnchor::Left,
+        PopupAnchor::Right => XdgAnchor::Right,
+        PopupAnchor::TopLeft => XdgAnchor::TopLeft,
+        PopupAnchor::BottomLeft => XdgAnchor::BottomLeft,
+        PopupAnchor::TopRight => XdgAnchor::TopRight,
+        PopupAnchor::BottomRight => XdgAnchor::BottomRight,
+    }
+}
+
+fn popup_gravity_to_xdg(gravity: PopupGravity) -> XdgGravity {
+    match gravity {
+        PopupGravity::Center => XdgGravity::None,
+        PopupGravity::Top => XdgGravity::Top,
+        PopupGravity::Bottom => XdgGravity::Bottom,
+        PopupGravity::Left => XdgGravity::Left,
+        PopupGravity::Right => XdgGravity::Right,
+        PopupGravity::TopLeft => XdgGravity::TopLeft,
+        PopupGravity::BottomLeft => XdgGravity::BottomLeft,
+        PopupGravity::TopRight => XdgGravity::TopRight,
+        PopupGravity::BottomRight => XdgGravity::BottomRight,
+    }
+}
+
 #[cfg(feature = "sctk-adwaita")]
 fn create_sctk_adwaita_config(theme: Option<Theme>) -> sctk_adwaita::FrameConfig {
     let config = match theme {
@@ -1243,3 +1871,57 @@ fn create_sctk_adwaita_config(theme: Option<Theme>) -> sctk_adwaita::FrameConfig
     let config = config.hide_titlebar(true);
     config
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layer_stretch_needs_zero_size_and_opposite_edges() {
+        let horizontal = LayerAnchor::LEFT | LayerAnchor::RIGHT;
+        let size = LogicalSize::new(0, 30);
+
+        let stretch = LayerStretch::new(horizontal | LayerAnchor::TOP, size);
+        assert_eq!(stretch, LayerStretch { horizontal: true, vertical: false });
+        assert_eq!(stretch.size(size), (0, 30));
+
+        // A given size is kept, even when anchored to both edges.
+        let stretch = LayerStretch::new(horizontal, LogicalSize::new(200, 30));
+        assert_eq!(stretch, LayerStretch::default());
+        assert_eq!(stretch.size(LogicalSize::new(200, 30)), (200, 30));
+
+        // A zero size can't be stretched without both edges.
+        let stretch =
+            LayerStretch::new(LayerAnchor::LEFT | LayerAnchor::TOP, LogicalSize::new(0, 0));
+        assert_eq!(stretch, LayerStretch::default());
+        assert_eq!(stretch.size(LogicalSize::new(0, 0)), (1, 1));
+
+        let all = horizontal | LayerAnchor::TOP | LayerAnchor::BOTTOM;
+        let stretch = LayerStretch::new(all, LogicalSize::new(0, 0));
+        assert_eq!(stretch, LayerStretch { horizontal: true, vertical: true });
+    }
+
+    #[test]
+    fn child_placement_uses_parent_scale() {
+        let placement = ChildPlacement::new(Some(PhysicalPosition::new(300, 150).into()), 1.5);
+        assert_eq!(placement.position, LogicalPosition::new(200, 100));
+        assert_eq!(placement.size(PhysicalSize::new(300, 150).into()), LogicalSize::new(200, 100));
+
+        // Logical values are kept as is.
+        let placement = ChildPlacement::new(Some(LogicalPosition::new(30, 40).into()), 2.);
+        assert_eq!(placement.position, LogicalPosition::new(30, 40));
+        assert_eq!(placement.size(LogicalSize::new(64., 48.).into()), LogicalSize::new(64, 48));
+    }
+
+    #[test]
+    fn child_placement_defaults_to_parent_origin() {
+        let placement = ChildPlacement::new(None, 2.);
+        assert_eq!(placement.position, LogicalPosition::new(0, 0));
+    }
+
+    #[test]
+    fn layer_resize_keeps_stretched_axes() {
+        let stretch = LayerStretch { horizontal: true, vertical: false };
+        assert_eq!(stretch.size(LogicalSize::new(640, 48)), (0, 48));
+    }
+}
@@ -71,6 +71,7 @@ pub(crate) mod event_loop;
 mod lock;
 pub(crate) mod main_thread;
 mod monitor;
+mod wake_lock;
 pub(crate) mod web_sys;
 pub(crate) mod window;
 
@@ -0,0 +1,149 @@
+//! Idle inhibition with the [Screen Wake Lock API].
+//!
+//! [Screen Wake Lock API]: https://developer.mozilla.org/en-US/docs/Web/API/Screen_Wake_Lock_API
+
+use std::cell::{OnceCell, RefCell};
+use std::rc::Rc;
+
+use js_sys::Promise;
+use tracing::error;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Document, DomException, Navigator, console};
+use winit_core::error::NotSupportedError;
+
+use crate::backend::{self, EventListenerHandle};
+
+/// A screen wake lock, which the browser releases on its own when the page is hidden.
+///
+/// The lock is requested again when the page becomes visible.
+#[derive(Default)]
+pub(crate) struct WakeLock {
+    state: Rc<RefCell<State>>,
+    on_visibility_change: OnceCell<EventListenerHandle<dyn FnMut(web_sys::Event)>>,
+}
+
+#[derive(Default)]
+struct State {
+    requested: bool,
+    pending: bool,
+    sentinel: Option<WakeLockSentinel>,
+}
+
+impl WakeLock {
+    pub(crate) fn set(
+        &self,
+        navigator: &Navigator,
+        document: &Document,
+        locked: bool,
+    ) -> Result<(), NotSupportedError> {
+        let mut state = self.state.borrow_mut();
+        state.requested = locked;
+
+        if !locked {
+            if let Some(sentinel) = state.sentinel.take() {
+                let _ = sentinel.release();
+            }
+            return Ok(());
+        }
+
+        drop(state);
+        request(&self.state, navigator)?;
+
+        self.on_visibility_change.get_or_init(|| {
+            let state = Rc::clone(&self.state);
+            let navigator = navigator.clone();
+            let document_clone = document.clone();
+            EventListenerHandle::new(
+                document.clone(),
+                "visibilitychange",
+                Closure::new(move |_| {
+                    if backend::is_visible(&document_clone) && state.borrow().requested {
+                        // The first request already checked that the API is supported.
+                        let _ = request(&state, &navigator);
+                    }
+                }),
+            )
+        });
+
+        Ok(())
+    }
+}
+
+/// Request the lock, unless it's held or already being requested.
+fn request(state: &Rc<RefCell<State>>, navigator: &Navigator) -> Result<(), NotSupportedError> {
+    let mut state_ref = state.borrow_mut();
+    if state_ref.pending || state_ref.sentinel.as_ref().is_some_and(|sentinel| !sentinel.released())
+    {
+        return Ok(());
+    }
+
+    let navigator: &NavigatorExt = navigator.unchecked_ref();
+    let Some(wake_lock) = navigator.wake_lock() else {
+        return Err(NotSupportedError::new("the Screen Wake Lock API is not supported"));
+    };
+
+    state_ref.pending = true;
+    let future = JsFuture::from(wake_lock.request("screen"));
+    let state = Rc::clone(state);
+    wasm_bindgen_futures::spawn_local(async move {
+        let result = future.await;
+        let mut state = state.borrow_mut();
+        state.pending = false;
+
+        match result {
+            Ok(sentinel) => {
+                let sentinel: WakeLockSentinel = sentinel.unchecked_into();
+                // The lock was lifted while the request was in flight.
+                if state.requested {
+                    state.sentinel = Some(sentinel);
+                } else {
+                    let _ = sentinel.release();
+                }
+            },
+            Err(error) => {
+                if let Some(error) = error.dyn_ref::<DomException>() {
+                    error!("Failed to acquire wake lock. {}: {}", error.name(), error.message());
+                } else {
+                    console::error_1(&error);
+                    error!("Failed to acquire wake lock");
+                }
+            },
+        }
+    });
+
+    Ok(())
+}
+
+impl Drop for WakeLock {
+    fn drop(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.requested = false;
+        if let Some(sentinel) = state.sentinel.take() {
+            let _ = sentinel.release();
+        }
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    type NavigatorExt;
+
+    #[wasm_bindgen(method, getter, js_name = wakeLock)]
+    fn wake_lock(this: &NavigatorExt) -> Option<WakeLockExt>;
+
+    type WakeLockExt;
+
+    #[wasm_bindgen(method)]
+    fn request(this: &WakeLockExt, kind: &str) -> Promise;
+
+    type WakeLockSentinel;
+
+    #[wasm_bindgen(method, getter)]
+    fn released(this: &WakeLockSentinel) -> bool;
+
+    #[wasm_bindgen(method)]
+    fn release(this: &WakeLockSentinel) -> JsValue;
+}
@@ -20,6 +20,7 @@ use crate::r#async::Dispatcher;
 use crate::event_loop::ActiveEventLoop;
 use crate::main_thread::MainThreadMarker;
 use crate::monitor::MonitorHandler;
+use crate::wake_lock::WakeLock;
 use crate::{backend, lock};
 
 pub struct Window {
@@ -38,6 +39,7 @@ pub struct Inner {
     monitor: Rc<MonitorHandler>,
     safe_area: Rc<backend::SafeAreaHandle>,
     canvas: Rc<backend::Canvas>,
+    wake_lock: WakeLock,
     destroy_fn: Option<Box<dyn FnOnce()>>,
 }
 
@@ -72,6 +74,7 @@ impl Window {
             monitor: Rc::clone(target.runner.monitor()),
             safe_area: Rc::clone(target.runner.safe_area()),
             canvas,
+            wake_lock: WakeLock::default(),
             destroy_fn: Some(destroy_fn),
         };
 
@@ -337,6 +340,12 @@ impl RootWindow for Window {
 
     fn set_content_protected(&self, _: bool) {}
 
+    fn set_idle_inhibited(&self, inhibited: bool) -> Result<(), RequestError> {
+        Ok(self.inner.queue(move |inner| {
+            inner.wake_lock.set(inner.canvas.navigator(), inner.canvas.document(), inhibited)
+        })?)
+    }
+
     fn title(&self) -> String {
         String::new()
     }
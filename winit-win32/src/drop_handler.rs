@@ -12,6 +12,7 @@ use windows_sys::Win32::System::Com::{DVASPECT_CONTENT, FORMATETC, TYMED_HGLOBAL
 use windows_sys::Win32::System::Ole::{CF_HDROP, DROPEFFECT_COPY, DROPEFFECT_NONE};
 use windows_sys::Win32::UI::Shell::{DragFinish, DragQueryFileW, HDROP};
 use windows_sys::core::{GUID, HRESULT};
+use winit_core::drag::DragActions;
 use winit_core::event::WindowEvent;
 
 use crate::definitions::{
@@ -91,7 +92,12 @@ impl FileDropHandler {
         let hdrop = unsafe { Self::iterate_filenames(pDataObj, |path| paths.push(path)) };
         drop_handler.valid = hdrop.is_some();
         if drop_handler.valid {
-            (drop_handler.send_event)(WindowEvent::DragEntered { paths, position });
+            (drop_handler.send_event)(WindowEvent::DragEntered {
+                paths,
+                mime_types: Vec::new(),
+                actions: DragActions::COPY,
+                position,
+            });
         }
         drop_handler.cursor_effect =
             if drop_handler.valid { DROPEFFECT_COPY } else { DROPEFFECT_NONE };
@@ -65,8 +65,8 @@ use winit_core::application::ApplicationHandler;
 use winit_core::cursor::{CustomCursor, CustomCursorSource};
 use winit_core::error::{EventLoopError, NotSupportedError, RequestError};
 use winit_core::event::{
-    DeviceEvent, DeviceId, FingerId, Force, Ime, RawKeyEvent, SurfaceSizeWriter, TabletToolButton,
-    TabletToolData, TabletToolKind, TabletToolTilt, TouchPhase, WindowEvent,
+    DeviceEvent, DeviceId, FingerId, Force, Ime, RawKeyEvent, ScrollSource, SurfaceSizeWriter,
+    TabletToolButton, TabletToolData, TabletToolKind, TabletToolTilt, TouchPhase, WindowEvent,
 };
 use winit_core::event_loop::pump_events::PumpStatus;
 use winit_core::event_loop::{
@@ -1629,8 +1629,8 @@ unsafe fn public_window_callback_inner(
         WM_MOUSEWHEEL => {
             use winit_core::event::MouseScrollDelta::LineDelta;
 
-            let value = (wparam >> 16) as i16;
-            let value = value as f32 / WHEEL_DELTA as f32;
+            let delta = (wparam >> 16) as i16;
+            let value = delta as f32 / WHEEL_DELTA as f32;
 
             update_modifiers(window, userdata);
 
@@ -1657,6 +1657,9 @@ unsafe fn public_window_callback_inner(
                 device_id: None,
                 delta: LineDelta(0.0, value * scroll_lines_multiplier as f32),
                 phase: TouchPhase::Moved,
+                source: ScrollSource::Unknown,
+                // `WHEEL_DELTA` is one step of 120.
+                value120: Some((0, delta.into())),
             });
 
             result = ProcResult::Value(0);
@@ -1665,8 +1668,8 @@ unsafe fn public_window_callback_inner(
         WM_MOUSEHWHEEL => {
             use winit_core::event::MouseScrollDelta::LineDelta;
 
-            let value = (wparam >> 16) as i16;
-            let value = -value as f32 / WHEEL_DELTA as f32; // NOTE: inverted! See https://github.com/rust-windowing/winit/pull/2105/
+            let delta = -i32::from((wparam >> 16) as i16);
+            let value = delta as f32 / WHEEL_DELTA as f32; // NOTE: inverted! See https://github.com/rust-windowing/winit/pull/2105/
 
             update_modifiers(window, userdata);
 
@@ -1690,6 +1693,8 @@ unsafe fn public_window_callback_inner(
                 device_id: None,
                 delta: LineDelta(value * scroll_characters_multiplier as f32, 0.0),
                 phase: TouchPhase::Moved,
+                source: ScrollSource::Unknown,
+                value120: Some((delta, 0)),
             });
 
             result = ProcResult::Value(0);
@@ -43,9 +43,16 @@ atom_manager! {
     XIM_SERVERS,
 
     // Assorted ICCCM Atoms
+    _NET_WM_ALLOWED_ACTIONS,
+    _NET_WM_BYPASS_COMPOSITOR,
+    _NET_WM_ACTION_CLOSE,
+    _NET_WM_ACTION_MAXIMIZE_HORZ,
+    _NET_WM_ACTION_MAXIMIZE_VERT,
+    _NET_WM_ACTION_MINIMIZE,
     _NET_WM_ICON,
     _NET_WM_MOVERESIZE,
     _NET_WM_NAME,
+    _NET_WM_OPAQUE_REGION,
     _NET_WM_PID,
     _NET_WM_PING,
     _NET_WM_SYNC_REQUEST,
@@ -57,6 +64,9 @@ atom_manager! {
     _NET_WM_STATE_HIDDEN,
     _NET_WM_STATE_MAXIMIZED_HORZ,
     _NET_WM_STATE_MAXIMIZED_VERT,
+    _NET_WM_STATE_MODAL,
+    _NET_WM_STRUT,
+    _NET_WM_STRUT_PARTIAL,
     _NET_WM_WINDOW_TYPE,
 
     // Activation atoms.
@@ -80,6 +90,18 @@ atom_manager! {
     _NET_WM_WINDOW_TYPE_DND,
     _NET_WM_WINDOW_TYPE_NORMAL,
 
+    // Selection Atoms
+    SAVE_TARGETS,
+    ClipboardSelection: b"CLIPBOARD",
+    Incr: b"INCR",
+    Multiple: b"MULTIPLE",
+    Targets: b"TARGETS",
+    Text: b"TEXT",
+    Timestamp: b"TIMESTAMP",
+    TextPlain: b"text/plain",
+    TextPlainUtf8: b"text/plain;charset=utf-8",
+    _WINIT_SELECTION,
+
     // Drag-N-Drop Atoms
     XdndAware,
     XdndEnter,
@@ -87,7 +109,9 @@ atom_manager! {
     XdndDrop,
     XdndPosition,
     XdndStatus,
-    XdndActionPrivate,
+    XdndActionCopy,
+    XdndActionMove,
+    XdndActionLink,
     XdndSelection,
     XdndFinished,
     XdndTypeList,
@@ -96,6 +120,7 @@ atom_manager! {
 
     // Miscellaneous Atoms
     _GTK_THEME_VARIANT,
+    _KDE_NET_WM_BLUR_BEHIND_REGION,
     _MOTIF_WM_HINTS,
     _NET_ACTIVE_WINDOW,
     _NET_CLIENT_LIST,
@@ -106,11 +131,11 @@ atom_manager! {
     _XSETTINGS_SETTINGS,
 
     // Stylus Atoms
-    ABS_X: b"Abs X",
-    ABS_Y: b"Abs Y",
     ABS_PRESSURE: b"Abs Pressure",
     ABS_TILT_X: b"Abs Tilt X",
-    ABS_TILT_Y: b"Abs Tilt Y"
+    ABS_TILT_Y: b"Abs Tilt Y",
+    ABS_RZ: b"Abs Rotary Z",
+    ABS_WHEEL: b"Abs Wheel"
 }
 
 impl Index<AtomName> for Atoms {
@@ -0,0 +1,702 @@
+//! X11 selection handling.
+//!
+//! Both reading and serving a selection go through a hidden helper window: conversions are
+//! requested on its behalf with `ConvertSelection`, and it is the window that owns the selection
+//! while we offer data, unless ownership was taken on behalf of one of our windows. See the ICCCM,
+//! section 2, for the protocol: <https://x.org/releases/X11R7.6/doc/xorg-docs/specs/ICCCM/icccm.html#use_of_selection_atoms>
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+use winit_core::clipboard::{
+    Clipboard, ClipboardData, ClipboardError, ClipboardEvent, TEXT_MIME_TYPE, is_text_mime_type,
+};
+use winit_core::error::RequestError;
+use winit_core::event_loop::AsyncRequestSerial;
+use x11_dl::xlib::{
+    self, XPropertyEvent, XSelectionClearEvent, XSelectionEvent, XSelectionRequestEvent,
+};
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xproto::{self, ConnectionExt as _};
+
+use crate::atoms::*;
+use crate::event_loop::X11Error;
+use crate::xdisplay::XConnection;
+
+/// Targets that are never reported as MIME types, since they're part of the protocol itself.
+const META_TARGETS: [AtomName; 4] = [Targets, Multiple, Timestamp, SAVE_TARGETS];
+
+/// How long the other client has to answer a conversion or take the next chunk of a transfer.
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A single X11 selection, such as `CLIPBOARD` or `PRIMARY`.
+pub(crate) struct Selection {
+    xconn: Arc<XConnection>,
+    /// The helper window used for transfers.
+    window: xproto::Window,
+    /// The window owning the selection while we offer data.
+    owner: Cell<xproto::Window>,
+    /// The selection atom.
+    selection: xproto::Atom,
+    state: RefCell<SelectionState>,
+}
+
+#[derive(Debug, Default)]
+struct SelectionState {
+    /// The contents we're offering while owning the selection.
+    offer: Option<Offer>,
+    /// Pending conversions, the front one is in flight.
+    requests: VecDeque<Request>,
+    /// Data received so far when the owner uses the `INCR` mechanism.
+    incremental: Option<Vec<u8>>,
+    /// The data we're sending with the `INCR` mechanism.
+    transfers: Vec<Transfer>,
+}
+
+#[derive(Debug)]
+struct Offer {
+    contents: Vec<ClipboardData>,
+    /// Every target we answer to and the index of the matching entry in `contents`.
+    targets: Vec<(xproto::Atom, usize)>,
+}
+
+#[derive(Debug)]
+struct Request {
+    serial: AsyncRequestSerial,
+    target: xproto::Atom,
+    /// The requested MIME type, `None` when requesting `TARGETS`.
+    mime_type: Option<String>,
+    /// When the request fails if the owner didn't answer, set once it's in flight.
+    deadline: Option<Instant>,
+}
+
+/// Data sent in chunks, each one once the requestor deleted the previous one.
+#[derive(Debug)]
+struct Transfer {
+    requestor: xproto::Window,
+    property: xproto::Atom,
+    ty: xproto::Atom,
+    data: Vec<u8>,
+    /// The amount of data sent so far.
+    offset: usize,
+    /// Whether the final, empty chunk was sent.
+    done: bool,
+    deadline: Instant,
+}
+
+impl fmt::Debug for Selection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Selection")
+            .field("window", &self.window)
+            .field("owner", &self.owner)
+            .field("selection", &self.selection)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Selection {
+    pub(crate) fn new(xconn: Arc<XConnection>, selection: xproto::Atom) -> Result<Self, X11Error> {
+        let root = xconn.default_root().root;
+        let window = xconn.xcb_connection().generate_id()?;
+        xconn.xcb_connection().create_window(
+            0,
+            window,
+            root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            xproto::WindowClass::INPUT_ONLY,
+            x11rb::COPY_FROM_PARENT,
+            &xproto::CreateWindowAux::new().event_mask(xproto::EventMask::PROPERTY_CHANGE),
+        )?;
+
+        Ok(Self { xconn, window, owner: Cell::new(window), selection, state: Default::default() })
+    }
+
+    /// Handle the reply to one of our conversion requests.
+    pub(crate) fn selection_notify(&self, xev: &XSelectionEvent) -> Option<ClipboardEvent> {
+        if xev.requestor as xproto::Window != self.window
+            || xev.selection as xproto::Atom != self.selection
+        {
+            return None;
+        }
+
+        let target = self.state.borrow().requests.front()?.target;
+        if xev.target as xproto::Atom != target {
+            return None;
+        }
+
+        if xev.property as xproto::Atom == x11rb::NONE {
+            let owner = self
+                .xconn
+                .xcb_connection()
+                .get_selection_owner(self.selection)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .map(|reply| reply.owner);
+            let error = if owner == Some(x11rb::NONE) {
+                ClipboardError::Empty
+            } else {
+                ClipboardError::MimeTypeUnavailable
+            };
+            return self.finish_request(Err(error));
+        }
+
+        let atoms = self.xconn.atoms();
+        match self.take_property() {
+            Ok((ty, _)) if ty == atoms[Incr] => {
+                // Deleting the property, which `take_property` did, tells the owner to start
+                // sending chunks. They arrive through `PropertyNotify`.
+                self.state.borrow_mut().incremental = Some(Vec::new());
+                None
+            },
+            Ok((_, data)) => self.finish_request(Ok(data)),
+            Err(err) => self.finish_request(Err(ClipboardError::TransferFailed(err.to_string()))),
+        }
+    }
+
+    /// Handle a chunk of an incremental transfer.
+    pub(crate) fn property_notify(&self, xev: &XPropertyEvent) -> Option<ClipboardEvent> {
+        if xev.state == xlib::PropertyDelete {
+            self.send_next_chunk(xev.window as xproto::Window, xev.atom as xproto::Atom);
+            return None;
+        }
+
+        if xev.window as xproto::Window != self.window
+            || xev.atom as xproto::Atom != self.xconn.atoms()[_WINIT_SELECTION]
+            || xev.state != xlib::PropertyNewValue
+            || self.state.borrow().incremental.is_none()
+        {
+            return None;
+        }
+
+        match self.take_property() {
+            Ok((_, chunk)) if chunk.is_empty() => {
+                let data = self.state.borrow_mut().incremental.take().unwrap_or_default();
+                self.finish_request(Ok(data))
+            },
+            Ok((_, chunk)) => {
+                let mut state = self.state.borrow_mut();
+                if let Some(data) = state.incremental.as_mut() {
+                    data.extend_from_slice(&chunk);
+                }
+                // Every chunk gives the owner more time.
+                if let Some(request) = state.requests.front_mut() {
+                    request.deadline = Some(Instant::now() + TRANSFER_TIMEOUT);
+                }
+                None
+            },
+            Err(err) => {
+                self.state.borrow_mut().incremental = None;
+                self.finish_request(Err(ClipboardError::TransferFailed(err.to_string())))
+            },
+        }
+    }
+
+    /// Send the next chunk of an incremental transfer, once the requestor deleted the last one.
+    fn send_next_chunk(&self, requestor: xproto::Window, property: xproto::Atom) {
+        let mut state = self.state.borrow_mut();
+        let Some(index) = state
+            .transfers
+            .iter()
+            .position(|transfer| transfer.requestor == requestor && transfer.property == property)
+        else {
+            return;
+        };
+
+        let transfer = &mut state.transfers[index];
+        if transfer.done {
+            state.transfers.remove(index);
+            return;
+        }
+
+        let chunk_size = self.chunk_size();
+        let end = transfer.data.len().min(transfer.offset + chunk_size);
+        let chunk = &transfer.data[transfer.offset..end];
+        let result = self.xconn.change_property(
+            requestor,
+            property,
+            transfer.ty,
+            xproto::PropMode::REPLACE,
+            chunk,
+        );
+        match result {
+            Ok(cookie) => cookie.ignore_error(),
+            Err(err) => {
+                warn!("Failed to send selection data: {err}");
+                state.transfers.remove(index);
+                return;
+            },
+        }
+
+        // The last chunk is empty.
+        transfer.done = chunk.is_empty();
+        transfer.offset = end;
+        transfer.deadline = Instant::now() + TRANSFER_TIMEOUT;
+        let _ = self.xconn.flush_requests();
+    }
+
+    /// The largest amount of data sent in a single request, leaving room for its header.
+    fn chunk_size(&self) -> usize {
+        (self.xconn.xcb_connection().maximum_request_bytes() / 4).max(1024)
+    }
+
+    /// The earliest time at which a transfer times out.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        let state = self.state.borrow();
+        let request = state.requests.front().and_then(|request| request.deadline);
+        let transfer = state.transfers.iter().map(|transfer| transfer.deadline).min();
+        request.into_iter().chain(transfer).min()
+    }
+
+    /// Fail the conversion request and drop the transfers whose other client doesn't answer.
+    pub(crate) fn expire(&self, now: Instant) -> Option<ClipboardEvent> {
+        let mut state = self.state.borrow_mut();
+        state.transfers.retain(|transfer| {
+            let expired = transfer.deadline <= now;
+            if expired {
+                warn!("Selection transfer to {} timed out", transfer.requestor);
+            }
+            !expired
+        });
+
+        let expired = state.requests.front()?.deadline.is_some_and(|deadline| deadline <= now);
+        if !expired {
+            return None;
+        }
+
+        state.incremental = None;
+        drop(state);
+        let error = "the selection owner didn't answer in time".to_owned();
+        self.finish_request(Err(ClipboardError::TransferFailed(error)))
+    }
+
+    /// Serve another client's conversion request.
+    pub(crate) fn selection_request(&self, xev: &XSelectionRequestEvent) {
+        if xev.owner as xproto::Window != self.owner.get()
+            || xev.selection as xproto::Atom != self.selection
+        {
+            return;
+        }
+
+        let requestor = xev.requestor as xproto::Window;
+        let target = xev.target as xproto::Atom;
+        // Obsolete clients don't set a property, the target is used instead.
+        let property = match xev.property as xproto::Atom {
+            x11rb::NONE => target,
+            property => property,
+        };
+
+        let property = match self.convert(requestor, target, property) {
+            Ok(true) => property,
+            Ok(false) => x11rb::NONE,
+            Err(err) => {
+                warn!("Failed to convert selection: {err}");
+                x11rb::NONE
+            },
+        };
+
+        let event = xproto::SelectionNotifyEvent {
+            response_type: xproto::SELECTION_NOTIFY_EVENT,
+            sequence: 0,
+            time: xev.time as xproto::Timestamp,
+            requestor,
+            selection: self.selection,
+            target,
+            property,
+        };
+        let result = self
+            .xconn
+            .xcb_connection()
+            .send_event(false, requestor, xproto::EventMask::NO_EVENT, event)
+            .map(|cookie| cookie.ignore_error());
+        if let Err(err) = result {
+            warn!("Failed to reply to selection request: {err}");
+        }
+        let _ = self.xconn.flush_requests();
+    }
+
+    /// Handle the loss of the selection to another client.
+    pub(crate) fn selection_clear(&self, xev: &XSelectionClearEvent) -> Option<ClipboardEvent> {
+        if xev.window as xproto::Window != self.owner.get()
+            || xev.selection as xproto::Atom != self.selection
+        {
+            return None;
+        }
+
+        self.lose_ownership()
+    }
+
+    /// Handle the destruction of one of our windows.
+    ///
+    /// The X server gives up the selection of a destroyed owner without telling it.
+    pub(crate) fn window_destroyed(&self, window: xproto::Window) -> Option<ClipboardEvent> {
+        if window != self.owner.get() {
+            return None;
+        }
+
+        self.lose_ownership()
+    }
+
+    /// Drop our offer, returning the event telling about it.
+    fn lose_ownership(&self) -> Option<ClipboardEvent> {
+        self.state.borrow_mut().offer.take()?;
+        if self.selection == xproto::AtomEnum::PRIMARY.into() {
+            Some(ClipboardEvent::PrimarySelectionOwnershipLost)
+        } else if self.selection == self.xconn.atoms()[ClipboardSelection] {
+            Some(ClipboardEvent::OwnershipLost)
+        } else {
+            None
+        }
+    }
+
+    /// Take ownership of the selection on behalf of `owner`, offering `contents`.
+    ///
+    /// The selection is lost when `owner` is destroyed.
+    pub(crate) fn set_contents_for(
+        &self,
+        owner: xproto::Window,
+        contents: Vec<ClipboardData>,
+    ) -> Result<(), RequestError> {
+        match self.take_ownership(owner, contents) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(RequestError::Ignored),
+            Err(err) => Err(os_error!(err).into()),
+        }
+    }
+
+    /// The targets offered while we own the selection.
+    pub(crate) fn targets(&self) -> Vec<xproto::Atom> {
+        let state = self.state.borrow();
+        state.offer.iter().flat_map(|offer| offer.targets.iter().map(|&(atom, _)| atom)).collect()
+    }
+
+    /// Write the data for `target` to `property` on the `requestor`.
+    ///
+    /// Returns `false` when the conversion was refused.
+    fn convert(
+        &self,
+        requestor: xproto::Window,
+        target: xproto::Atom,
+        property: xproto::Atom,
+    ) -> Result<bool, X11Error> {
+        let atoms = self.xconn.atoms();
+        let state = self.state.borrow();
+        let offer = match state.offer.as_ref() {
+            Some(offer) => offer,
+            None => return Ok(false),
+        };
+
+        if target == atoms[Targets] {
+            let targets: Vec<xproto::Atom> = [atoms[Targets], atoms[Timestamp]]
+                .into_iter()
+                .chain(offer.targets.iter().map(|&(atom, _)| atom))
+                .collect();
+            self.xconn
+                .change_property(
+                    requestor,
+                    property,
+                    xproto::AtomEnum::ATOM.into(),
+                    xproto::PropMode::REPLACE,
+                    &targets,
+                )?
+                .ignore_error();
+            return Ok(true);
+        }
+
+        let data = match offer.targets.iter().find(|&&(atom, _)| atom == target) {
+            Some(&(_, index)) => &offer.contents[index].data,
+            None => return Ok(false),
+        };
+
+        let ty = if target == atoms[Text] { atoms[UTF8_STRING] } else { target };
+        if data.len() > self.chunk_size() {
+            let data = data.clone();
+            drop(state);
+            return self.start_transfer(requestor, property, ty, data);
+        }
+
+        self.xconn
+            .change_property(requestor, property, ty, xproto::PropMode::REPLACE, data)?
+            .ignore_error();
+        Ok(true)
+    }
+
+    /// Start sending `data` in chunks with the `INCR` mechanism.
+    fn start_transfer(
+        &self,
+        requestor: xproto::Window,
+        property: xproto::Atom,
+        ty: xproto::Atom,
+        data: Vec<u8>,
+    ) -> Result<bool, X11Error> {
+        let conn = self.xconn.xcb_connection();
+
+        // The chunks are sent when the requestor deletes the property, keep the events we
+        // already selected on the window, which may be one of ours.
+        let event_mask = conn.get_window_attributes(requestor)?.reply()?.your_event_mask;
+        conn.change_window_attributes(
+            requestor,
+            &xproto::ChangeWindowAttributesAux::new()
+                .event_mask(event_mask | xproto::EventMask::PROPERTY_CHANGE),
+        )?
+        .ignore_error();
+
+        // The property holds a lower bound of the size of the data.
+        let size = u32::try_from(data.len()).unwrap_or(u32::MAX);
+        self.xconn
+            .change_property(
+                requestor,
+                property,
+                self.xconn.atoms()[Incr],
+                xproto::PropMode::REPLACE,
+                &[size],
+            )?
+            .ignore_error();
+
+        let mut state = self.state.borrow_mut();
+        state
+            .transfers
+            .retain(|transfer| transfer.requestor != requestor || transfer.property != property);
+        state.transfers.push(Transfer {
+            requestor,
+            property,
+            ty,
+            data,
+            offset: 0,
+            done: false,
+            deadline: Instant::now() + TRANSFER_TIMEOUT,
+        });
+        Ok(true)
+    }
+
+    /// Read and delete the transfer property on the helper window.
+    fn take_property(&self) -> Result<(xproto::Atom, Vec<u8>), X11Error> {
+        let reply = self
+            .xconn
+            .xcb_connection()
+            .get_property(
+                true,
+                self.window,
+                self.xconn.atoms()[_WINIT_SELECTION],
+                xproto::AtomEnum::ANY,
+                0,
+                u32::MAX,
+            )?
+            .reply()?;
+        Ok((reply.type_, reply.value))
+    }
+
+    /// Complete the request in flight and start the next one.
+    fn finish_request(&self, result: Result<Vec<u8>, ClipboardError>) -> Option<ClipboardEvent> {
+        let Some(request) = self.state.borrow_mut().requests.pop_front() else {
+            warn!("Received selection data without a request in flight");
+            return None;
+        };
+
+        let event = match request.mime_type {
+            Some(mime_type) => ClipboardEvent::Data {
+                serial: request.serial,
+                result: result.map(|data| ClipboardData::new(mime_type, data)),
+            },
+            None => ClipboardEvent::MimeTypes {
+                serial: request.serial,
+                result: result.and_then(|data| self.parse_targets(&data)),
+            },
+        };
+
+        self.send_next_request();
+
+        Some(event)
+    }
+
+    fn parse_targets(&self, data: &[u8]) -> Result<Vec<String>, ClipboardError> {
+        let targets = data
+            .chunks_exact(4)
+            .map(|chunk| xproto::Atom::from_ne_bytes(chunk.try_into().unwrap()));
+        target_mime_types(&self.xconn, targets)
+            .map_err(|err| ClipboardError::TransferFailed(err.to_string()))
+    }
+
+    fn send_next_request(&self) {
+        let target = match self.state.borrow_mut().requests.front_mut() {
+            Some(request) => {
+                request.deadline = Some(Instant::now() + TRANSFER_TIMEOUT);
+                request.target
+            },
+            None => return,
+        };
+
+        let result = self.xconn.xcb_connection().convert_selection(
+            self.window,
+            self.selection,
+            target,
+            self.xconn.atoms()[_WINIT_SELECTION],
+            self.xconn.timestamp(),
+        );
+        match result {
+            Ok(cookie) => cookie.ignore_error(),
+            Err(err) => warn!("Failed to request selection conversion: {err}"),
+        }
+        let _ = self.xconn.flush_requests();
+    }
+
+    fn queue_request(
+        &self,
+        serial: AsyncRequestSerial,
+        target: xproto::Atom,
+        mime_type: Option<String>,
+    ) {
+        let mut state = self.state.borrow_mut();
+        state.requests.push_back(Request { serial, target, mime_type, deadline: None });
+        let idle = state.requests.len() == 1;
+        drop(state);
+
+        if idle {
+            self.send_next_request();
+        }
+    }
+
+    /// Request the selection converted to `target`, delivered as `mime_type` with
+    /// [`ClipboardEvent::Data`] for `serial`.
+    pub(crate) fn request_target(
+        &self,
+        serial: AsyncRequestSerial,
+        target: xproto::Atom,
+        mime_type: String,
+    ) {
+        self.queue_request(serial, target, Some(mime_type));
+    }
+
+    fn intern_atom(&self, name: &str) -> Result<xproto::Atom, X11Error> {
+        Ok(self.xconn.xcb_connection().intern_atom(false, name.as_bytes())?.reply()?.atom)
+    }
+
+    /// Take ownership of the selection on behalf of `owner` and offer `contents`.
+    ///
+    /// Returns `false` when the X server refused the ownership change.
+    pub(crate) fn take_ownership(
+        &self,
+        owner: xproto::Window,
+        contents: Vec<ClipboardData>,
+    ) -> Result<bool, X11Error> {
+        let atoms = self.xconn.atoms();
+        let text_targets = [
+            atoms[UTF8_STRING],
+            xproto::AtomEnum::STRING.into(),
+            atoms[Text],
+            atoms[TextPlain],
+            atoms[TextPlainUtf8],
+        ];
+
+        let mut targets = Vec::new();
+        for (index, data) in contents.iter().enumerate() {
+            let mut add = |atom| {
+                if !targets.iter().any(|&(target, _)| target == atom) {
+                    targets.push((atom, index));
+                }
+            };
+
+            if data.is_text() {
+                text_targets.into_iter().for_each(&mut add);
+            }
+            add(self.intern_atom(&data.mime_type)?);
+        }
+
+        let conn = self.xconn.xcb_connection();
+        conn.set_selection_owner(owner, self.selection, self.xconn.timestamp())?;
+        if conn.get_selection_owner(self.selection)?.reply()?.owner != owner {
+            return Ok(false);
+        }
+
+        self.owner.set(owner);
+        self.state.borrow_mut().offer = Some(Offer { contents, targets });
+        Ok(true)
+    }
+}
+
+impl Clipboard for Selection {
+    fn set_contents(&self, contents: Vec<ClipboardData>) -> Result<(), RequestError> {
+        self.set_contents_for(self.window, contents)
+    }
+
+    fn clear(&self) -> Result<(), RequestError> {
+        if self.state.borrow_mut().offer.take().is_none() {
+            return Ok(());
+        }
+
+        // The owner window may have been destroyed in the meantime, in which case the selection
+        // could already belong to someone else.
+        let conn = self.xconn.xcb_connection();
+        let owner = conn
+            .get_selection_owner(self.selection)
+            .map_err(|err| os_error!(X11Error::from(err)))?
+            .reply()
+            .map_err(|err| os_error!(X11Error::from(err)))?
+            .owner;
+        if owner != self.owner.get() {
+            return Ok(());
+        }
+
+        conn.set_selection_owner(x11rb::NONE, self.selection, self.xconn.timestamp())
+            .map_err(|err| os_error!(X11Error::from(err)))?
+            .ignore_error();
+        self.xconn.flush_requests().map_err(|err| os_error!(X11Error::from(err)))?;
+        Ok(())
+    }
+
+    fn request_mime_types(&self) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = AsyncRequestSerial::get();
+        self.queue_request(serial, self.xconn.atoms()[Targets], None);
+        Ok(serial)
+    }
+
+    fn request_data(&self, mime_type: &str) -> Result<AsyncRequestSerial, RequestError> {
+        let target = if is_text_mime_type(mime_type) {
+            self.xconn.atoms()[UTF8_STRING]
+        } else {
+            self.intern_atom(mime_type).map_err(|err| os_error!(err))?
+        };
+
+        let serial = AsyncRequestSerial::get();
+        self.request_target(serial, target, mime_type.to_owned());
+        Ok(serial)
+    }
+}
+
+/// The MIME types of the selection `targets`, leaving out the protocol targets.
+pub(crate) fn target_mime_types(
+    xconn: &XConnection,
+    targets: impl IntoIterator<Item = xproto::Atom>,
+) -> Result<Vec<String>, X11Error> {
+    let atoms = xconn.atoms();
+    let conn = xconn.xcb_connection();
+
+    let cookies = targets
+        .into_iter()
+        .filter(|&atom| atom != x11rb::NONE)
+        .filter(|atom| META_TARGETS.iter().all(|&name| atoms[name] != *atom))
+        .map(|atom| conn.get_atom_name(atom))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut mime_types = Vec::with_capacity(cookies.len());
+    for cookie in cookies {
+        mime_types.push(String::from_utf8_lossy(&cookie.reply()?.name).into_owned());
+    }
+
+    // Legacy text targets are reported under the common MIME type as well.
+    if mime_types.iter().any(|mime_type| is_text_mime_type(mime_type))
+        && !mime_types.iter().any(|mime_type| mime_type == TEXT_MIME_TYPE)
+    {
+        mime_types.insert(0, TEXT_MIME_TYPE.to_owned());
+    }
+
+    Ok(mime_types)
+}
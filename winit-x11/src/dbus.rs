@@ -0,0 +1,280 @@
+//! Inhibition of the screensaver through the `org.freedesktop.ScreenSaver` D-Bus service, for
+//! servers without the MIT-SCREEN-SAVER extension.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use tracing::warn;
+use zbus::blocking::Connection;
+use zbus::blocking::connection::Builder;
+
+const DESTINATION: &str = "org.freedesktop.ScreenSaver";
+const PATH: &str = "/org/freedesktop/ScreenSaver";
+const INTERFACE: &str = "org.freedesktop.ScreenSaver";
+
+/// How long to wait for the reply of the service.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connect to the session bus.
+pub fn session() -> zbus::Result<Connection> {
+    Builder::session()?.method_timeout(TIMEOUT).build()
+}
+
+/// A client of `org.freedesktop.ScreenSaver`.
+///
+/// The calls are made from a worker thread, over a single connection to the session bus opened on
+/// the first inhibition, so the caller never waits for the bus. This makes the inhibition best
+/// effort: failures are only logged.
+#[derive(Debug)]
+pub struct ScreenSaver {
+    sender: Sender<Request>,
+    next_id: AtomicU64,
+}
+
+#[derive(Debug)]
+enum Request {
+    Inhibit { id: u64, application: String, reason: String },
+    UnInhibit { id: u64 },
+}
+
+impl ScreenSaver {
+    /// Start the worker, which opens its connection with `connect`.
+    pub fn new<F>(connect: F) -> Self
+    where
+        F: FnMut() -> zbus::Result<Connection> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        // The worker stops once the sender is dropped.
+        thread::Builder::new()
+            .name("winit screensaver".into())
+            .spawn(move || screensaver_worker(connect, receiver))
+            .expect("failed to spawn the screensaver thread");
+
+        Self { sender, next_id: AtomicU64::new(0) }
+    }
+
+    /// Inhibit the screensaver until the returned inhibitor is dropped.
+    pub fn inhibit(&self, application: &str, reason: &str) -> ScreenSaverInhibitor {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let _ = self.sender.send(Request::Inhibit {
+            id,
+            application: application.to_owned(),
+            reason: reason.to_owned(),
+        });
+
+        ScreenSaverInhibitor { sender: self.sender.clone(), id }
+    }
+}
+
+/// Inhibition of the screensaver through `org.freedesktop.ScreenSaver`, lifted when dropped.
+#[derive(Debug)]
+pub struct ScreenSaverInhibitor {
+    sender: Sender<Request>,
+    id: u64,
+}
+
+impl Drop for ScreenSaverInhibitor {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Request::UnInhibit { id: self.id });
+    }
+}
+
+/// Call `Inhibit`, returning the cookie identifying the inhibition.
+fn inhibit(connection: &Connection, application: &str, reason: &str) -> zbus::Result<u32> {
+    let reply = connection.call_method(
+        Some(DESTINATION),
+        PATH,
+        Some(INTERFACE),
+        "Inhibit",
+        &(application, reason),
+    )?;
+    reply.body().deserialize()
+}
+
+/// Call `UnInhibit` with the cookie returned by `Inhibit`.
+fn uninhibit(connection: &Connection, cookie: u32) -> zbus::Result<()> {
+    connection.call_method(Some(DESTINATION), PATH, Some(INTERFACE), "UnInhibit", &(cookie,))?;
+    Ok(())
+}
+
+fn screensaver_worker<F>(mut connect: F, receiver: Receiver<Request>)
+where
+    F: FnMut() -> zbus::Result<Connection>,
+{
+    let mut connection = None;
+    // The cookies of the service for our inhibitions.
+    let mut cookies = HashMap::new();
+
+    for request in receiver {
+        let result = match request {
+            Request::Inhibit { id, application, reason } => {
+                let connection = match connection.as_mut() {
+                    Some(connection) => connection,
+                    None => match connect() {
+                        Ok(new) => connection.insert(new),
+                        Err(err) => {
+                            warn!("Failed to connect to the session bus: {err}");
+                            continue;
+                        },
+                    },
+                };
+
+                inhibit(connection, &application, &reason)
+                    .map(|cookie| {
+                        cookies.insert(id, cookie);
+                    })
+                    .map_err(|err| ("inhibit", err))
+            },
+            Request::UnInhibit { id } => {
+                let (Some(cookie), Some(connection)) = (cookies.remove(&id), connection.as_ref())
+                else {
+                    continue;
+                };
+
+                uninhibit(connection, cookie).map_err(|err| ("lift the inhibition of", err))
+            },
+        };
+
+        if let Err((action, err)) = result {
+            warn!("Failed to {action} the screensaver: {err}");
+            // Anything but an error reply of the service, like a broken connection or a malformed
+            // reply, leaves the connection in an unknown state. The service lifts the inhibitions
+            // of a client when it disconnects, so start over.
+            if !matches!(err, zbus::Error::MethodError(..)) {
+                connection = None;
+                cookies.clear();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+/// Tests against a private session bus, run with `cargo test -- --ignored` when `dbus-daemon` is
+/// installed.
+mod tests {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+
+    use super::*;
+
+    struct Daemon {
+        child: Child,
+        address: String,
+    }
+
+    impl Daemon {
+        fn spawn() -> Self {
+            let mut child = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("failed to spawn dbus-daemon");
+
+            let mut address = String::new();
+            BufReader::new(child.stdout.as_mut().unwrap()).read_line(&mut address).unwrap();
+            let address = address.trim().to_owned();
+            Self { child, address }
+        }
+
+        fn connect(&self) -> Connection {
+            Builder::address(self.address.as_str())
+                .unwrap()
+                .method_timeout(TIMEOUT)
+                .build()
+                .unwrap()
+        }
+    }
+
+    impl Drop for Daemon {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    /// A method call received by a mock service.
+    #[derive(Debug, PartialEq)]
+    enum Call {
+        Inhibit(String, String),
+        UnInhibit(u32),
+    }
+
+    struct MockScreenSaver {
+        calls: Sender<Call>,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.ScreenSaver")]
+    impl MockScreenSaver {
+        fn inhibit(&self, application: String, reason: String) -> u32 {
+            self.calls.send(Call::Inhibit(application, reason)).unwrap();
+            42
+        }
+
+        fn un_inhibit(&self, cookie: u32) {
+            self.calls.send(Call::UnInhibit(cookie)).unwrap();
+        }
+    }
+
+    /// A service replying to `Inhibit` without a cookie.
+    struct MalformedScreenSaver;
+
+    #[zbus::interface(name = "org.freedesktop.ScreenSaver")]
+    impl MalformedScreenSaver {
+        fn inhibit(&self, _application: String, _reason: String) -> String {
+            String::from("cookie")
+        }
+    }
+
+    fn serve<I: zbus::object_server::Interface>(daemon: &Daemon, service: I) -> Connection {
+        Builder::address(daemon.address.as_str())
+            .unwrap()
+            .name(DESTINATION)
+            .unwrap()
+            .serve_at(PATH, service)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn missing_screensaver() {
+        let daemon = Daemon::spawn();
+        let err = inhibit(&daemon.connect(), "winit", "test").unwrap_err();
+        let zbus::Error::MethodError(name, ..) = err else {
+            panic!("expected an error reply, got {err:?}");
+        };
+        assert_eq!(name.as_str(), "org.freedesktop.DBus.Error.ServiceUnknown");
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn malformed_reply() {
+        let daemon = Daemon::spawn();
+        let _service = serve(&daemon, MalformedScreenSaver);
+        let err = inhibit(&daemon.connect(), "winit", "test").unwrap_err();
+        assert!(!matches!(err, zbus::Error::MethodError(..)), "{err:?}");
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn screensaver_service() {
+        let daemon = Daemon::spawn();
+        let (sender, calls) = mpsc::channel();
+        let _service = serve(&daemon, MockScreenSaver { calls: sender });
+
+        let address = daemon.address.clone();
+        let screensaver = ScreenSaver::new(move || Builder::address(address.as_str())?.build());
+        let inhibitor = screensaver.inhibit("winit", "test");
+        let call = calls.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!(call, Call::Inhibit("winit".into(), "test".into()));
+
+        drop(inhibitor);
+        assert_eq!(calls.recv_timeout(TIMEOUT).unwrap(), Call::UnInhibit(42));
+    }
+}
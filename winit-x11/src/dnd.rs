@@ -3,20 +3,26 @@ use std::os::raw::*;
 use std::path::{Path, PathBuf};
 use std::str::Utf8Error;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use dpi::PhysicalPosition;
 use percent_encoding::percent_decode;
+use winit_core::clipboard::{ClipboardData, is_text_mime_type};
+use winit_core::drag::{DragAction, DragActions};
+use winit_core::event_loop::AsyncRequestSerial;
+use winit_core::window::WindowId;
 use x11rb::protocol::xproto::{self, ConnectionExt};
 
 use crate::atoms::AtomName::None as DndNone;
 use crate::atoms::*;
+use crate::clipboard::Selection;
 use crate::event_loop::{CookieResultExt, X11Error};
 use crate::util;
-use crate::xdisplay::XConnection;
+use crate::xdisplay::{GrabOwner, XConnection};
 
 #[derive(Debug, Clone, Copy)]
 pub enum DndState {
-    Accepted,
+    Accepted(DragAction),
     Rejected,
 }
 
@@ -41,20 +47,129 @@ impl From<io::Error> for DndDataParseError {
     }
 }
 
+/// How long the drop target has to answer `XdndDrop` with `XdndFinished`.
+pub const FINISH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A drag and drop request from a window, handled by the event loop.
+#[derive(Debug)]
+pub enum DragRequest {
+    /// Start a drag with `Window::start_drag`.
+    Start {
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        contents: Vec<ClipboardData>,
+        actions: DragActions,
+    },
+    /// Accept the drop with `Window::accept_drag`, or reject it when `None`.
+    Accept { window_id: WindowId, accepted: Option<(String, DragAction)> },
+    /// Read the dragged data with `Window::request_drag_data`.
+    Data { window_id: WindowId, serial: AsyncRequestSerial, mime_type: String },
+}
+
+/// A drag out of one of our windows, for which we're the XDND source.
+#[derive(Debug)]
+pub struct DragSource {
+    pub window: xproto::Window,
+    pub serial: AsyncRequestSerial,
+    pub actions: DragActions,
+    /// The targets the data is offered as.
+    pub types: Vec<xproto::Atom>,
+    /// The XDND aware window under the pointer and the protocol version used with it.
+    pub target: Option<(xproto::Window, u8)>,
+    /// The action of the last `XdndStatus` from the target, if it accepts the drop.
+    pub accepted: Option<DragAction>,
+    /// Whether an `XdndPosition` wasn't answered with an `XdndStatus` yet.
+    pub awaiting_status: bool,
+    /// The pointer position to send once the target answers, in root coordinates.
+    pub pending_position: Option<(i16, i16, xproto::Timestamp)>,
+    /// Whether `XdndDrop` was sent and we're waiting for `XdndFinished`.
+    pub dropped: bool,
+    /// When we stop waiting for `XdndFinished`.
+    pub deadline: Option<Instant>,
+}
+
+impl DragSource {
+    pub fn new(
+        window: xproto::Window,
+        serial: AsyncRequestSerial,
+        actions: DragActions,
+        types: Vec<xproto::Atom>,
+    ) -> Self {
+        DragSource {
+            window,
+            serial,
+            actions,
+            types,
+            target: None,
+            accepted: None,
+            awaiting_status: false,
+            pending_position: None,
+            dropped: false,
+            deadline: None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Dnd {
     xconn: Arc<XConnection>,
     // Populated by XdndEnter event handler
     pub version: Option<c_long>,
     pub type_list: Option<Vec<xproto::Atom>>,
+    pub mime_types: Vec<String>,
     // Populated by XdndPosition event handler
     pub source_window: Option<xproto::Window>,
     // Populated by XdndPosition event handler
+    pub window: Option<xproto::Window>,
+    // Populated by XdndPosition event handler
     pub position: PhysicalPosition<f64>,
+    // Populated by XdndPosition event handler
+    pub source_action: Option<DragAction>,
+    // The action we accept the drop with, if any
+    pub accepted: Option<DragAction>,
+    // Whether the paths were requested, which happens on the first XdndPosition
+    pub paths_requested: bool,
     // Populated by SelectionNotify event handler (triggered by XdndPosition event handler)
     pub result: Option<Result<Vec<PathBuf>, DndDataParseError>>,
-    // Populated by SelectionNotify event handler (triggered by XdndPosition event handler)
+    // Whether `DragEntered` was sent
     pub dragging: bool,
+    // Populated by XdndDrop event handler, `XdndFinished` is sent once the requested data is read
+    pub dropped: bool,
+    // Pending `Window::request_drag_data` requests and the window they're for
+    pub data_requests: DataRequests,
+}
+
+/// The pending `Window::request_drag_data` requests.
+#[derive(Debug, Default)]
+pub struct DataRequests {
+    /// The requests for the current drag, which the drop waits for.
+    current: Vec<(AsyncRequestSerial, xproto::Window)>,
+    /// The requests of previous drags, still answered to their window.
+    previous: Vec<(AsyncRequestSerial, xproto::Window)>,
+}
+
+impl DataRequests {
+    pub fn push(&mut self, serial: AsyncRequestSerial, window: xproto::Window) {
+        self.current.push((serial, window));
+    }
+
+    /// Remove the request with `serial`, returning the window it's for.
+    pub fn remove(&mut self, serial: AsyncRequestSerial) -> Option<xproto::Window> {
+        [&mut self.current, &mut self.previous].into_iter().find_map(|requests| {
+            let index = requests.iter().position(|&(request, _)| request == serial)?;
+            Some(requests.remove(index).1)
+        })
+    }
+
+    /// Whether the current drag has no pending request.
+    pub fn is_empty(&self) -> bool {
+        self.current.is_empty()
+    }
+
+    /// Detach the pending requests from the drag that ended.
+    pub fn reset(&mut self) {
+        self.previous.append(&mut self.current);
+    }
 }
 
 impl Dnd {
@@ -63,19 +178,61 @@ impl Dnd {
             xconn,
             version: None,
             type_list: None,
+            mime_types: Vec::new(),
             source_window: None,
+            window: None,
             position: PhysicalPosition::default(),
+            source_action: None,
+            accepted: None,
+            paths_requested: false,
             result: None,
             dragging: false,
+            dropped: false,
+            data_requests: DataRequests::default(),
         })
     }
 
     pub fn reset(&mut self) {
         self.version = None;
         self.type_list = None;
+        self.mime_types.clear();
         self.source_window = None;
+        self.window = None;
+        self.source_action = None;
+        self.accepted = None;
+        self.paths_requested = false;
         self.result = None;
         self.dragging = false;
+        self.dropped = false;
+        self.data_requests.reset();
+    }
+
+    /// The actions the source allows.
+    pub fn source_actions(&self) -> DragActions {
+        // Only the action proposed with `XdndPosition` is known.
+        self.source_action.map_or(DragActions::COPY, DragActions::from)
+    }
+
+    /// The offered target to read `mime_type` as.
+    pub fn offered_target(&self, mime_type: &str) -> Option<xproto::Atom> {
+        let atoms = self.xconn.atoms();
+        let type_list = self.type_list.as_deref()?;
+        if is_text_mime_type(mime_type) {
+            [
+                atoms[UTF8_STRING],
+                atoms[TextPlainUtf8],
+                atoms[TextPlain],
+                xproto::AtomEnum::STRING.into(),
+                atoms[Text],
+            ]
+            .into_iter()
+            .find(|target| type_list.contains(target))
+        } else {
+            let target =
+                self.xconn.xcb_connection().intern_atom(true, mime_type.as_bytes()).ok()?;
+            let target = target.reply().ok()?.atom;
+            (target != x11rb::NONE && type_list.contains(&target)).then_some(target)
+        }
     }
 
     pub unsafe fn send_status(
@@ -86,7 +243,7 @@ impl Dnd {
     ) -> Result<(), X11Error> {
         let atoms = self.xconn.atoms();
         let (accepted, action) = match state {
-            DndState::Accepted => (1, atoms[XdndActionPrivate]),
+            DndState::Accepted(action) => (1, self.action_atom(action)),
             DndState::Rejected => (0, atoms[DndNone]),
         };
         self.xconn
@@ -110,7 +267,7 @@ impl Dnd {
     ) -> Result<(), X11Error> {
         let atoms = self.xconn.atoms();
         let (accepted, action) = match state {
-            DndState::Accepted => (1, atoms[XdndActionPrivate]),
+            DndState::Accepted(action) => (1, self.action_atom(action)),
             DndState::Rejected => (0, atoms[DndNone]),
         };
         self.xconn
@@ -160,6 +317,203 @@ impl Dnd {
         self.xconn.get_property(window, atoms[XdndSelection], atoms[TextUriList])
     }
 
+    /// Become the XDND source from `window`, offering `contents` through `selection`.
+    ///
+    /// Returns the offered targets, or `None` when no pointer button is held or the pointer
+    /// can't be grabbed.
+    pub fn start_source(
+        &self,
+        window: xproto::Window,
+        selection: &Selection,
+        contents: Vec<ClipboardData>,
+    ) -> Result<Option<Vec<xproto::Atom>>, X11Error> {
+        let atoms = self.xconn.atoms();
+        let conn = self.xconn.xcb_connection();
+
+        let buttons = xproto::KeyButMask::BUTTON1
+            | xproto::KeyButMask::BUTTON2
+            | xproto::KeyButMask::BUTTON3
+            | xproto::KeyButMask::BUTTON4
+            | xproto::KeyButMask::BUTTON5;
+        if !conn.query_pointer(window)?.reply()?.mask.intersects(buttons) {
+            return Ok(None);
+        }
+
+        if !selection.take_ownership(window, contents)? {
+            return Ok(None);
+        }
+
+        let types = selection.targets();
+        if types.len() > 3 {
+            self.xconn
+                .change_property(
+                    window,
+                    atoms[XdndTypeList],
+                    xproto::AtomEnum::ATOM.into(),
+                    xproto::PropMode::REPLACE,
+                    &types,
+                )?
+                .ignore_error();
+        }
+
+        // Grab the pointer to follow it over other clients' windows.
+        let status = conn
+            .grab_pointer(
+                false,
+                window,
+                xproto::EventMask::POINTER_MOTION | xproto::EventMask::BUTTON_RELEASE,
+                xproto::GrabMode::ASYNC,
+                xproto::GrabMode::ASYNC,
+                x11rb::NONE,
+                x11rb::NONE,
+                self.xconn.timestamp(),
+            )?
+            .reply()?
+            .status;
+        if status != xproto::GrabStatus::SUCCESS {
+            return Ok(None);
+        }
+        *self.xconn.pointer_grab.lock().unwrap() = Some(GrabOwner::Drag(window));
+
+        Ok(Some(types))
+    }
+
+    /// Find the XDND aware window at the given root coordinates.
+    pub fn find_target(
+        &self,
+        root: xproto::Window,
+        x: i16,
+        y: i16,
+    ) -> Result<Option<(xproto::Window, u8)>, X11Error> {
+        let atoms = self.xconn.atoms();
+        let mut window = root;
+        loop {
+            // The root window is a target too, when the desktop is drawn on it.
+            let version = self
+                .xconn
+                .get_property::<xproto::Atom>(
+                    window,
+                    atoms[XdndAware],
+                    xproto::AtomEnum::ATOM.into(),
+                )
+                .ok()
+                .and_then(|version| version.first().copied());
+            // Versions before 3 are obsolete.
+            if let Some(version @ 3..) = version {
+                return Ok(Some((window, version.min(5) as u8)));
+            }
+
+            let reply =
+                self.xconn.xcb_connection().translate_coordinates(root, window, x, y)?.reply()?;
+            if reply.child == x11rb::NONE {
+                return Ok(None);
+            }
+            window = reply.child;
+        }
+    }
+
+    pub fn send_enter(
+        &self,
+        source: &DragSource,
+        target_window: xproto::Window,
+        version: u8,
+    ) -> Result<(), X11Error> {
+        let atoms = self.xconn.atoms();
+        let more_types = source.types.len() > 3;
+        let mut types = [x11rb::NONE; 3];
+        for (slot, &ty) in types.iter_mut().zip(&source.types) {
+            *slot = ty;
+        }
+        let [a, b, c] = types;
+        self.xconn
+            .send_client_msg(target_window, target_window, atoms[XdndEnter], None, [
+                source.window,
+                (version as u32) << 24 | more_types as u32,
+                a,
+                b,
+                c,
+            ])?
+            .ignore_error();
+
+        Ok(())
+    }
+
+    pub fn send_position(
+        &self,
+        source: &DragSource,
+        target_window: xproto::Window,
+        (x, y, time): (i16, i16, xproto::Timestamp),
+    ) -> Result<(), X11Error> {
+        let atoms = self.xconn.atoms();
+        let action =
+            source.actions.preferred().map_or(atoms[DndNone], |action| self.action_atom(action));
+        self.xconn
+            .send_client_msg(target_window, target_window, atoms[XdndPosition], None, [
+                source.window,
+                0,
+                (x as u16 as u32) << 16 | y as u16 as u32,
+                time,
+                action,
+            ])?
+            .ignore_error();
+
+        Ok(())
+    }
+
+    pub fn send_leave(
+        &self,
+        source: &DragSource,
+        target_window: xproto::Window,
+    ) -> Result<(), X11Error> {
+        let atoms = self.xconn.atoms();
+        self.xconn
+            .send_client_msg(target_window, target_window, atoms[XdndLeave], None, [
+                source.window,
+                0,
+                0,
+                0,
+                0,
+            ])?
+            .ignore_error();
+
+        Ok(())
+    }
+
+    pub fn send_drop(
+        &self,
+        source: &DragSource,
+        target_window: xproto::Window,
+        time: xproto::Timestamp,
+    ) -> Result<(), X11Error> {
+        let atoms = self.xconn.atoms();
+        self.xconn
+            .send_client_msg(target_window, target_window, atoms[XdndDrop], None, [
+                source.window,
+                0,
+                time,
+                0,
+                0,
+            ])?
+            .ignore_error();
+
+        Ok(())
+    }
+
+    pub fn action_atom(&self, action: DragAction) -> xproto::Atom {
+        let atoms = self.xconn.atoms();
+        match action {
+            DragAction::Copy => atoms[XdndActionCopy],
+            DragAction::Move => atoms[XdndActionMove],
+            DragAction::Link => atoms[XdndActionLink],
+        }
+    }
+
+    pub fn atom_action(&self, atom: xproto::Atom) -> Option<DragAction> {
+        [DragAction::Copy, DragAction::Move, DragAction::Link]
+            .into_iter()
+            .find(|&action| self.action_atom(action) == atom)
+    }
+
     pub fn parse_data(&self, data: &mut [c_uchar]) -> Result<Vec<PathBuf>, DndDataParseError> {
         if !data.is_empty() {
             let mut path_list = Vec::new();
@@ -189,3 +543,27 @@ impl Dnd {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_requests_outlive_their_drag() {
+        let (first, second) = (AsyncRequestSerial::get(), AsyncRequestSerial::get());
+        let mut requests = DataRequests::default();
+        requests.push(first, 1);
+        assert!(!requests.is_empty());
+
+        // The next drop doesn't wait for the request of the previous drag.
+        requests.reset();
+        assert!(requests.is_empty());
+
+        requests.push(second, 2);
+        assert_eq!(requests.remove(first), Some(1));
+        assert!(!requests.is_empty());
+        assert_eq!(requests.remove(second), Some(2));
+        assert!(requests.is_empty());
+        assert_eq!(requests.remove(second), None);
+    }
+}
@@ -18,9 +18,13 @@ use libc::{LC_CTYPE, setlocale};
 use tracing::warn;
 use winit_common::xkb::Context;
 use winit_core::application::ApplicationHandler;
+use winit_core::clipboard::Clipboard;
 use winit_core::cursor::{CustomCursor as CoreCustomCursor, CustomCursorSource};
 use winit_core::error::{EventLoopError, RequestError};
-use winit_core::event::{DeviceId, StartCause, WindowEvent};
+use winit_core::event::{
+    DeviceId, DeviceInfo as CoreDeviceInfo, DeviceKind, Force, ScrollSource, StartCause,
+    TabletToolData, TabletToolKind, TabletToolTilt, WindowEvent,
+};
 use winit_core::event_loop::pump_events::PumpStatus;
 use winit_core::event_loop::{
     ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents,
@@ -32,12 +36,13 @@ use winit_core::window::{Theme, Window as CoreWindow, WindowAttributes, WindowId
 use x11rb::connection::RequestConnection;
 use x11rb::errors::{ConnectError, ConnectionError, IdsExhausted, ReplyError};
 use x11rb::protocol::xinput::{self, ConnectionExt as _};
-use x11rb::protocol::{xkb, xproto};
+use x11rb::protocol::{present, xkb, xproto};
 use x11rb::x11_utils::X11Error as LogicalError;
 use x11rb::xcb_ffi::ReplyOrIdError;
 
 use crate::atoms::*;
-use crate::dnd::Dnd;
+use crate::clipboard::Selection;
+use crate::dnd::{Dnd, DragRequest};
 use crate::event_processor::{EventProcessor, MAX_MOD_REPLAY_LEN};
 use crate::ime::{self, Ime, ImeCreationError, ImeSender};
 use crate::util::{self, CustomCursor};
@@ -171,6 +176,8 @@ pub struct ActiveEventLoop {
     pub(crate) wm_delete_window: xproto::Atom,
     pub(crate) net_wm_ping: xproto::Atom,
     pub(crate) net_wm_sync_request: xproto::Atom,
+    /// Whether the server supports the gesture events of XInput 2.4.
+    pub(crate) xinput_gestures: bool,
     pub(crate) ime_sender: ImeSender,
     control_flow: Cell<ControlFlow>,
     exit: Cell<Option<i32>>,
@@ -179,8 +186,13 @@ pub struct ActiveEventLoop {
     pub(crate) windows: RefCell<HashMap<WindowId, Weak<UnownedWindow>>>,
     pub(crate) redraw_sender: WakeSender<WindowId>,
     pub(crate) activation_sender: WakeSender<ActivationItem>,
+    pub(crate) drag_sender: WakeSender<DragRequest>,
+    pub(crate) keyboard_grab_sender: WakeSender<(WindowId, bool)>,
     event_loop_proxy: CoreEventLoopProxy,
     device_events: Cell<DeviceEvents>,
+    pub(crate) clipboard: Selection,
+    pub(crate) primary_selection: Selection,
+    pub(crate) dnd_selection: Selection,
 }
 
 #[derive(Debug)]
@@ -190,6 +202,8 @@ pub struct EventLoop {
     event_processor: EventProcessor,
     redraw_receiver: PeekableReceiver<WindowId>,
     activation_receiver: PeekableReceiver<ActivationItem>,
+    drag_receiver: PeekableReceiver<DragRequest>,
+    keyboard_grab_receiver: PeekableReceiver<(WindowId, bool)>,
 
     /// The current state of the event loop.
     state: EventLoopState,
@@ -229,6 +243,14 @@ impl EventLoop {
         let dnd = Dnd::new(Arc::clone(&xconn))
             .expect("Failed to call XInternAtoms when initializing drag and drop");
 
+        let clipboard = Selection::new(Arc::clone(&xconn), atoms[ClipboardSelection])
+            .map_err(|err| os_error!(err))?;
+        let primary_selection =
+            Selection::new(Arc::clone(&xconn), xproto::AtomEnum::PRIMARY.into())
+                .map_err(|err| os_error!(err))?;
+        let dnd_selection = Selection::new(Arc::clone(&xconn), atoms[XdndSelection])
+            .map_err(|err| os_error!(err))?;
+
         let (ime_sender, ime_receiver) = mpsc::channel();
         let (ime_event_sender, ime_event_receiver) = mpsc::channel();
         // Input methods will open successfully without setting the locale, but it won't be
@@ -266,6 +288,8 @@ impl EventLoop {
 
         let randr_event_offset =
             xconn.select_xrandr_input(root).expect("Failed to query XRandR extension");
+        // Cache the monitor list, the monitor events are computed against it.
+        let _ = xconn.available_monitors();
 
         let xi2ext = xconn
             .xcb_connection()
@@ -277,14 +301,23 @@ impl EventLoop {
             .extension_information(xkb::X11_EXTENSION_NAME)
             .expect("Failed to query XKB extension")
             .expect("X server missing XKB extension");
+        let presentext = xconn
+            .xcb_connection()
+            .extension_information(present::X11_EXTENSION_NAME)
+            .expect("Failed to query Present extension");
+        if let Some(presentext) = presentext {
+            util::cookie::keep_wire_events(&xconn, presentext.major_opcode);
+        }
 
-        // Check for XInput2 support.
-        xconn
+        // Check for XInput2 support, the gesture events need XInput 2.4.
+        let xinput_version = xconn
             .xcb_connection()
-            .xinput_xi_query_version(2, 3)
+            .xinput_xi_query_version(2, 4)
             .expect("Failed to send XInput2 query version request")
             .reply()
             .expect("Error while checking for XInput2 query version reply");
+        let xinput_gestures =
+            (xinput_version.major_version, xinput_version.minor_version) >= (2, 4);
 
         xconn.update_cached_wm_info(root);
 
@@ -322,6 +355,12 @@ impl EventLoop {
         // Create a channel for sending activation tokens.
         let (activation_token_sender, activation_token_channel) = mpsc::channel();
 
+        // Create a channel for starting drags.
+        let (drag_sender, drag_channel) = mpsc::channel();
+
+        // Create a channel for reporting keyboard grab changes.
+        let (keyboard_grab_sender, keyboard_grab_channel) = mpsc::channel();
+
         // Create a channel for sending user events.
         let (user_waker, user_waker_source) =
             calloop::ping::make_ping().expect("Failed to create user event loop waker.");
@@ -351,6 +390,7 @@ impl EventLoop {
             wm_delete_window,
             net_wm_ping,
             net_wm_sync_request,
+            xinput_gestures,
             redraw_sender: WakeSender {
                 sender: redraw_sender, // not used again so no clone
                 waker: waker.clone(),
@@ -359,8 +399,19 @@ impl EventLoop {
                 sender: activation_token_sender, // not used again so no clone
                 waker: waker.clone(),
             },
+            drag_sender: WakeSender {
+                sender: drag_sender, // not used again so no clone
+                waker: waker.clone(),
+            },
+            keyboard_grab_sender: WakeSender {
+                sender: keyboard_grab_sender, // not used again so no clone
+                waker: waker.clone(),
+            },
             event_loop_proxy: event_loop_proxy.into(),
             device_events: Default::default(),
+            clipboard,
+            primary_selection,
+            dnd_selection,
         };
 
         // Set initial device event filter.
@@ -374,6 +425,7 @@ impl EventLoop {
             ime_receiver,
             ime_event_receiver,
             xi2ext,
+            presentext,
             xfiltered_modifiers: VecDeque::with_capacity(MAX_MOD_REPLAY_LEN),
             xmodmap,
             xkbext,
@@ -381,8 +433,11 @@ impl EventLoop {
             num_touch: 0,
             held_key_press: None,
             first_touch: None,
+            pinch_scale: None,
             active_window: None,
+            drag_source: None,
             modifiers: Default::default(),
+            keyboard_layouts: None,
             is_composing: false,
         };
 
@@ -417,6 +472,8 @@ impl EventLoop {
             event_processor,
             redraw_receiver: PeekableReceiver::from_recv(redraw_channel),
             activation_receiver: PeekableReceiver::from_recv(activation_token_channel),
+            drag_receiver: PeekableReceiver::from_recv(drag_channel),
+            keyboard_grab_receiver: PeekableReceiver::from_recv(keyboard_grab_channel),
             state: EventLoopState { x11_readiness: Readiness::EMPTY, proxy_wake_up: false },
         };
 
@@ -489,6 +546,8 @@ impl EventLoop {
         self.event_processor.poll()
             || self.state.proxy_wake_up
             || self.redraw_receiver.has_incoming()
+            || self.drag_receiver.has_incoming()
+            || self.keyboard_grab_receiver.has_incoming()
     }
 
     fn poll_events_with_timeout<A: ApplicationHandler>(
@@ -512,7 +571,11 @@ impl EventLoop {
                 },
             };
 
-            min_timeout(control_flow_timeout, timeout)
+            // Wake up in time to fail the transfers which are stuck.
+            let deadline = self.event_processor.deadline();
+            let deadline_timeout =
+                deadline.map(|deadline| deadline.saturating_duration_since(start));
+            min_timeout(min_timeout(control_flow_timeout, deadline_timeout), timeout)
         };
 
         self.state.x11_readiness = Readiness::EMPTY;
@@ -592,6 +655,23 @@ impl EventLoop {
             }
         }
 
+        // Handle the drag and drop requests.
+        while let Ok(request) = self.drag_receiver.try_recv() {
+            self.event_processor.drag_request(request, app);
+        }
+
+        // Complete the drop once the data it needs is read.
+        self.event_processor.finish_drop();
+
+        // Fail the transfers which timed out.
+        self.event_processor.expire(Instant::now(), app);
+
+        // Report the keyboard grabs which were taken or released.
+        while let Ok((window_id, grabbed)) = self.keyboard_grab_receiver.try_recv() {
+            let event = WindowEvent::KeyboardGrabbed(grabbed);
+            app.window_event(&self.event_processor.target, window_id, event);
+        }
+
         // Empty the user event buffer
         if mem::take(&mut self.state.proxy_wake_up) {
             app.proxy_wake_up(&self.event_processor.target);
@@ -611,6 +691,12 @@ impl EventLoop {
                     window_id,
                     WindowEvent::RedrawRequested,
                 );
+
+                // The frame of a configure may be awaited by the window manager.
+                self.event_processor
+                    .with_window(window_id.into_raw() as xproto::Window, |window| {
+                        window.finish_sync_request()
+                    });
             }
         }
 
@@ -663,6 +749,12 @@ impl ActiveEventLoop {
         &self.xconn
     }
 
+    /// Returns the selections events are dispatched to.
+    #[inline]
+    pub(crate) fn selections(&self) -> [&Selection; 3] {
+        [&self.clipboard, &self.primary_selection, &self.dnd_selection]
+    }
+
     /// Update the device event based on window focus.
     pub fn update_listen_device_events(&self, focus: bool) {
         let device_events = self.device_events.get() == DeviceEvents::Always
@@ -724,6 +816,26 @@ impl RootActiveEventLoop for ActiveEventLoop {
         )
     }
 
+    fn devices(&self) -> Box<dyn Iterator<Item = CoreDeviceInfo>> {
+        let Some(infos) = DeviceInfo::get(&self.xconn, ALL_DEVICES.into()) else {
+            return Box::new(std::iter::empty());
+        };
+
+        let atoms = self.xconn.atoms();
+        let devices: Vec<_> = infos
+            .iter()
+            .filter(|info| Device::physical_device(info))
+            .map(|info| {
+                let seat = infos
+                    .iter()
+                    .find(|master| master.deviceid == info.attachment)
+                    .map(|master| unsafe { CStr::from_ptr(master.name) }.to_string_lossy().into());
+                Device::info(info, seat, atoms)
+            })
+            .collect();
+        Box::new(devices.into_iter())
+    }
+
     fn primary_monitor(&self) -> Option<CoreMonitorHandle> {
         self.xconn.primary_monitor().ok().map(|monitor| CoreMonitorHandle(Arc::new(monitor)))
     }
@@ -752,6 +864,10 @@ impl RootActiveEventLoop for ActiveEventLoop {
         self.exit.get().is_some()
     }
 
+    fn clipboard(&self) -> Option<&dyn Clipboard> {
+        Some(&self.clipboard)
+    }
+
     fn owned_display_handle(&self) -> CoreOwnedDisplayHandle {
         CoreOwnedDisplayHandle::new(self.x_connection().clone())
     }
@@ -999,6 +1115,8 @@ pub(crate) fn mkdid(w: xinput::DeviceId) -> DeviceId {
 pub struct Device {
     _name: String,
     pub(crate) scroll_axes: Vec<(i32, ScrollAxis)>,
+    pub(crate) scroll_source: ScrollSource,
+    pub(crate) tablet_axes: TabletAxes,
     // For master devices, this is the paired device (pointer <-> keyboard).
     // For slave devices, this is the master.
     pub(crate) attachment: c_int,
@@ -1013,6 +1131,93 @@ pub(crate) enum DeviceType {
     Eraser,
 }
 
+impl DeviceType {
+    pub(crate) fn tablet_tool_kind(self) -> Option<TabletToolKind> {
+        match self {
+            DeviceType::Pen => Some(TabletToolKind::Pen),
+            DeviceType::Eraser => Some(TabletToolKind::Eraser),
+            DeviceType::Mouse | DeviceType::Touch => None,
+        }
+    }
+}
+
+/// The valuators of a tablet tool.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct TabletAxes {
+    pressure: Option<ValuatorAxis>,
+    tilt_x: Option<ValuatorAxis>,
+    tilt_y: Option<ValuatorAxis>,
+    rotation: Option<ValuatorAxis>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct ValuatorAxis {
+    number: i32,
+    min: f64,
+    max: f64,
+    value: f64,
+}
+
+impl ValuatorAxis {
+    fn new(info: &ffi::XIValuatorClassInfo) -> Self {
+        Self { number: info.number, min: info.min, max: info.max, value: info.value }
+    }
+
+    /// The value in the range of 0 to 1.
+    fn normalized(&self) -> f64 {
+        if self.max > self.min {
+            ((self.value - self.min) / (self.max - self.min)).clamp(0., 1.)
+        } else {
+            0.
+        }
+    }
+
+    /// The angle in degrees, with the bound of the range furthest from 0 as 90 degrees.
+    ///
+    /// The drivers don't report the tilt in degrees, the wacom driver uses a range of -64 to 63.
+    fn tilt(&self) -> i8 {
+        let bound = self.min.abs().max(self.max.abs());
+        if bound > 0. { (self.value / bound * 90.).round().clamp(-90., 90.) as i8 } else { 0 }
+    }
+}
+
+impl TabletAxes {
+    fn is_empty(&self) -> bool {
+        self.pressure.is_none() && self.tilt_x.is_none() && self.tilt_y.is_none()
+    }
+
+    /// Record the value of the valuator `number`.
+    pub(crate) fn update(&mut self, number: i32, value: f64) {
+        for axis in [&mut self.pressure, &mut self.tilt_x, &mut self.tilt_y, &mut self.rotation]
+            .into_iter()
+            .flatten()
+        {
+            if axis.number == number {
+                axis.value = value;
+            }
+        }
+    }
+
+    pub(crate) fn data(&self) -> TabletToolData {
+        let tilt = |axis: Option<ValuatorAxis>| axis.map_or(0, |axis| axis.tilt());
+
+        TabletToolData {
+            force: self.pressure.map(|axis| Force::Normalized(axis.normalized())),
+            tangential_force: None,
+            twist: self.rotation.map(|axis| (axis.normalized() * 360.).round() as u16 % 360),
+            tilt: (self.tilt_x.is_some() || self.tilt_y.is_some())
+                .then(|| TabletToolTilt { x: tilt(self.tilt_x), y: tilt(self.tilt_y) }),
+            angle: None,
+        }
+    }
+}
+
+/// Touchpads driven by libinput or synaptics are plain pointers, only the name tells them apart
+/// from mice.
+fn is_touchpad(name: &str) -> bool {
+    name.to_lowercase().contains("touchpad")
+}
+
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct ScrollAxis {
     pub(crate) increment: f64,
@@ -1030,6 +1235,7 @@ impl Device {
     pub(crate) fn new(info: &ffi::XIDeviceInfo, atoms: &Atoms) -> Self {
         let name = unsafe { CStr::from_ptr(info.name).to_string_lossy() };
         let mut scroll_axes = Vec::new();
+        let mut tablet_axes = TabletAxes::default();
         let mut r#type = None;
 
         if Device::physical_device(info) {
@@ -1049,29 +1255,46 @@ impl Device {
                     }));
                 } else if ty == ffi::XITouchClass {
                     r#type = Some(DeviceType::Touch);
-                } else if r#type.is_none() && ty == ffi::XIValuatorClass {
+                } else if ty == ffi::XIValuatorClass {
                     let info = unsafe { &*(class_ptr as *const ffi::XIValuatorClassInfo) };
                     let atom = info.label as xproto::Atom;
-
-                    if atom == atoms[ABS_X]
-                        || atom == atoms[ABS_Y]
-                        || atom == atoms[ABS_PRESSURE]
-                        || atom == atoms[ABS_TILT_X]
-                        || atom == atoms[ABS_TILT_Y]
+                    let axis = Some(ValuatorAxis::new(info));
+
+                    if atom == atoms[ABS_PRESSURE] {
+                        tablet_axes.pressure = axis;
+                    } else if atom == atoms[ABS_TILT_X] {
+                        tablet_axes.tilt_x = axis;
+                    } else if atom == atoms[ABS_TILT_Y] {
+                        tablet_axes.tilt_y = axis;
+                    } else if atom == atoms[ABS_RZ]
+                        || (atom == atoms[ABS_WHEEL] && tablet_axes.rotation.is_none())
                     {
-                        if name.contains("eraser") {
-                            r#type = Some(DeviceType::Eraser);
-                        } else {
-                            r#type = Some(DeviceType::Pen);
-                        }
+                        // The libinput driver reports the rotation on `Abs Rotary Z`, and the
+                        // `Abs Wheel` of the wacom driver is only used when there is none.
+                        tablet_axes.rotation = axis;
                     }
                 }
             }
+
+            // Absolute pointers without pressure or tilt, like the ones of virtual machines, are
+            // plain mice.
+            if r#type.is_none() && !tablet_axes.is_empty() {
+                if name.to_lowercase().contains("eraser") {
+                    r#type = Some(DeviceType::Eraser);
+                } else {
+                    r#type = Some(DeviceType::Pen);
+                }
+            }
         }
 
+        let scroll_source =
+            if is_touchpad(&name) { ScrollSource::Finger } else { ScrollSource::Wheel };
+
         let mut device = Device {
             _name: name.into_owned(),
             scroll_axes,
+            scroll_source,
+            tablet_axes,
             attachment: info.attachment,
             r#type: r#type.unwrap_or(DeviceType::Mouse),
         };
@@ -1079,6 +1302,38 @@ impl Device {
         device
     }
 
+    /// Describe the physical device `info`, attached to the master device named `seat`.
+    pub(crate) fn info(
+        info: &ffi::XIDeviceInfo,
+        seat: Option<String>,
+        atoms: &Atoms,
+    ) -> CoreDeviceInfo {
+        let name = unsafe { CStr::from_ptr(info.name) }.to_string_lossy().into_owned();
+        let touch_mode = Device::classes(info).iter().find_map(|&class_ptr| {
+            let info = unsafe { &*(class_ptr as *const ffi::XITouchClassInfo) };
+            (info._type == ffi::XITouchClass).then_some(info.mode)
+        });
+
+        let kind = if info._use == ffi::XISlaveKeyboard {
+            DeviceKind::Keyboard
+        } else if let Some(mode) = touch_mode {
+            if mode == ffi::XIDirectTouch { DeviceKind::Touchscreen } else { DeviceKind::Touchpad }
+        } else {
+            match Device::new(info, atoms).r#type {
+                DeviceType::Pen | DeviceType::Eraser => DeviceKind::Tablet,
+                _ if is_touchpad(&name) => DeviceKind::Touchpad,
+                _ => DeviceKind::Mouse,
+            }
+        };
+
+        CoreDeviceInfo {
+            id: mkdid(info.deviceid as xinput::DeviceId),
+            name: Some(name),
+            kind,
+            seat,
+        }
+    }
+
     pub(crate) fn reset_scroll_position(&mut self, info: &ffi::XIDeviceInfo) {
         if Device::physical_device(info) {
             for &class_ptr in Device::classes(info) {
@@ -1096,7 +1351,7 @@ impl Device {
     }
 
     #[inline]
-    fn physical_device(info: &ffi::XIDeviceInfo) -> bool {
+    pub(crate) fn physical_device(info: &ffi::XIDeviceInfo) -> bool {
         info._use == ffi::XISlaveKeyboard
             || info._use == ffi::XISlavePointer
             || info._use == ffi::XIFloatingSlave
@@ -1125,3 +1380,57 @@ pub(crate) fn xinput_fp1616_to_float(fp: xinput::Fp1616) -> f64 {
 fn min_timeout(a: Option<Duration>, b: Option<Duration>) -> Option<Duration> {
     a.map_or(b, |a_timeout| b.map_or(Some(a_timeout), |b_timeout| Some(a_timeout.min(b_timeout))))
 }
+
+#[cfg(test)]
+mod tests {
+    use winit_core::event::{Force, TabletToolTilt};
+
+    use super::*;
+
+    fn axis(number: i32, min: f64, max: f64, value: f64) -> Option<ValuatorAxis> {
+        Some(ValuatorAxis { number, min, max, value })
+    }
+
+    #[test]
+    fn valuator_normalized() {
+        assert_eq!(axis(0, 0., 2048., 512.).unwrap().normalized(), 0.25);
+        assert_eq!(axis(0, 0., 2048., 4096.).unwrap().normalized(), 1.);
+        assert_eq!(axis(0, 0., 2048., -1.).unwrap().normalized(), 0.);
+        assert_eq!(axis(0, 5., 5., 5.).unwrap().normalized(), 0.);
+    }
+
+    #[test]
+    fn tilt_scaled_from_valuator_range() {
+        let tilt = |value| axis(0, -64., 63., value).unwrap().tilt();
+        assert_eq!(tilt(0.), 0);
+        assert_eq!(tilt(-64.), -90);
+        assert_eq!(tilt(32.), 45);
+        assert_eq!(tilt(63.), 89);
+        assert_eq!(axis(0, 0., 0., 10.).unwrap().tilt(), 0);
+    }
+
+    #[test]
+    fn tablet_axes_data() {
+        let mut axes = TabletAxes {
+            pressure: axis(2, 0., 65535., 0.),
+            tilt_x: axis(3, -64., 63., 0.),
+            tilt_y: None,
+            rotation: axis(5, 0., 1000., 0.),
+        };
+        axes.update(2, 65535.);
+        axes.update(3, -32.);
+        axes.update(5, 250.);
+        // Not an axis of the tool.
+        axes.update(4, 10.);
+
+        let data = axes.data();
+        assert_eq!(data.force, Some(Force::Normalized(1.)));
+        assert_eq!(data.tilt, Some(TabletToolTilt { x: -45, y: 0 }));
+        assert_eq!(data.twist, Some(90));
+        assert_eq!(data.angle, None);
+
+        let axes = TabletAxes { pressure: axis(2, 0., 1., 0.5), ..Default::default() };
+        assert_eq!(axes.data().tilt, None);
+        assert_eq!(axes.data().twist, None);
+    }
+}
@@ -2,44 +2,53 @@ use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, VecDeque};
 use std::os::raw::{c_char, c_int, c_long, c_ulong};
 use std::slice;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
 
 use dpi::{PhysicalPosition, PhysicalSize};
+use tracing::warn;
 use winit_common::xkb::{self, Context, XkbState};
 use winit_core::application::ApplicationHandler;
+use winit_core::clipboard::{Clipboard, ClipboardData, ClipboardError, ClipboardEvent};
+use winit_core::drag::{DragAction, DragActions, DragResult};
 use winit_core::event::{
-    ButtonSource, DeviceEvent, DeviceId, ElementState, FingerId, Ime, MouseButton,
-    MouseScrollDelta, PointerKind, PointerSource, RawKeyEvent, SurfaceSizeWriter, TouchPhase,
-    WindowEvent,
+    ButtonSource, DeviceEvent, DeviceId, ElementState, FingerId, FramePresentation, Ime,
+    MouseButton, MouseScrollDelta, PointerKind, PointerSource, RawKeyEvent, ScrollSource,
+    SurfaceSizeWriter, TabletToolButton, TabletToolData, TouchPhase, WindowEvent,
 };
-use winit_core::keyboard::ModifiersState;
+use winit_core::event_loop::AsyncRequestSerial;
+use winit_core::keyboard::{KeyCode, KeyboardLayouts, ModifiersState};
+use winit_core::monitor::MonitorHandleProvider;
 use winit_core::window::WindowId;
 use x11_dl::xinput2::{
     self, XIDeviceEvent, XIEnterEvent, XIFocusInEvent, XIFocusOutEvent, XIHierarchyEvent,
     XILeaveEvent, XIModifierState, XIRawEvent,
 };
 use x11_dl::xlib::{
-    self, Display as XDisplay, Window as XWindow, XAnyEvent, XClientMessageEvent, XConfigureEvent,
-    XDestroyWindowEvent, XEvent, XExposeEvent, XKeyEvent, XMapEvent, XPropertyEvent,
-    XReparentEvent, XSelectionEvent, XVisibilityEvent, XkbAnyEvent, XkbStateRec,
+    self, Display as XDisplay, Window as XWindow, XAnyEvent, XButtonEvent, XClientMessageEvent,
+    XConfigureEvent, XDestroyWindowEvent, XEvent, XExposeEvent, XKeyEvent, XMapEvent, XMotionEvent,
+    XPropertyEvent, XReparentEvent, XSelectionEvent, XVisibilityEvent, XkbAnyEvent, XkbStateRec,
 };
-use x11rb::protocol::sync::{ConnectionExt, Int64};
-use x11rb::protocol::xinput;
+use x11rb::protocol::sync::Int64;
 use x11rb::protocol::xkb::ID as XkbId;
 use x11rb::protocol::xproto::{self, ConnectionExt as _, ModMask};
-use x11rb::x11_utils::{ExtensionInformation, Serialize};
+use x11rb::protocol::{present, xinput};
+use x11rb::x11_utils::{ExtensionInformation, Serialize, TryParse};
 use xkbcommon_dl::xkb_mod_mask_t;
 
 use crate::atoms::*;
-use crate::dnd::{Dnd, DndState};
+use crate::clipboard::target_mime_types;
+use crate::dnd::{Dnd, DndDataParseError, DndState, DragRequest, DragSource, FINISH_TIMEOUT};
 use crate::event_loop::{
     ALL_DEVICES, ActiveEventLoop, CookieResultExt, Device, DeviceInfo, DeviceType,
     ScrollOrientation, mkdid, mkwid,
 };
 use crate::ime::{ImeEvent, ImeEventReceiver, ImeReceiver, ImeRequest};
-use crate::util;
 use crate::util::cookie::GenericEventCookie;
 use crate::window::UnownedWindow;
+use crate::xdisplay::GrabOwner;
+use crate::{ffi, monitor, util};
 
 /// The maximum amount of X modifiers to replay.
 pub const MAX_MOD_REPLAY_LEN: usize = 32;
@@ -56,6 +65,7 @@ pub struct EventProcessor {
     pub devices: RefCell<HashMap<DeviceId, Device>>,
     pub xi2ext: ExtensionInformation,
     pub xkbext: ExtensionInformation,
+    pub presentext: Option<ExtensionInformation>,
     pub target: ActiveEventLoop,
     pub xkb_context: Context,
     // Number of touch events currently in progress
@@ -66,10 +76,16 @@ pub struct EventProcessor {
     // Used to detect key repeats.
     pub held_key_press: Option<u32>,
     pub first_touch: Option<u32>,
+    // Scale of the two-finger pinch gesture in progress
+    pub pinch_scale: Option<f64>,
     // Currently focused window belonging to this process
     pub active_window: Option<xproto::Window>,
+    // Drag started from one of our windows, while it's in progress
+    pub drag_source: Option<DragSource>,
     /// Latest modifiers we've sent for the user to trigger change in event.
     pub modifiers: Cell<ModifiersState>,
+    /// Latest keyboard layouts we've sent, to only send changes.
+    pub keyboard_layouts: Option<KeyboardLayouts>,
     // Track modifiers based on keycodes. NOTE: that serials generally don't work for tracking
     // since they are not unique and could be duplicated in case of sequence of key events is
     // delivered at near the same time.
@@ -182,7 +198,27 @@ impl EventProcessor {
         match event_type {
             xlib::ClientMessage => self.client_message(xev.as_ref(), app),
             xlib::SelectionNotify => self.selection_notify(xev.as_ref(), app),
+            xlib::SelectionRequest => {
+                for selection in self.target.selections() {
+                    selection.selection_request(xev.as_ref());
+                }
+            },
+            xlib::SelectionClear => {
+                let xev = xev.as_ref();
+                let event = self
+                    .target
+                    .selections()
+                    .into_iter()
+                    .find_map(|selection| selection.selection_clear(xev));
+                if let Some(event) = event {
+                    app.clipboard_event(&self.target, event);
+                }
+            },
             xlib::ConfigureNotify => self.configure_notify(xev.as_ref(), app),
+            // Core pointer events are only selected by the pointer grabs of drags and popups.
+            xlib::MotionNotify => self.drag_motion(xev.as_ref()),
+            xlib::ButtonPress => self.popup_button_press(xev.as_ref(), app),
+            xlib::ButtonRelease => self.drag_button_release(xev.as_ref(), app),
             xlib::ReparentNotify => self.reparent_notify(xev.as_ref()),
             xlib::MapNotify => self.map_notify(xev.as_ref(), app),
             xlib::DestroyNotify => self.destroy_notify(xev.as_ref(), app),
@@ -190,6 +226,7 @@ impl EventProcessor {
             xlib::VisibilityNotify => self.visibility_notify(xev.as_ref(), app),
             xlib::Expose => self.expose(xev.as_ref()),
             // Note that in compose/pre-edit sequences, we'll always receive KeyRelease events.
+            xlib::KeyPress if self.drag_escape(xev.as_ref(), app) => {},
             ty @ xlib::KeyPress | ty @ xlib::KeyRelease => {
                 let state = if ty == xlib::KeyPress {
                     ElementState::Pressed
@@ -203,6 +240,13 @@ impl EventProcessor {
                 let xev: GenericEventCookie =
                     match GenericEventCookie::from_event(self.target.xconn.clone(), *xev) {
                         Some(xev) if xev.extension() == self.xi2ext.major_opcode => xev,
+                        Some(xev)
+                            if Some(xev.extension())
+                                == self.presentext.map(|ext| ext.major_opcode) =>
+                        {
+                            self.present_event(&xev, app);
+                            return;
+                        },
                         _ => return,
                     };
 
@@ -271,9 +315,23 @@ impl EventProcessor {
                         self.xinput2_raw_key_input(xev, state, app);
                     },
 
+                    ffi::XI_GesturePinchBegin
+                    | ffi::XI_GesturePinchUpdate
+                    | ffi::XI_GesturePinchEnd => {
+                        let xev: &ffi::XIGesturePinchEvent = unsafe { xev.as_event() };
+                        self.update_mods_from_xinput2_event(&xev.mods, &xev.group, false, app);
+                        self.xinput2_pinch_gesture(xev, app);
+                    },
+                    ffi::XI_GestureSwipeBegin
+                    | ffi::XI_GestureSwipeUpdate
+                    | ffi::XI_GestureSwipeEnd => {
+                        let xev: &ffi::XIGestureSwipeEvent = unsafe { xev.as_event() };
+                        self.update_mods_from_xinput2_event(&xev.mods, &xev.group, false, app);
+                        self.xinput2_swipe_gesture(xev, app);
+                    },
                     xinput2::XI_HierarchyChanged => {
                         let xev: &XIHierarchyEvent = unsafe { xev.as_event() };
-                        self.xinput2_hierarchy_changed(xev);
+                        self.xinput2_hierarchy_changed(xev, app);
                     },
                     _ => {},
                 }
@@ -331,6 +389,36 @@ impl EventProcessor {
         }
     }
 
+    fn device_type(&self, device_id: c_int) -> Option<DeviceType> {
+        self.devices.borrow().get(&mkdid(device_id as xinput::DeviceId)).map(|device| device.r#type)
+    }
+
+    /// Record the valuators of a tablet tool event.
+    ///
+    /// Returns the data of the tool, and whether it changed.
+    fn tablet_tool_data(&self, event: &XIDeviceEvent) -> Option<(TabletToolData, bool)> {
+        let mut devices = self.devices.borrow_mut();
+        let device = devices.get_mut(&mkdid(event.sourceid as xinput::DeviceId))?;
+        let old_data = device.tablet_axes.data();
+
+        let mask = unsafe {
+            slice::from_raw_parts(event.valuators.mask, event.valuators.mask_len as usize)
+        };
+        let mut value = event.valuators.values;
+        for i in 0..event.valuators.mask_len * 8 {
+            if !xinput2::XIMaskIsSet(mask, i) {
+                continue;
+            }
+
+            device.tablet_axes.update(i, unsafe { *value });
+            value = unsafe { value.offset(1) };
+        }
+
+        let data = device.tablet_axes.data();
+        let changed = data != old_data;
+        Some((data, changed))
+    }
+
     pub fn with_window<F, Ret>(&self, window_id: xproto::Window, callback: F) -> Option<Ret>
     where
         F: Fn(&Arc<UnownedWindow>) -> Ret,
@@ -396,13 +484,6 @@ impl EventProcessor {
         }
 
         if xev.data.get_long(0) as xproto::Atom == self.target.net_wm_sync_request {
-            let sync_counter_id = match self
-                .with_window(xev.window as xproto::Window, |window| window.sync_counter_id())
-            {
-                Some(Some(sync_counter_id)) => sync_counter_id.get(),
-                _ => return,
-            };
-
             #[cfg(target_pointer_width = "32")]
             let (lo, hi) =
                 (bytemuck::cast::<c_long, u32>(xev.data.get_long(2)), xev.data.get_long(3));
@@ -413,16 +494,27 @@ impl EventProcessor {
                 bytemuck::cast::<u32, i32>((xev.data.get_long(3) & 0xffffffff) as u32),
             );
 
-            self.target
-                .xconn
-                .xcb_connection()
-                .sync_set_counter(sync_counter_id, Int64 { lo, hi })
-                .expect_then_ignore_error("Failed to set XSync counter.");
+            // The counter is set once the frame for the following configure is drawn.
+            self.with_window(xev.window as xproto::Window, |window| {
+                window.sync_requested(Int64 { lo, hi })
+            });
+
+            return;
+        }
+
+        if xev.message_type == atoms[XdndStatus] as c_ulong {
+            self.drag_status(xev);
+            return;
+        }
 
+        if xev.message_type == atoms[XdndFinished] as c_ulong {
+            self.drag_finished(xev, app);
             return;
         }
 
         if xev.message_type == atoms[XdndEnter] as c_ulong {
+            self.dnd.reset();
+
             let source_window = xev.data.get_long(0) as xproto::Window;
             let flags = xev.data.get_long(1);
             let version = flags >> 24;
@@ -438,17 +530,20 @@ impl EventProcessor {
             } else if let Ok(more_types) = unsafe { self.dnd.get_type_list(source_window) } {
                 self.dnd.type_list = Some(more_types);
             }
+
+            let type_list = self.dnd.type_list.iter().flatten().copied();
+            self.dnd.mime_types =
+                target_mime_types(&self.target.xconn, type_list).unwrap_or_else(|err| {
+                    warn!("Failed to get the dragged MIME types: {err}");
+                    Vec::new()
+                });
             return;
         }
 
         if xev.message_type == atoms[XdndPosition] as c_ulong {
-            // This event occurs every time the mouse moves while a file's being dragged
-            // over our window. We emit HoveredFile in response; while the macOS backend
-            // does that upon a drag entering, XDND doesn't have access to the actual drop
-            // data until this event. For parity with other platforms, we only emit
-            // `HoveredFile` the first time, though if winit's API is later extended to
-            // supply position updates with `HoveredFile` or another event, implementing
-            // that here would be trivial.
+            // This event occurs every time the mouse moves while something's being dragged over
+            // our window. When files are dragged, XDND doesn't have access to the actual drop data
+            // until this event, so `DragEntered` is only emitted once their paths are read.
 
             let source_window = xev.data.get_long(0) as xproto::Window;
 
@@ -469,26 +564,13 @@ impl EventProcessor {
             // By our own state flow, `version` should never be `None` at this point.
             let version = self.dnd.version.unwrap_or(5);
 
-            // Action is specified in versions 2 and up, though we don't need it anyway.
-            // let action = xev.data.get_long(4);
-
-            let accepted = if let Some(ref type_list) = self.dnd.type_list {
-                type_list.contains(&atoms[TextUriList])
-            } else {
-                false
-            };
-
-            if !accepted {
-                unsafe {
-                    self.dnd
-                        .send_status(window, source_window, DndState::Rejected)
-                        .expect("Failed to send `XdndStatus` message.");
-                }
-                self.dnd.reset();
-                return;
+            // Action is specified in versions 2 and up.
+            if version >= 2 {
+                self.dnd.source_action = self.dnd.atom_action(xev.data.get_long(4) as xproto::Atom);
             }
 
             self.dnd.source_window = Some(source_window);
+            self.dnd.window = Some(window);
             let time = if version == 0 {
                 // In version 0, time isn't specified
                 x11rb::CURRENT_TIME
@@ -499,43 +581,63 @@ impl EventProcessor {
             // Log this timestamp.
             self.target.xconn.set_timestamp(time);
 
-            // This results in the `SelectionNotify` event below
-            unsafe {
-                self.dnd.convert_selection(window, time);
+            if self.dnd.dragging {
+                let event = WindowEvent::DragMoved { position: self.dnd.position };
+                app.window_event(&self.target, window_id, event);
+            } else if !self.dnd.paths_requested {
+                let has_paths = self
+                    .dnd
+                    .type_list
+                    .as_ref()
+                    .is_some_and(|type_list| type_list.contains(&atoms[TextUriList]));
+
+                if has_paths {
+                    // Files are accepted unless the application decides otherwise.
+                    self.dnd.accepted = Some(self.dnd.source_action.unwrap_or(DragAction::Copy));
+                    self.dnd.paths_requested = true;
+
+                    // This results in the `SelectionNotify` event below
+                    unsafe {
+                        self.dnd.convert_selection(window, time);
+                    }
+                } else {
+                    self.dnd.dragging = true;
+                    let event = WindowEvent::DragEntered {
+                        paths: Vec::new(),
+                        mime_types: self.dnd.mime_types.clone(),
+                        actions: self.dnd.source_actions(),
+                        position: self.dnd.position,
+                    };
+                    app.window_event(&self.target, window_id, event);
+                }
             }
 
-            unsafe {
-                self.dnd
-                    .send_status(window, source_window, DndState::Accepted)
-                    .expect("Failed to send `XdndStatus` message.");
-            }
+            self.send_drop_status();
             return;
         }
 
         if xev.message_type == atoms[XdndDrop] as c_ulong {
-            let (source_window, state) = if let Some(source_window) = self.dnd.source_window {
-                if let Some(Ok(ref path_list)) = self.dnd.result {
-                    let event = WindowEvent::DragDropped {
-                        paths: path_list.iter().map(Into::into).collect(),
-                        position: self.dnd.position,
-                    };
-                    app.window_event(&self.target, window_id, event);
-                }
-                (source_window, DndState::Accepted)
+            if self.dnd.accepted.is_some() && self.dnd.window == Some(window) {
+                let paths = match self.dnd.result {
+                    Some(Ok(ref path_list)) => path_list.iter().map(Into::into).collect(),
+                    _ => Vec::new(),
+                };
+                let event = WindowEvent::DragDropped { paths, position: self.dnd.position };
+                app.window_event(&self.target, window_id, event);
+
+                // `XdndFinished` is sent once the data requested in response is read.
+                self.dnd.dropped = true;
             } else {
-                // `source_window` won't be part of our DND state if we already rejected the drop in
-                // our `XdndPosition` handler.
+                // The drop was rejected with our last `XdndStatus`.
                 let source_window = xev.data.get_long(0) as xproto::Window;
-                (source_window, DndState::Rejected)
-            };
+                unsafe {
+                    self.dnd
+                        .send_finished(window, source_window, DndState::Rejected)
+                        .expect("Failed to send `XdndFinished` message.");
+                }
 
-            unsafe {
-                self.dnd
-                    .send_finished(window, source_window, state)
-                    .expect("Failed to send `XdndFinished` message.");
+                self.dnd.reset();
             }
-
-            self.dnd.reset();
             return;
         }
 
@@ -557,31 +659,426 @@ impl EventProcessor {
         // Set the timestamp.
         self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
 
-        if xev.property != atoms[XdndSelection] as c_ulong {
+        let event = self
+            .target
+            .selections()
+            .into_iter()
+            .find_map(|selection| selection.selection_notify(xev));
+        if let Some(event) = event {
+            self.selection_event(event, app);
             return;
         }
 
-        // This is where we receive data from drag and drop
-        self.dnd.result = None;
-        if let Ok(mut data) = unsafe { self.dnd.read_data(window) } {
-            let parse_result = self.dnd.parse_data(&mut data);
+        if xev.selection != atoms[XdndSelection] as c_ulong {
+            return;
+        }
 
-            if let Ok(ref path_list) = parse_result {
-                let event = if self.dnd.dragging {
-                    WindowEvent::DragMoved { position: self.dnd.position }
-                } else {
-                    let paths = path_list.iter().map(Into::into).collect();
-                    self.dnd.dragging = true;
-                    WindowEvent::DragEntered { paths, position: self.dnd.position }
-                };
+        // This is where we receive the dragged paths, reading fails when the conversion failed
+        let parse_result = match unsafe { self.dnd.read_data(window) } {
+            Ok(mut data) => self.dnd.parse_data(&mut data),
+            Err(_) => Err(DndDataParseError::EmptyData),
+        };
 
+        if !self.dnd.dragging && self.dnd.window == Some(window) {
+            let paths = match parse_result {
+                Ok(ref path_list) => path_list.iter().map(Into::into).collect(),
+                Err(_) => Vec::new(),
+            };
+            self.dnd.dragging = true;
+            let event = WindowEvent::DragEntered {
+                paths,
+                mime_types: self.dnd.mime_types.clone(),
+                actions: self.dnd.source_actions(),
+                position: self.dnd.position,
+            };
+            app.window_event(&self.target, window_id, event);
+        }
+
+        self.dnd.result = Some(parse_result);
+    }
+
+    /// Deliver an event of one of our selections, the dragged data goes to the window that
+    /// requested it.
+    fn selection_event(&mut self, event: ClipboardEvent, app: &mut dyn ApplicationHandler) {
+        let ClipboardEvent::Data { serial, result } = event else {
+            app.clipboard_event(&self.target, event);
+            return;
+        };
+
+        match self.dnd.data_requests.remove(serial) {
+            Some(window) => {
+                let event = WindowEvent::DragData { serial, result };
+                app.window_event(&self.target, mkwid(window), event);
+            },
+            None => app.clipboard_event(&self.target, ClipboardEvent::Data { serial, result }),
+        }
+    }
+
+    /// The earliest time at which a pending transfer or sync request times out.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        let drag = self.drag_source.as_ref().and_then(|source| source.deadline);
+        let sync = self
+            .target
+            .windows
+            .borrow()
+            .values()
+            .filter_map(|window| window.upgrade()?.sync_deadline())
+            .min();
+        self.target
+            .selections()
+            .into_iter()
+            .filter_map(|selection| selection.deadline())
+            .chain(drag)
+            .chain(sync)
+            .min()
+    }
+
+    /// Fail the transfers and answer the sync requests which timed out.
+    pub(crate) fn expire(&mut self, now: Instant, app: &mut dyn ApplicationHandler) {
+        let windows: Vec<_> =
+            self.target.windows.borrow().values().filter_map(|window| window.upgrade()).collect();
+        for window in windows {
+            window.expire_sync_request(now);
+        }
+
+        let events: Vec<_> =
+            self.target.selections().into_iter().filter_map(|s| s.expire(now)).collect();
+        for event in events {
+            self.selection_event(event, app);
+        }
+
+        if self.drag_source.as_ref().and_then(|source| source.deadline).is_some_and(|d| d <= now) {
+            warn!("The drop target didn't finish the drop in time");
+            let source = self.drag_source.take().unwrap();
+            self.finish_drag(source, DragResult::Cancelled, app);
+        }
+    }
+
+    /// Tell the drag source whether we accept the drop.
+    fn send_drop_status(&self) {
+        let (Some(window), Some(source_window)) = (self.dnd.window, self.dnd.source_window) else {
+            return;
+        };
+
+        let state = self.dnd.accepted.map_or(DndState::Rejected, DndState::Accepted);
+        unsafe {
+            self.dnd
+                .send_status(window, source_window, state)
+                .expect("Failed to send `XdndStatus` message.");
+        }
+    }
+
+    /// Complete the drop once the data requested while handling `DragDropped` was read.
+    pub(crate) fn finish_drop(&mut self) {
+        if !self.dnd.dropped || !self.dnd.data_requests.is_empty() {
+            return;
+        }
+
+        if let (Some(window), Some(source_window)) = (self.dnd.window, self.dnd.source_window) {
+            let state = self.dnd.accepted.map_or(DndState::Rejected, DndState::Accepted);
+            unsafe {
+                self.dnd
+                    .send_finished(window, source_window, state)
+                    .expect("Failed to send `XdndFinished` message.");
+            }
+        }
+
+        self.dnd.reset();
+    }
+
+    pub(crate) fn drag_request(&mut self, request: DragRequest, app: &mut dyn ApplicationHandler) {
+        match request {
+            DragRequest::Start { window_id, serial, contents, actions } => {
+                self.start_drag(window_id, serial, contents, actions, app)
+            },
+            DragRequest::Accept { window_id, accepted } => {
+                let window = window_id.into_raw() as xproto::Window;
+                if self.dnd.window != Some(window) || self.dnd.dropped {
+                    return;
+                }
+
+                self.dnd.accepted = accepted.and_then(|(mime_type, action)| {
+                    if self.dnd.offered_target(&mime_type).is_none() {
+                        warn!("Rejecting drop as `{mime_type}`, which isn't offered");
+                        return None;
+                    }
+                    Some(action)
+                });
+                self.send_drop_status();
+            },
+            DragRequest::Data { window_id, serial, mime_type } => {
+                let window = window_id.into_raw() as xproto::Window;
+                let target =
+                    self.dnd.offered_target(&mime_type).filter(|_| self.dnd.window == Some(window));
+                match target {
+                    Some(target) => {
+                        self.dnd.data_requests.push(serial, window);
+                        self.target.dnd_selection.request_target(serial, target, mime_type);
+                    },
+                    None => {
+                        let result = Err(if self.dnd.window == Some(window) {
+                            ClipboardError::MimeTypeUnavailable
+                        } else {
+                            ClipboardError::Empty
+                        });
+                        app.window_event(&self.target, window_id, WindowEvent::DragData {
+                            serial,
+                            result,
+                        });
+                    },
+                }
+            },
+        }
+    }
+
+    fn start_drag(
+        &mut self,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        contents: Vec<ClipboardData>,
+        actions: DragActions,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        // Starting a drag cancels the previous one.
+        if let Some(source) = self.drag_source.take() {
+            self.finish_drag(source, DragResult::Cancelled, app);
+        }
+
+        let window = window_id.into_raw() as xproto::Window;
+        match self.dnd.start_source(window, &self.target.dnd_selection, contents) {
+            Ok(Some(types)) => {
+                self.drag_source = Some(DragSource::new(window, serial, actions, types));
+            },
+            result => {
+                if let Err(err) = result {
+                    warn!("Failed to start drag: {err}");
+                }
+                let event =
+                    WindowEvent::DragSourceFinished { serial, result: DragResult::Cancelled };
                 app.window_event(&self.target, window_id, event);
+            },
+        }
+    }
+
+    fn drag_motion(&mut self, xev: &XMotionEvent) {
+        let Some(source) = self.drag_source.as_mut() else { return };
+        if source.dropped {
+            return;
+        }
+
+        self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
+        let position = (xev.x_root as i16, xev.y_root as i16, xev.time as xproto::Timestamp);
+
+        let target = self.dnd.find_target(self.target.root, position.0, position.1);
+        let target = target.unwrap_or_else(|err| {
+            warn!("Failed to find drop target: {err}");
+            None
+        });
+        if target.map(|(window, _)| window) != source.target.map(|(window, _)| window) {
+            if let Some((window, _)) = source.target.take() {
+                self.dnd.send_leave(source, window).expect("Failed to send `XdndLeave` message.");
+            }
+            source.accepted = None;
+            source.awaiting_status = false;
+            source.pending_position = None;
+            if let Some((window, version)) = target {
+                self.dnd
+                    .send_enter(source, window, version)
+                    .expect("Failed to send `XdndEnter` message.");
+                source.target = target;
             }
+        }
 
-            self.dnd.result = Some(parse_result);
+        let Some((window, _)) = source.target else { return };
+        if source.awaiting_status {
+            // Positions are sent one at a time, only the latest one matters.
+            source.pending_position = Some(position);
+        } else {
+            self.dnd
+                .send_position(source, window, position)
+                .expect("Failed to send `XdndPosition` message.");
+            source.awaiting_status = true;
         }
     }
 
+    /// Cancel the drag when Escape is pressed, returning whether it was.
+    fn drag_escape(&mut self, xev: &XKeyEvent, app: &mut dyn ApplicationHandler) -> bool {
+        if self.drag_source.as_ref().is_none_or(|source| source.dropped)
+            || xkb::raw_keycode_to_physicalkey(xev.keycode) != KeyCode::Escape
+        {
+            return false;
+        }
+
+        self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
+        let source = self.drag_source.take().unwrap();
+        self.finish_drag(source, DragResult::Cancelled, app);
+        true
+    }
+
+    fn drag_button_release(&mut self, xev: &XButtonEvent, app: &mut dyn ApplicationHandler) {
+        let Some(source) = self.drag_source.as_mut() else { return };
+        if source.dropped {
+            return;
+        }
+
+        self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
+
+        match source.target {
+            Some((window, _)) if source.accepted.is_some() => {
+                self.dnd
+                    .send_drop(source, window, xev.time as xproto::Timestamp)
+                    .expect("Failed to send `XdndDrop` message.");
+                source.dropped = true;
+                source.deadline = Some(Instant::now() + FINISH_TIMEOUT);
+                let window = source.window;
+                self.ungrab_drag_pointer(window);
+            },
+            _ => {
+                let source = self.drag_source.take().unwrap();
+                self.finish_drag(source, DragResult::Cancelled, app);
+            },
+        }
+    }
+
+    fn present_event(&self, xev: &GenericEventCookie, app: &mut dyn ApplicationHandler) {
+        if xev.evtype() != c_int::from(present::COMPLETE_NOTIFY_EVENT) {
+            return;
+        }
+
+        // SAFETY: Present events are registered with `keep_wire_events`.
+        let Ok((event, _)) = present::CompleteNotifyEvent::try_parse(unsafe { xev.wire_data() })
+        else {
+            return;
+        };
+        if event.kind != present::CompleteKind::PIXMAP {
+            return;
+        }
+
+        let refresh_interval = self.with_window(event.window, |window| {
+            if !window.presentation_requested.swap(false, Ordering::Relaxed) {
+                return None;
+            }
+
+            // The Present extension doesn't report it, so use the mode of the monitor.
+            let monitor = window.shared_state_lock().last_monitor.clone();
+            let refresh =
+                monitor.current_video_mode().and_then(|mode| mode.refresh_rate_millihertz());
+            Some(refresh.map(|mhz| Duration::from_nanos(1_000_000_000_000 / u64::from(mhz.get()))))
+        });
+        let Some(Some(refresh_interval)) = refresh_interval else { return };
+
+        let presentation = FramePresentation {
+            timestamp: Duration::from_micros(event.ust),
+            refresh_interval,
+            sequence: (event.msc != 0).then_some(event.msc),
+        };
+        let window_id = mkwid(event.window);
+        app.window_event(&self.target, window_id, WindowEvent::FramePresented(presentation));
+    }
+
+    /// A click outside of our windows, reported to the popup holding the pointer grab.
+    fn popup_button_press(&self, xev: &XButtonEvent, app: &mut dyn ApplicationHandler) {
+        self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
+        self.dismiss_popups(None, app);
+    }
+
+    /// Dismiss the popups holding a grab, except for `clicked` and the popups it's nested in.
+    fn dismiss_popups(&self, clicked: Option<WindowId>, app: &mut dyn ApplicationHandler) {
+        let windows: Vec<_> =
+            self.target.windows.borrow().values().filter_map(Weak::upgrade).collect();
+
+        let mut kept = Vec::new();
+        let mut next = clicked;
+        while let Some(window_id) = next.filter(|window_id| !kept.contains(window_id)) {
+            kept.push(window_id);
+            next = windows
+                .iter()
+                .find(|window| window.id() == window_id)
+                .and_then(|window| window.popup_parent());
+        }
+
+        for window in windows {
+            if !kept.contains(&window.id()) && window.ungrab_popup_input() {
+                app.window_event(&self.target, window.id(), WindowEvent::PopupDismissed);
+            }
+        }
+    }
+
+    fn drag_status(&mut self, xev: &XClientMessageEvent) {
+        let Some(source) = self.drag_source.as_mut() else { return };
+        if source.target.map(|(window, _)| window) != Some(xev.data.get_long(0) as xproto::Window) {
+            return;
+        }
+
+        source.awaiting_status = false;
+        // The target may answer with any action, fall back to the one we proposed.
+        let action = self
+            .dnd
+            .atom_action(xev.data.get_long(4) as xproto::Atom)
+            .filter(|&action| source.actions.contains(action.into()))
+            .or(source.actions.preferred());
+        source.accepted = action.filter(|_| xev.data.get_long(1) & 1 == 1);
+
+        if let (Some(position), Some((window, _))) = (source.pending_position.take(), source.target)
+        {
+            if !source.dropped {
+                self.dnd
+                    .send_position(source, window, position)
+                    .expect("Failed to send `XdndPosition` message.");
+                source.awaiting_status = true;
+            }
+        }
+    }
+
+    fn drag_finished(&mut self, xev: &XClientMessageEvent, app: &mut dyn ApplicationHandler) {
+        let Some(source) = self.drag_source.as_ref() else { return };
+        let Some((window, version)) = source.target else { return };
+        if !source.dropped || window != xev.data.get_long(0) as xproto::Window {
+            return;
+        }
+
+        let action = if version >= 5 {
+            // Version 5 reports whether the drop succeeded and the performed action.
+            (xev.data.get_long(1) & 1 == 1)
+                .then(|| self.dnd.atom_action(xev.data.get_long(2) as xproto::Atom))
+                .flatten()
+        } else {
+            source.accepted
+        };
+        let result = action.map_or(DragResult::Cancelled, DragResult::Dropped);
+
+        let source = self.drag_source.take().unwrap();
+        self.finish_drag(source, result, app);
+    }
+
+    fn finish_drag(
+        &self,
+        source: DragSource,
+        result: DragResult,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        if !source.dropped {
+            if let Some((window, _)) = source.target {
+                self.dnd.send_leave(&source, window).expect("Failed to send `XdndLeave` message.");
+            }
+            self.ungrab_drag_pointer(source.window);
+        }
+
+        if let Err(err) = self.target.dnd_selection.clear() {
+            warn!("Failed to release the drag selection: {err}");
+        }
+
+        let event = WindowEvent::DragSourceFinished { serial: source.serial, result };
+        app.window_event(&self.target, mkwid(source.window), event);
+    }
+
+    fn ungrab_drag_pointer(&self, window: xproto::Window) {
+        self.target
+            .xconn
+            .ungrab_pointer_for(GrabOwner::Drag(window), self.target.xconn.timestamp())
+            .expect("Failed to ungrab pointer");
+    }
+
     fn configure_notify(&self, xev: &XConfigureEvent, app: &mut dyn ApplicationHandler) {
         let xwindow = xev.window as xproto::Window;
         let window_id = mkwid(xwindow);
@@ -717,6 +1214,13 @@ impl EventProcessor {
             }
         }
 
+        // The strut depends on the monitor the window is on.
+        if moved && window.shared_state_lock().strut.is_some() {
+            if let Err(err) = window.update_strut() {
+                warn!("Failed to update the strut: {err}");
+            }
+        }
+
         // NOTE: Ensure that the lock is dropped before handling the resized and
         // sending the event back to user.
         let hittest = {
@@ -749,9 +1253,18 @@ impl EventProcessor {
         }
 
         if resized {
+            if let Err(err) = window.update_opaque_region(new_surface_size) {
+                warn!("Failed to update the opaque region: {err}");
+            }
+
             let event = WindowEvent::SurfaceResized(new_surface_size.into());
             app.window_event(&self.target, window_id, event);
         }
+
+        // The window manager waits for the frame of the new size.
+        if window.sync_configured() {
+            window.request_redraw();
+        }
     }
 
     /// This is generally a reliable way to detect when the window manager's been
@@ -796,10 +1309,27 @@ impl EventProcessor {
                 .expect("Failed to destroy input context");
         }
 
+        // The selections owned on behalf of the window are gone with it.
+        for selection in self.target.selections() {
+            if let Some(event) = selection.window_destroyed(window) {
+                app.clipboard_event(&self.target, event);
+            }
+        }
+
         app.window_event(&self.target, window_id, WindowEvent::Destroyed);
     }
 
     fn property_notify(&mut self, xev: &XPropertyEvent, app: &mut dyn ApplicationHandler) {
+        let event = self
+            .target
+            .selections()
+            .into_iter()
+            .find_map(|selection| selection.property_notify(xev));
+        if let Some(event) = event {
+            self.selection_event(event, app);
+            return;
+        }
+
         let atoms = self.target.x_connection().atoms();
         let atom = xev.atom as xproto::Atom;
 
@@ -976,11 +1506,8 @@ impl EventProcessor {
         // Set the timestamp.
         self.target.xconn.set_timestamp(event.time as xproto::Timestamp);
 
-        let Some(DeviceType::Mouse) = self
-            .devices
-            .borrow()
-            .get(&mkdid(event.sourceid as xinput::DeviceId))
-            .map(|device| device.r#type)
+        let Some(r#type @ (DeviceType::Mouse | DeviceType::Pen | DeviceType::Eraser)) =
+            self.device_type(event.sourceid)
         else {
             return;
         };
@@ -990,8 +1517,37 @@ impl EventProcessor {
             return;
         }
 
+        if state == ElementState::Pressed {
+            self.dismiss_popups(Some(window_id), app);
+        }
+
         let position = PhysicalPosition::new(event.event_x, event.event_y);
 
+        if let Some(kind) = r#type.tablet_tool_kind() {
+            let button = match event.detail {
+                1 => Some(TabletToolButton::Contact),
+                2 => Some(TabletToolButton::Other(1)),
+                3 => Some(TabletToolButton::Barrel),
+                // Scrolling with the tool is handled like a mouse wheel.
+                4..=7 => None,
+                // Numbered like the mouse buttons in `TabletToolButton::mouse_button()`, where
+                // the back and forward buttons 8 and 9 are `Other(3)` and `Other(4)`.
+                x => Some(TabletToolButton::Other((x - 5) as u16)),
+            };
+
+            if let Some((button, (data, _))) = button.zip(self.tablet_tool_data(event)) {
+                let event = WindowEvent::PointerButton {
+                    device_id,
+                    primary: true,
+                    state,
+                    position,
+                    button: ButtonSource::TabletTool { kind, button, data },
+                };
+                app.window_event(&self.target, window_id, event);
+                return;
+            }
+        }
+
         let event = match event.detail as u32 {
             xlib::Button1 => WindowEvent::PointerButton {
                 device_id,
@@ -1020,16 +1576,21 @@ impl EventProcessor {
             // evdev (and XInput2 in turn) as axis motion, so we don't otherwise
             // special-case these button presses.
             4..=7 => match state {
-                ElementState::Pressed => WindowEvent::MouseWheel {
-                    device_id,
-                    delta: match event.detail {
-                        4 => MouseScrollDelta::LineDelta(0.0, 1.0),
-                        5 => MouseScrollDelta::LineDelta(0.0, -1.0),
-                        6 => MouseScrollDelta::LineDelta(1.0, 0.0),
-                        7 => MouseScrollDelta::LineDelta(-1.0, 0.0),
+                ElementState::Pressed => {
+                    let (x, y) = match event.detail {
+                        4 => (0, 1),
+                        5 => (0, -1),
+                        6 => (1, 0),
+                        7 => (-1, 0),
                         _ => unreachable!(),
-                    },
-                    phase: TouchPhase::Moved,
+                    };
+                    WindowEvent::MouseWheel {
+                        device_id,
+                        delta: MouseScrollDelta::LineDelta(x as f32, y as f32),
+                        phase: TouchPhase::Moved,
+                        source: ScrollSource::Wheel,
+                        value120: Some((x * 120, y * 120)),
+                    }
                 },
                 ElementState::Released => return,
             },
@@ -1061,34 +1622,43 @@ impl EventProcessor {
         // Set the timestamp.
         self.target.xconn.set_timestamp(event.time as xproto::Timestamp);
 
-        let Some(DeviceType::Mouse) = self
-            .devices
-            .borrow()
-            .get(&mkdid(event.sourceid as xinput::DeviceId))
-            .map(|device| device.r#type)
+        let Some(r#type @ (DeviceType::Mouse | DeviceType::Pen | DeviceType::Eraser)) =
+            self.device_type(event.sourceid)
         else {
             return;
         };
 
+        let (source, tool_changed) = match r#type.tablet_tool_kind() {
+            Some(kind) => {
+                let Some((data, changed)) = self.tablet_tool_data(event) else {
+                    return;
+                };
+                (PointerSource::TabletTool { kind, data }, changed)
+            },
+            None => (PointerSource::Mouse, false),
+        };
+
         let device_id = Some(mkdid(event.deviceid as xinput::DeviceId));
         let window = event.event as xproto::Window;
         let window_id = mkwid(window);
         let new_cursor_pos = (event.event_x, event.event_y);
 
         let cursor_moved = self.with_window(window, |window| {
+            // A locked cursor is warped back instead of being moved, including the motion
+            // generated by the warp itself.
+            if window.restore_locked_cursor(new_cursor_pos) {
+                return false;
+            }
+
             let mut shared_state_lock = window.shared_state_lock();
-            util::maybe_change(&mut shared_state_lock.cursor_pos, new_cursor_pos)
+            // The pressure or tilt of a tool can change without it moving.
+            util::maybe_change(&mut shared_state_lock.cursor_pos, new_cursor_pos) || tool_changed
         });
 
         if cursor_moved == Some(true) {
             let position = PhysicalPosition::new(event.event_x, event.event_y);
 
-            let event = WindowEvent::PointerMoved {
-                device_id,
-                primary: true,
-                position,
-                source: PointerSource::Mouse,
-            };
+            let event = WindowEvent::PointerMoved { device_id, primary: true, position, source };
             app.window_event(&self.target, window_id, event);
         } else if cursor_moved.is_none() {
             return;
@@ -1104,6 +1674,7 @@ impl EventProcessor {
             None => return,
         };
 
+        let source = physical_device.scroll_source;
         let mut events = Vec::new();
         let mut value = event.valuators.values;
         for i in 0..event.valuators.mask_len * 8 {
@@ -1119,14 +1690,21 @@ impl EventProcessor {
                 let delta = (x - info.position) / info.increment;
                 info.position = x;
                 // X11 vertical scroll coordinates are opposite to winit's
-                let delta = match info.orientation {
-                    ScrollOrientation::Horizontal => {
-                        MouseScrollDelta::LineDelta(-delta as f32, 0.0)
-                    },
-                    ScrollOrientation::Vertical => MouseScrollDelta::LineDelta(0.0, -delta as f32),
+                let (x, y) = match info.orientation {
+                    ScrollOrientation::Horizontal => (-delta, 0.0),
+                    ScrollOrientation::Vertical => (0.0, -delta),
                 };
+                // The increment of the smooth scroll valuators of wheels is one step.
+                let value120 = (source == ScrollSource::Wheel)
+                    .then(|| ((x * 120.).round() as i32, (y * 120.).round() as i32));
 
-                let event = WindowEvent::MouseWheel { device_id, delta, phase: TouchPhase::Moved };
+                let event = WindowEvent::MouseWheel {
+                    device_id,
+                    delta: MouseScrollDelta::LineDelta(x as f32, y as f32),
+                    phase: TouchPhase::Moved,
+                    source,
+                    value120,
+                };
                 events.push(event);
             }
 
@@ -1167,12 +1745,11 @@ impl EventProcessor {
             let device_id = Some(device_id);
             let position = PhysicalPosition::new(event.event_x, event.event_y);
 
-            let event = WindowEvent::PointerEntered {
-                device_id,
-                primary: true,
-                position,
-                kind: PointerKind::Mouse,
-            };
+            let kind = self
+                .device_type(event.sourceid)
+                .and_then(DeviceType::tablet_tool_kind)
+                .map_or(PointerKind::Mouse, PointerKind::TabletTool);
+            let event = WindowEvent::PointerEntered { device_id, primary: true, position, kind };
             app.window_event(&self.target, window_id, event);
         }
     }
@@ -1187,11 +1764,15 @@ impl EventProcessor {
         // been destroyed, which the user presumably doesn't want to deal with.
         if self.window_exists(window) {
             let window_id = mkwid(window);
+            let kind = self
+                .device_type(event.sourceid)
+                .and_then(DeviceType::tablet_tool_kind)
+                .map_or(PointerKind::Mouse, PointerKind::TabletTool);
             let event = WindowEvent::PointerLeft {
                 device_id: Some(mkdid(event.deviceid as xinput::DeviceId)),
                 primary: true,
                 position: Some(PhysicalPosition::new(event.event_x, event.event_y)),
-                kind: PointerKind::Mouse,
+                kind,
             };
             app.window_event(&self.target, window_id, event);
         }
@@ -1220,6 +1801,9 @@ impl EventProcessor {
 
         if let Some(window) = self.with_window(window, Arc::clone) {
             window.shared_state_lock().has_focus = true;
+            if let Err(err) = window.update_keyboard_grab() {
+                warn!("Failed to grab the keyboard: {err}");
+            }
         }
 
         app.window_event(&self.target, window_id, WindowEvent::Focused(true));
@@ -1293,6 +1877,9 @@ impl EventProcessor {
 
             if let Some(window) = self.with_window(window, Arc::clone) {
                 window.shared_state_lock().has_focus = false;
+                if let Err(err) = window.update_keyboard_grab() {
+                    warn!("Failed to release the keyboard grab: {err}");
+                }
             }
 
             app.window_event(&self.target, window_id, WindowEvent::Focused(false));
@@ -1375,6 +1962,99 @@ impl EventProcessor {
         }
     }
 
+    fn xinput2_pinch_gesture(
+        &mut self,
+        xev: &ffi::XIGesturePinchEvent,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        // Set the timestamp.
+        self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
+
+        let window = xev.event as xproto::Window;
+        if !self.window_exists(window) {
+            return;
+        }
+
+        let (phase, pan_delta, pinch_delta, rotation_delta) = match xev.evtype {
+            ffi::XI_GesturePinchBegin => {
+                // We only support two fingers for now.
+                if xev.detail != 2 {
+                    return;
+                }
+
+                self.pinch_scale = Some(xev.scale);
+                (TouchPhase::Started, PhysicalPosition::new(0., 0.), 0., 0.)
+            },
+            ffi::XI_GesturePinchUpdate => {
+                let Some(pinch_scale) = self.pinch_scale.replace(xev.scale) else {
+                    return;
+                };
+
+                let pan_delta = PhysicalPosition::new(xev.delta_x as f32, xev.delta_y as f32);
+                // The angle is in degrees cw, opposite of winit's degrees ccw.
+                (TouchPhase::Moved, pan_delta, xev.scale - pinch_scale, -xev.delta_angle as f32)
+            },
+            ffi::XI_GesturePinchEnd => {
+                if self.pinch_scale.take().is_none() {
+                    return;
+                }
+
+                let phase = if xev.flags & ffi::XIGesturePinchEventCancelled == 0 {
+                    TouchPhase::Ended
+                } else {
+                    TouchPhase::Cancelled
+                };
+                (phase, PhysicalPosition::new(0., 0.), 0., 0.)
+            },
+            _ => unreachable!(),
+        };
+
+        let window_id = mkwid(window);
+        let device_id = Some(mkdid(xev.deviceid as xinput::DeviceId));
+
+        // The chance of only one of these events being necessary is extremely small,
+        // so it is easier to just send all three
+        let event = WindowEvent::PanGesture { device_id, delta: pan_delta, phase };
+        app.window_event(&self.target, window_id, event);
+        let event = WindowEvent::PinchGesture { device_id, delta: pinch_delta, phase };
+        app.window_event(&self.target, window_id, event);
+        let event = WindowEvent::RotationGesture { device_id, delta: rotation_delta, phase };
+        app.window_event(&self.target, window_id, event);
+    }
+
+    fn xinput2_swipe_gesture(
+        &self,
+        xev: &ffi::XIGestureSwipeEvent,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        // Set the timestamp.
+        self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
+
+        let window = xev.event as xproto::Window;
+        if !self.window_exists(window) {
+            return;
+        }
+
+        let (phase, delta) = match xev.evtype {
+            ffi::XI_GestureSwipeBegin => (TouchPhase::Started, PhysicalPosition::new(0., 0.)),
+            ffi::XI_GestureSwipeUpdate => {
+                (TouchPhase::Moved, PhysicalPosition::new(xev.delta_x as f32, xev.delta_y as f32))
+            },
+            ffi::XI_GestureSwipeEnd if xev.flags & ffi::XIGestureSwipeEventCancelled == 0 => {
+                (TouchPhase::Ended, PhysicalPosition::new(0., 0.))
+            },
+            ffi::XI_GestureSwipeEnd => (TouchPhase::Cancelled, PhysicalPosition::new(0., 0.)),
+            _ => unreachable!(),
+        };
+
+        let event = WindowEvent::PanGesture {
+            device_id: Some(mkdid(xev.deviceid as xinput::DeviceId)),
+            delta,
+            phase,
+        };
+        app.window_event(&self.target, mkwid(window), event);
+    }
+
     fn xinput2_raw_button_input(
         &self,
         xev: &XIRawEvent,
@@ -1460,16 +2140,28 @@ impl EventProcessor {
         app.device_event(&self.target, device_id, event);
     }
 
-    fn xinput2_hierarchy_changed(&mut self, xev: &XIHierarchyEvent) {
+    fn xinput2_hierarchy_changed(
+        &mut self,
+        xev: &XIHierarchyEvent,
+        app: &mut dyn ApplicationHandler,
+    ) {
         // Set the timestamp.
         self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
         let infos = unsafe { slice::from_raw_parts(xev.info, xev.num_info as usize) };
         for info in infos {
+            let device_id = mkdid(info.deviceid as xinput::DeviceId);
             if 0 != info.flags & (xinput2::XISlaveAdded | xinput2::XIMasterAdded) {
                 self.init_device(info.deviceid as xinput::DeviceId);
             } else if 0 != info.flags & (xinput2::XISlaveRemoved | xinput2::XIMasterRemoved) {
                 let mut devices = self.devices.borrow_mut();
-                devices.remove(&mkdid(info.deviceid as xinput::DeviceId));
+                devices.remove(&device_id);
+            }
+
+            // Only the physical devices are reported, see `ActiveEventLoop::devices`.
+            if 0 != info.flags & xinput2::XISlaveAdded {
+                app.device_event(&self.target, Some(device_id), DeviceEvent::Added);
+            } else if 0 != info.flags & xinput2::XISlaveRemoved {
+                app.device_event(&self.target, Some(device_id), DeviceEvent::Removed);
             }
         }
     }
@@ -1504,6 +2196,7 @@ impl EventProcessor {
                         let mods = state.modifiers().into();
                         self.send_modifiers(window_id, mods, true, app);
                     }
+                    self.send_keyboard_layouts(window_id, false, app);
                 }
             },
             xlib::XkbMapNotify => {
@@ -1519,6 +2212,7 @@ impl EventProcessor {
                     let mods = state.modifiers().into();
                     self.send_modifiers(window_id, mods, true, app);
                 }
+                self.send_keyboard_layouts(window_id, false, app);
             },
             xlib::XkbStateNotify => {
                 let xev = unsafe { &*(xev as *const _ as *const xlib::XkbStateNotifyEvent) };
@@ -1543,6 +2237,7 @@ impl EventProcessor {
 
                     let mods = state.modifiers().into();
                     self.send_modifiers(window_id, mods, true, app);
+                    self.send_keyboard_layouts(window_id, false, app);
                 }
             },
             _ => {},
@@ -1575,6 +2270,7 @@ impl EventProcessor {
 
             let mods = state.modifiers();
             self.send_modifiers(window_id, mods.into(), force, app);
+            self.send_keyboard_layouts(window_id, force, app);
         }
     }
 
@@ -1608,7 +2304,8 @@ impl EventProcessor {
         }
 
         let mods = xkb_state.modifiers();
-        self.send_modifiers(window_id, mods.into(), true, app)
+        self.send_modifiers(window_id, mods.into(), true, app);
+        self.send_keyboard_layouts(window_id, true, app);
     }
 
     pub(crate) fn update_mods_from_core_event(
@@ -1641,6 +2338,7 @@ impl EventProcessor {
 
         let mods = xkb_state.modifiers();
         self.send_modifiers(window_id, mods.into(), false, app);
+        self.send_keyboard_layouts(window_id, false, app);
     }
 
     // Bits 13 and 14 report the state keyboard group.
@@ -1703,6 +2401,20 @@ impl EventProcessor {
         }
     }
 
+    /// Send the keyboard layouts when they changed, or unconditionally when `force` is `true`.
+    fn send_keyboard_layouts(
+        &mut self,
+        window_id: winit_core::window::WindowId,
+        force: bool,
+        app: &mut dyn ApplicationHandler,
+    ) {
+        let Some(layouts) = self.xkb_context.keyboard_layouts() else { return };
+        if self.keyboard_layouts.as_ref() != Some(&layouts) || force {
+            self.keyboard_layouts = Some(layouts.clone());
+            app.window_event(&self.target, window_id, WindowEvent::KeyboardLayoutChanged(layouts));
+        }
+    }
+
     fn handle_pressed_keys(
         target: &ActiveEventLoop,
         window_id: winit_core::window::WindowId,
@@ -1738,7 +2450,6 @@ impl EventProcessor {
     fn process_dpi_change(&self, app: &mut dyn ApplicationHandler) {
         self.target.xconn.reload_database().expect("failed to reload Xft database");
 
-        // In the future, it would be quite easy to emit monitor hotplug events.
         let prev_list = {
             let prev_list = self.target.xconn.invalidate_cached_monitor_list();
             match prev_list {
@@ -1748,6 +2459,18 @@ impl EventProcessor {
         };
 
         let new_list = self.target.xconn.available_monitors().expect("Failed to get monitor list");
+        for event in monitor::monitor_changes(&prev_list, &new_list) {
+            app.monitor_event(&self.target, event);
+        }
+
+        for window in self.target.windows.borrow().values().filter_map(|w| w.upgrade()) {
+            if window.shared_state_lock().strut.is_some() {
+                if let Err(err) = window.update_strut() {
+                    warn!("Failed to update the strut: {err}");
+                }
+            }
+        }
+
         for new_monitor in new_list {
             // Previous list may be empty, in case of disconnecting and
             // reconnecting the only one monitor. We still need to emit events in
@@ -1757,7 +2480,7 @@ impl EventProcessor {
                 .find(|prev_monitor| prev_monitor.name == new_monitor.name)
                 .map(|prev_monitor| prev_monitor.scale_factor);
             if Some(new_monitor.scale_factor) != maybe_prev_scale_factor {
-                for window in self.target.windows.borrow().iter().filter_map(|(_, w)| w.upgrade()) {
+                for window in self.target.windows.borrow().values().filter_map(|w| w.upgrade()) {
                     window.refresh_dpi_for_monitor(
                         &new_monitor,
                         maybe_prev_scale_factor,
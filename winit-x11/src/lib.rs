@@ -3,7 +3,12 @@
 use dpi::Size;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use winit_core::event_loop::ActiveEventLoop as CoreActiveEventLoop;
+#[cfg(doc)]
+use winit_core::clipboard::ClipboardEvent;
+use winit_core::clipboard::{Clipboard, ClipboardData};
+use winit_core::error::{NotSupportedError, RequestError};
+use winit_core::event_loop::{ActiveEventLoop as CoreActiveEventLoop, AsyncRequestSerial};
+use winit_core::monitor::MonitorHandle as CoreMonitorHandle;
 use winit_core::window::{ActivationToken, PlatformWindowAttributes, Window as CoreWindow};
 
 pub use crate::event_loop::{ActiveEventLoop, EventLoop};
@@ -15,6 +20,8 @@ macro_rules! os_error {
 
 mod activation;
 mod atoms;
+mod clipboard;
+mod dbus;
 mod dnd;
 mod event_loop;
 mod event_processor;
@@ -108,6 +115,36 @@ pub fn register_xlib_error_hook(hook: XlibErrorHook) {
 pub trait ActiveEventLoopExtX11 {
     /// True if the event loop uses X11.
     fn is_x11(&self) -> bool;
+
+    /// Take ownership of the `PRIMARY` selection on behalf of `window`, offering `contents`.
+    ///
+    /// The selection is taken with the timestamp of the latest event, so this should be called
+    /// in response to user input, typically when text is selected. Ownership is lost when another
+    /// client takes the selection, which is reported with
+    /// [`ClipboardEvent::PrimarySelectionOwnershipLost`], or when `window` is destroyed.
+    ///
+    /// Returns [`RequestError::Ignored`] when the X server refused the ownership change.
+    fn set_primary_selection(
+        &self,
+        window: &dyn CoreWindow,
+        contents: Vec<ClipboardData>,
+    ) -> Result<(), RequestError>;
+
+    /// Give up ownership of the `PRIMARY` selection, if we own it.
+    fn clear_primary_selection(&self) -> Result<(), RequestError>;
+
+    /// Request the MIME types offered through the `PRIMARY` selection.
+    ///
+    /// The result is delivered with [`ClipboardEvent::MimeTypes`] carrying the returned serial.
+    fn request_primary_selection_mime_types(&self) -> Result<AsyncRequestSerial, RequestError>;
+
+    /// Request the contents of the `PRIMARY` selection as `mime_type`.
+    ///
+    /// The result is delivered with [`ClipboardEvent::Data`] carrying the returned serial.
+    fn request_primary_selection(
+        &self,
+        mime_type: &str,
+    ) -> Result<AsyncRequestSerial, RequestError>;
 }
 
 impl ActiveEventLoopExtX11 for dyn CoreActiveEventLoop + '_ {
@@ -115,6 +152,39 @@ impl ActiveEventLoopExtX11 for dyn CoreActiveEventLoop + '_ {
     fn is_x11(&self) -> bool {
         self.cast_ref::<ActiveEventLoop>().is_some()
     }
+
+    fn set_primary_selection(
+        &self,
+        window: &dyn CoreWindow,
+        contents: Vec<ClipboardData>,
+    ) -> Result<(), RequestError> {
+        let event_loop = x11_event_loop(self)?;
+        let window = window
+            .cast_ref::<Window>()
+            .ok_or(NotSupportedError::new("the window is not an X11 window"))?;
+        event_loop.primary_selection.set_contents_for(window.xlib_window() as XWindow, contents)
+    }
+
+    fn clear_primary_selection(&self) -> Result<(), RequestError> {
+        x11_event_loop(self)?.primary_selection.clear()
+    }
+
+    fn request_primary_selection_mime_types(&self) -> Result<AsyncRequestSerial, RequestError> {
+        x11_event_loop(self)?.primary_selection.request_mime_types()
+    }
+
+    fn request_primary_selection(
+        &self,
+        mime_type: &str,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        x11_event_loop(self)?.primary_selection.request_data(mime_type)
+    }
+}
+
+fn x11_event_loop(event_loop: &dyn CoreActiveEventLoop) -> Result<&ActiveEventLoop, RequestError> {
+    event_loop
+        .cast_ref::<ActiveEventLoop>()
+        .ok_or_else(|| NotSupportedError::new("the event loop is not using X11").into())
 }
 
 /// Additional methods on [`EventLoop`] that are specific to X11.
@@ -138,9 +208,59 @@ pub trait EventLoopBuilderExtX11 {
 /// Additional methods on [`Window`] that are specific to X11.
 ///
 /// [`Window`]: crate::window::Window
-pub trait WindowExtX11 {}
+pub trait WindowExtX11 {
+    /// Reserve space along an edge of a monitor for the window, or stop reserving it with `None`.
+    ///
+    /// See [`WindowAttributesX11::with_strut()`].
+    fn set_strut(&self, strut: Option<Strut>) -> Result<(), RequestError>;
+}
 
-impl WindowExtX11 for dyn CoreWindow {}
+impl WindowExtX11 for dyn CoreWindow + '_ {
+    fn set_strut(&self, strut: Option<Strut>) -> Result<(), RequestError> {
+        let window = self
+            .cast_ref::<Window>()
+            .ok_or(NotSupportedError::new("the window is not an X11 window"))?;
+        window.set_strut(strut)
+    }
+}
+
+/// The edge of a monitor along which a [`Strut`] reserves space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StrutEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Space reserved along an edge of a monitor, which maximized windows don't overlap.
+///
+/// This is what panels and docks use, alongside [`WindowType::Dock`]. It's set with
+/// `_NET_WM_STRUT_PARTIAL`, and `_NET_WM_STRUT` for older window managers, and recomputed when
+/// the window moves or the monitors change.
+#[derive(Debug, Clone)]
+pub struct Strut {
+    pub(crate) edge: StrutEdge,
+    pub(crate) thickness: u32,
+    pub(crate) monitor: Option<CoreMonitorHandle>,
+}
+
+impl Strut {
+    /// Reserve `thickness` physical pixels along `edge` of the monitor the window is on.
+    pub fn new(edge: StrutEdge, thickness: u32) -> Self {
+        Self { edge, thickness, monitor: None }
+    }
+
+    /// Reserve the space on `monitor`, instead of the monitor the window is on.
+    ///
+    /// Nothing is reserved while the monitor is disconnected.
+    #[inline]
+    pub fn with_monitor(mut self, monitor: CoreMonitorHandle) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct ApplicationName {
@@ -157,6 +277,7 @@ pub struct WindowAttributesX11 {
     pub(crate) base_size: Option<Size>,
     pub(crate) override_redirect: bool,
     pub(crate) x11_window_types: Vec<WindowType>,
+    pub(crate) strut: Option<Strut>,
 
     /// The parent window to embed this window into.
     pub(crate) embed_window: Option<XWindow>,
@@ -172,6 +293,7 @@ impl Default for WindowAttributesX11 {
             base_size: None,
             override_redirect: false,
             x11_window_types: vec![WindowType::Normal],
+            strut: None,
             embed_window: None,
         }
     }
@@ -214,6 +336,14 @@ impl WindowAttributesX11 {
         self
     }
 
+    /// Build window reserving space along an edge of a monitor, like panels and docks do.
+    ///
+    /// See [`Strut`].
+    pub fn with_strut(mut self, strut: Strut) -> Self {
+        self.strut = Some(strut);
+        self
+    }
+
     /// Build window with base size hint.
     ///
     /// ```
@@ -1,7 +1,10 @@
 use std::num::NonZeroU32;
+use std::sync::Arc;
 
 use dpi::PhysicalPosition;
-use winit_core::monitor::{MonitorHandleProvider, VideoMode};
+use winit_core::monitor::{
+    MonitorEvent, MonitorHandle as CoreMonitorHandle, MonitorHandleProvider, VideoMode,
+};
 use x11rb::connection::RequestConnection;
 use x11rb::protocol::randr::{self, ConnectionExt as _};
 use x11rb::protocol::xproto;
@@ -37,6 +40,8 @@ impl From<VideoModeHandle> for VideoMode {
 pub struct MonitorHandle {
     /// The actual id
     pub(crate) id: randr::Crtc,
+    /// The output shown on the CRTC, which identifies the monitor across reconfigurations
+    pub(crate) output: randr::Output,
     /// The name of the monitor
     pub(crate) name: String,
     /// The position of the monitor in the X screen
@@ -133,12 +138,14 @@ impl MonitorHandle {
 
         let rect = util::AaRect::new(position, dimensions);
 
-        Some(MonitorHandle { id, name, scale_factor, position, primary, rect, video_modes })
+        let output = crtc.outputs[0];
+        Some(MonitorHandle { id, output, name, scale_factor, position, primary, rect, video_modes })
     }
 
     pub fn dummy() -> Self {
         MonitorHandle {
             id: 0,
+            output: 0,
             name: "<dummy monitor>".into(),
             scale_factor: 1.0,
             position: (0, 0),
@@ -152,6 +159,34 @@ impl MonitorHandle {
         // Zero is an invalid XID value; no real monitor will have it
         self.id == 0
     }
+
+    /// Whether `other` has the same CRTC, geometry, scale factor and current video mode.
+    pub(crate) fn same_configuration(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.rect == other.rect
+            && self.scale_factor == other.scale_factor
+            && self.current_video_mode() == other.current_video_mode()
+    }
+}
+
+/// The events describing the changes from the `prev` monitors to the `new` ones.
+///
+/// Monitors are matched by their RandR output, since names aren't unique and CRTCs get
+/// reassigned.
+pub(crate) fn monitor_changes(prev: &[MonitorHandle], new: &[MonitorHandle]) -> Vec<MonitorEvent> {
+    let handle = |monitor: &MonitorHandle| CoreMonitorHandle(Arc::new(monitor.clone()));
+
+    let removed = prev
+        .iter()
+        .filter(|prev| !new.iter().any(|new| new.output == prev.output))
+        .map(|prev| MonitorEvent::Removed(handle(prev)));
+    let changed =
+        new.iter().filter_map(|new| match prev.iter().find(|prev| prev.output == new.output) {
+            None => Some(MonitorEvent::Added(handle(new))),
+            Some(prev) if !prev.same_configuration(new) => Some(MonitorEvent::Changed(handle(new))),
+            Some(_) => None,
+        });
+    removed.chain(changed).collect()
 }
 
 impl XConnection {
@@ -313,3 +348,48 @@ impl ScreenResources {
         Self { modes: reply.modes, crtcs: reply.crtcs }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(id: randr::Crtc, output: randr::Output, name: &str, x: i32) -> MonitorHandle {
+        MonitorHandle {
+            id,
+            output,
+            name: name.into(),
+            position: (x, 0),
+            rect: util::AaRect::new((x, 0), (1920, 1080)),
+            ..MonitorHandle::dummy()
+        }
+    }
+
+    fn summary(events: &[MonitorEvent]) -> Vec<(&'static str, u64)> {
+        events
+            .iter()
+            .map(|event| match event {
+                MonitorEvent::Added(monitor) => ("added", monitor.native_id()),
+                MonitorEvent::Removed(monitor) => ("removed", monitor.native_id()),
+                MonitorEvent::Changed(monitor) => ("changed", monitor.native_id()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn monitor_changes_match_outputs() {
+        let prev = [monitor(1, 10, "DP-1", 0), monitor(2, 20, "DP-2", 1920)];
+
+        assert!(monitor_changes(&prev, &prev).is_empty());
+
+        // Monitors sharing a name are still told apart.
+        let new = [monitor(1, 10, "DP-1", 0), monitor(3, 30, "DP-1", 1920)];
+        assert_eq!(summary(&monitor_changes(&prev, &new)), [("removed", 2), ("added", 3)]);
+
+        // An output moved to another CRTC is the same monitor.
+        let new = [monitor(1, 10, "DP-1", 0), monitor(3, 20, "DP-2", 1920)];
+        assert_eq!(summary(&monitor_changes(&prev, &new)), [("changed", 3)]);
+
+        let new = [monitor(1, 10, "DP-1", 0), monitor(2, 20, "DP-2", 3840)];
+        assert_eq!(summary(&monitor_changes(&prev, &new)), [("changed", 2)]);
+    }
+}
@@ -1,7 +1,8 @@
-use std::ffi::c_int;
+use std::ffi::{c_int, c_ulong};
 use std::sync::Arc;
+use std::{ptr, slice};
 
-use x11_dl::xlib::{self, XEvent, XGenericEventCookie};
+use x11_dl::xlib::{self, Display, XEvent, XGenericEventCookie, xEvent};
 
 use crate::xdisplay::XConnection;
 
@@ -35,6 +36,19 @@ impl GenericEventCookie {
         self.cookie.evtype
     }
 
+    /// The data of an event of an extension registered with [`keep_wire_events()`].
+    ///
+    /// ## SAFETY
+    ///
+    /// The event must belong to such an extension.
+    #[inline]
+    pub unsafe fn wire_data(&self) -> &[u8] {
+        unsafe {
+            let data = self.cookie.data as *const u8;
+            slice::from_raw_parts(data, generic_event_len(data))
+        }
+    }
+
     /// Borrow inner event data as `&T`.
     ///
     /// ## SAFETY
@@ -53,3 +67,174 @@ impl Drop for GenericEventCookie {
         }
     }
 }
+
+/// Keep the generic events of the extension with the given major opcode as their wire data, to be
+/// read with [`GenericEventCookie::wire_data()`].
+///
+/// Xlib drops the data of the generic events from the extensions it doesn't handle itself.
+pub fn keep_wire_events(xconn: &XConnection, major_opcode: u8) {
+    unsafe {
+        (xconn.xlib.XESetWireToEventCookie)(
+            xconn.display,
+            major_opcode.into(),
+            Some(wire_to_cookie),
+        );
+        (xconn.xlib.XESetCopyEventCookie)(xconn.display, major_opcode.into(), Some(copy_cookie));
+    }
+}
+
+/// The length of the `xGenericEvent` at `data`, including its additional data.
+unsafe fn generic_event_len(data: *const u8) -> usize {
+    let length = unsafe { ptr::read_unaligned(data.add(4) as *const u32) };
+    32 + 4 * length as usize
+}
+
+/// Copy `len` bytes from `data` to memory that Xlib frees with `XFreeEventData`.
+unsafe fn copy_to_xlib(data: *const u8, len: usize) -> *mut u8 {
+    unsafe {
+        let copy = libc::malloc(len) as *mut u8;
+        if !copy.is_null() {
+            ptr::copy_nonoverlapping(data, copy, len);
+        }
+        copy
+    }
+}
+
+unsafe extern "C" fn wire_to_cookie(
+    display: *mut Display,
+    cookie: *mut XGenericEventCookie,
+    event: *mut xEvent,
+) -> c_int {
+    unsafe {
+        // Xlib reads the additional data right after the 32 bytes of the event.
+        let wire = event as *const u8;
+        let data = copy_to_xlib(wire, generic_event_len(wire));
+        if data.is_null() {
+            return xlib::False;
+        }
+
+        let cookie = &mut *cookie;
+        cookie.type_ = c_int::from(*wire & 0x7f);
+        cookie.serial = c_ulong::from(ptr::read_unaligned(wire.add(2) as *const u16));
+        cookie.send_event = c_int::from(*wire & 0x80 != 0);
+        cookie.display = display;
+        cookie.extension = c_int::from(*wire.add(1));
+        cookie.evtype = c_int::from(ptr::read_unaligned(wire.add(8) as *const u16));
+        cookie.data = data.cast();
+        xlib::True
+    }
+}
+
+unsafe extern "C" fn copy_cookie(
+    _: *mut Display,
+    from: *mut XGenericEventCookie,
+    to: *mut XGenericEventCookie,
+) -> c_int {
+    unsafe {
+        let from = &*from;
+        let data = from.data as *const u8;
+        let copy = copy_to_xlib(data, generic_event_len(data));
+        if copy.is_null() {
+            return xlib::False;
+        }
+
+        *to = XGenericEventCookie { data: copy.cast(), ..*from };
+        xlib::True
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use x11rb::protocol::present::{CompleteKind, CompleteMode, CompleteNotifyEvent};
+    use x11rb::x11_utils::{Serialize, TryParse};
+
+    use super::*;
+
+    fn complete_notify() -> CompleteNotifyEvent {
+        CompleteNotifyEvent {
+            response_type: x11rb::protocol::xproto::GE_GENERIC_EVENT,
+            extension: 147,
+            sequence: 0x1234,
+            length: 2,
+            event_type: x11rb::protocol::present::COMPLETE_NOTIFY_EVENT,
+            kind: CompleteKind::PIXMAP,
+            mode: CompleteMode::FLIP,
+            event: 7,
+            window: 0x0040_0001,
+            serial: 42,
+            ust: 1_000_000,
+            msc: 60,
+        }
+    }
+
+    fn free(cookie: &XGenericEventCookie) {
+        unsafe { libc::free(cookie.data) };
+    }
+
+    #[test]
+    fn wire_event_length_includes_additional_data() {
+        let wire = complete_notify().serialize();
+        assert_eq!(unsafe { generic_event_len(wire.as_ptr()) }, wire.len());
+    }
+
+    #[test]
+    fn wire_to_cookie_keeps_the_wire_data() {
+        let event = complete_notify();
+        let mut wire = event.serialize();
+        let mut cookie: XGenericEventCookie = unsafe { std::mem::zeroed() };
+        let display = ptr::dangling_mut::<Display>();
+
+        let converted =
+            unsafe { wire_to_cookie(display, &mut cookie, wire.as_mut_ptr() as *mut xEvent) };
+        assert_eq!(converted, xlib::True);
+        assert_eq!(cookie.type_, xlib::GenericEvent);
+        assert_eq!(cookie.serial, 0x1234);
+        assert_eq!(cookie.send_event, xlib::False);
+        assert_eq!(cookie.display, display);
+        assert_eq!(cookie.extension, 147);
+        assert_eq!(cookie.evtype, c_int::from(event.event_type));
+
+        let data = unsafe { slice::from_raw_parts(cookie.data as *const u8, wire.len()) };
+        assert_eq!(data, wire);
+        let (parsed, _) = CompleteNotifyEvent::try_parse(data).unwrap();
+        assert_eq!(
+            (parsed.window, parsed.serial, parsed.ust, parsed.msc),
+            (event.window, event.serial, event.ust, event.msc)
+        );
+        free(&cookie);
+    }
+
+    #[test]
+    fn wire_to_cookie_reads_the_send_event_flag() {
+        let mut wire = complete_notify().serialize();
+        wire[0] |= 0x80;
+        let mut cookie: XGenericEventCookie = unsafe { std::mem::zeroed() };
+
+        unsafe { wire_to_cookie(ptr::null_mut(), &mut cookie, wire.as_mut_ptr() as *mut xEvent) };
+        assert_eq!(cookie.type_, xlib::GenericEvent);
+        assert_eq!(cookie.send_event, xlib::True);
+        free(&cookie);
+    }
+
+    #[test]
+    fn copy_cookie_duplicates_the_wire_data() {
+        let mut wire = complete_notify().serialize();
+        let mut from: XGenericEventCookie = unsafe { std::mem::zeroed() };
+        let mut to: XGenericEventCookie = unsafe { std::mem::zeroed() };
+
+        unsafe {
+            wire_to_cookie(ptr::null_mut(), &mut from, wire.as_mut_ptr() as *mut xEvent);
+            assert_eq!(copy_cookie(ptr::null_mut(), &mut from, &mut to), xlib::True);
+        }
+        assert_ne!(to.data, from.data);
+        assert_eq!(
+            (to.type_, to.serial, to.extension, to.evtype),
+            (from.type_, from.serial, from.extension, from.evtype)
+        );
+
+        free(&from);
+        let data = unsafe { slice::from_raw_parts(to.data as *const u8, wire.len()) };
+        assert_eq!(data, wire);
+        free(&to);
+    }
+}
@@ -1,6 +1,9 @@
 use std::cmp;
 
+use winit_core::window::{PopupAnchor, PopupAttributes, PopupConstraintAdjustment, PopupGravity};
+
 use super::*;
+use crate::StrutEdge;
 
 // Friendly neighborhood axis-aligned rectangle
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -35,6 +38,187 @@ impl AaRect {
     }
 }
 
+/// Place a popup of the given size relative to its anchor rectangle, keeping it within the bounds
+/// as far as the constraint adjustment allows, following the semantics of `xdg_positioner`.
+///
+/// Returns the position and the size of the popup.
+pub fn place_popup(
+    popup: &PopupAttributes,
+    anchor_rect: &AaRect,
+    (width, height): (u32, u32),
+    bounds: &AaRect,
+) -> ((i32, i32), (u32, u32)) {
+    let (anchor_x, anchor_y) = anchor_sides(popup.anchor);
+    let (gravity_x, gravity_y) = gravity_sides(popup.gravity);
+    let adjustment = popup.constraint_adjustment;
+
+    let (x, width) = place_popup_axis(
+        (anchor_rect.x, anchor_rect.width),
+        (anchor_x, gravity_x),
+        width as i64,
+        (bounds.x, bounds.width),
+        [
+            adjustment.contains(PopupConstraintAdjustment::FLIP_X),
+            adjustment.contains(PopupConstraintAdjustment::SLIDE_X),
+            adjustment.contains(PopupConstraintAdjustment::RESIZE_X),
+        ],
+    );
+    let (y, height) = place_popup_axis(
+        (anchor_rect.y, anchor_rect.height),
+        (anchor_y, gravity_y),
+        height as i64,
+        (bounds.y, bounds.height),
+        [
+            adjustment.contains(PopupConstraintAdjustment::FLIP_Y),
+            adjustment.contains(PopupConstraintAdjustment::SLIDE_Y),
+            adjustment.contains(PopupConstraintAdjustment::RESIZE_Y),
+        ],
+    );
+
+    ((x as i32, y as i32), (width as u32, height as u32))
+}
+
+/// A side of a rectangle along one axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Start,
+    Center,
+    End,
+}
+
+impl Side {
+    fn flip(self) -> Self {
+        match self {
+            Side::Start => Side::End,
+            Side::Center => Side::Center,
+            Side::End => Side::Start,
+        }
+    }
+}
+
+fn anchor_sides(anchor: PopupAnchor) -> (Side, Side) {
+    match anchor {
+        PopupAnchor::Center => (Side::Center, Side::Center),
+        PopupAnchor::Top => (Side::Center, Side::Start),
+        PopupAnchor::Bottom => (Side::Center, Side::End),
+        PopupAnchor::Left => (Side::Start, Side::Center),
+        PopupAnchor::Right => (Side::End, Side::Center),
+        PopupAnchor::TopLeft => (Side::Start, Side::Start),
+        PopupAnchor::BottomLeft => (Side::Start, Side::End),
+        PopupAnchor::TopRight => (Side::End, Side::Start),
+        PopupAnchor::BottomRight => (Side::End, Side::End),
+    }
+}
+
+fn gravity_sides(gravity: PopupGravity) -> (Side, Side) {
+    match gravity {
+        PopupGravity::Center => (Side::Center, Side::Center),
+        PopupGravity::Top => (Side::Center, Side::Start),
+        PopupGravity::Bottom => (Side::Center, Side::End),
+        PopupGravity::Left => (Side::Start, Side::Center),
+        PopupGravity::Right => (Side::End, Side::Center),
+        PopupGravity::TopLeft => (Side::Start, Side::Start),
+        PopupGravity::BottomLeft => (Side::Start, Side::End),
+        PopupGravity::TopRight => (Side::End, Side::Start),
+        PopupGravity::BottomRight => (Side::End, Side::End),
+    }
+}
+
+/// Place the popup along one axis, returning its start and length.
+fn place_popup_axis(
+    (rect_start, rect_len): (i64, i64),
+    (anchor, gravity): (Side, Side),
+    len: i64,
+    (bounds_start, bounds_len): (i64, i64),
+    [flip, slide, resize]: [bool; 3],
+) -> (i64, i64) {
+    let place = |anchor: Side, gravity: Side| {
+        let point = match anchor {
+            Side::Start => rect_start,
+            Side::Center => rect_start + rect_len / 2,
+            Side::End => rect_start + rect_len,
+        };
+        // The gravity is the direction the popup extends to from the anchor point.
+        match gravity {
+            Side::Start => point - len,
+            Side::Center => point - len / 2,
+            Side::End => point,
+        }
+    };
+    let bounds_end = bounds_start + bounds_len;
+    let fits = |start: i64| start >= bounds_start && start + len <= bounds_end;
+
+    let start = place(anchor, gravity);
+    if fits(start) {
+        return (start, len);
+    }
+
+    if flip {
+        let flipped = place(anchor.flip(), gravity.flip());
+        if fits(flipped) {
+            return (flipped, len);
+        }
+    }
+
+    if slide {
+        // Keep the start of the popup visible when it's larger than the bounds.
+        let slid = cmp::max(cmp::min(start, bounds_end - len), bounds_start);
+        if !resize || fits(slid) {
+            return (slid, len);
+        }
+    }
+
+    if resize {
+        let resized_start = cmp::max(start, bounds_start);
+        let resized_end = cmp::min(start + len, bounds_end);
+        return (resized_start, cmp::max(resized_end - resized_start, 1));
+    }
+
+    (start, len)
+}
+
+/// The `_NET_WM_STRUT_PARTIAL` reserving `thickness` pixels along `edge` of `monitor`, for a root
+/// window of the given size.
+///
+/// The strut is relative to the edge of the root window, so it includes the space between the
+/// monitor and that edge.
+pub fn strut_partial(
+    edge: StrutEdge,
+    thickness: u32,
+    monitor: &AaRect,
+    (root_width, root_height): (u32, u32),
+) -> [u32; 12] {
+    let mut strut = [0; 12];
+    if thickness == 0 {
+        return strut;
+    }
+
+    let thickness = thickness as i64;
+    let clamp = |value: i64| value.clamp(0, u32::MAX as i64) as u32;
+    let (start_x, end_x) = (clamp(monitor.x), clamp(monitor.x + monitor.width - 1));
+    let (start_y, end_y) = (clamp(monitor.y), clamp(monitor.y + monitor.height - 1));
+    match edge {
+        StrutEdge::Left => {
+            strut[0] = clamp(monitor.x + thickness);
+            (strut[4], strut[5]) = (start_y, end_y);
+        },
+        StrutEdge::Right => {
+            strut[1] = clamp(root_width as i64 - (monitor.x + monitor.width) + thickness);
+            (strut[6], strut[7]) = (start_y, end_y);
+        },
+        StrutEdge::Top => {
+            strut[2] = clamp(monitor.y + thickness);
+            (strut[8], strut[9]) = (start_x, end_x);
+        },
+        StrutEdge::Bottom => {
+            strut[3] = clamp(root_height as i64 - (monitor.y + monitor.height) + thickness);
+            (strut[10], strut[11]) = (start_x, end_x);
+        },
+    }
+
+    strut
+}
+
 #[derive(Debug, Clone)]
 pub struct FrameExtents {
     pub left: u32,
@@ -281,3 +465,65 @@ impl XConnection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use winit_core::window::WindowId;
+
+    use super::*;
+
+    fn place(popup: &PopupAttributes, anchor: (i32, i32)) -> ((i32, i32), (u32, u32)) {
+        let anchor_rect = AaRect::new(anchor, (20, 10));
+        let bounds = AaRect::new((0, 0), (100, 100));
+        place_popup(popup, &anchor_rect, (30, 40), &bounds)
+    }
+
+    fn popup() -> PopupAttributes {
+        let position = dpi::PhysicalPosition::new(0, 0).into();
+        let size = dpi::PhysicalSize::new(0, 0).into();
+        PopupAttributes::new(WindowId::from_raw(1), position, size)
+    }
+
+    #[test]
+    fn popup_below_anchor() {
+        assert_eq!(place(&popup(), (10, 10)), ((10, 20), (30, 40)));
+    }
+
+    #[test]
+    fn popup_flips_above_anchor() {
+        assert_eq!(place(&popup(), (10, 80)), ((10, 40), (30, 40)));
+    }
+
+    #[test]
+    fn popup_slides_into_bounds() {
+        assert_eq!(place(&popup(), (90, 10)), ((70, 20), (30, 40)));
+    }
+
+    #[test]
+    fn popup_resizes_into_bounds() {
+        let popup = popup().with_constraint_adjustment(PopupConstraintAdjustment::RESIZE_Y);
+        assert_eq!(place(&popup, (10, 80)), ((10, 90), (30, 10)));
+    }
+
+    #[test]
+    fn strut_on_second_monitor() {
+        let monitor = AaRect::new((1920, 0), (1280, 1024));
+        let root = (3200, 1080);
+        assert_eq!(strut_partial(StrutEdge::Bottom, 30, &monitor, root), [
+            0, 0, 0, 86, 0, 0, 0, 0, 0, 0, 1920, 3199
+        ]);
+        assert_eq!(strut_partial(StrutEdge::Left, 30, &monitor, root), [
+            1950, 0, 0, 0, 0, 1023, 0, 0, 0, 0, 0, 0
+        ]);
+        assert_eq!(strut_partial(StrutEdge::Top, 0, &monitor, root), [0; 12]);
+    }
+
+    #[test]
+    fn popup_without_adjustment_overflows() {
+        let popup = popup()
+            .with_anchor(PopupAnchor::Right)
+            .with_gravity(PopupGravity::Right)
+            .with_constraint_adjustment(PopupConstraintAdjustment::empty());
+        assert_eq!(place(&popup, (90, 10)), ((110, -5), (30, 40)));
+    }
+}
@@ -89,6 +89,22 @@ impl MotifHints {
         }
     }
 
+    pub fn set_minimizable(&mut self, minimizable: bool) {
+        if minimizable {
+            self.add_func(mwm::MWM_FUNC_MINIMIZE);
+        } else {
+            self.remove_func(mwm::MWM_FUNC_MINIMIZE);
+        }
+    }
+
+    pub fn set_closable(&mut self, closable: bool) {
+        if closable {
+            self.add_func(mwm::MWM_FUNC_CLOSE);
+        } else {
+            self.remove_func(mwm::MWM_FUNC_CLOSE);
+        }
+    }
+
     fn add_func(&mut self, func: u32) {
         if self.hints.flags & mwm::MWM_HINTS_FUNCTIONS != 0 {
             if self.hints.functions & mwm::MWM_FUNC_ALL != 0 {
@@ -5,13 +5,17 @@ use std::num::NonZeroU32;
 use std::ops::Deref;
 use std::os::raw::*;
 use std::path::Path;
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::time::{Duration, Instant};
 use std::{cmp, env};
 
 use dpi::{PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
 use tracing::{debug, info, warn};
 use winit_core::application::ApplicationHandler;
+use winit_core::clipboard::ClipboardData;
 use winit_core::cursor::Cursor;
+use winit_core::drag::{DragAction, DragActions};
 use winit_core::error::{NotSupportedError, RequestError};
 use winit_core::event::{SurfaceSizeWriter, WindowEvent};
 use winit_core::event_loop::AsyncRequestSerial;
@@ -20,18 +24,22 @@ use winit_core::monitor::{
     Fullscreen, MonitorHandle as CoreMonitorHandle, MonitorHandleProvider, VideoMode,
 };
 use winit_core::window::{
-    CursorGrabMode, ImeCapabilities, ImeRequest as CoreImeRequest, ImeRequestError,
-    ResizeDirection, Theme, UserAttentionType, Window as CoreWindow, WindowAttributes,
-    WindowButtons, WindowId, WindowLevel,
+    ContentType, CursorGrabMode, ImeCapabilities, ImeRequest as CoreImeRequest, ImeRequestError,
+    PopupAttributes, ResizeDirection, Theme, UserAttentionType, Window as CoreWindow,
+    WindowAttributes, WindowButtons, WindowId, WindowLevel,
 };
 use x11rb::connection::{Connection, RequestConnection};
 use x11rb::properties::{WmHints, WmSizeHints, WmSizeHintsSpecification};
+use x11rb::protocol::present::{self, ConnectionExt as _};
+use x11rb::protocol::screensaver::{self, ConnectionExt as _};
 use x11rb::protocol::shape::{ConnectionExt as ShapeExt, SK, SO};
 use x11rb::protocol::sync::{ConnectionExt as _, Int64};
+use x11rb::protocol::xkb::{self, ConnectionExt as _};
 use x11rb::protocol::xproto::{self, ClipOrdering, ConnectionExt as _, Rectangle};
 use x11rb::protocol::{randr, xinput};
 
 use crate::atoms::*;
+use crate::dnd::DragRequest;
 use crate::event_loop::{
     ALL_MASTER_DEVICES, ActivationItem, ActiveEventLoop, CookieResultExt, ICONIC_STATE, VoidCookie,
     WakeSender, X11Error, xinput_fp1616_to_float,
@@ -39,8 +47,8 @@ use crate::event_loop::{
 use crate::ime::{ImeRequest, ImeSender};
 use crate::monitor::MonitorHandle as X11MonitorHandle;
 use crate::util::{self, CustomCursor, SelectedCursor, rgba_to_cardinals};
-use crate::xdisplay::XConnection;
-use crate::{WindowAttributesX11, WindowType, ffi};
+use crate::xdisplay::{GrabOwner, XConnection};
+use crate::{Strut, WindowAttributesX11, WindowType, dbus, ffi};
 
 #[derive(Debug)]
 pub struct Window(Arc<UnownedWindow>);
@@ -190,6 +198,14 @@ impl CoreWindow for Window {
         self.0.fullscreen()
     }
 
+    fn set_content_type(&self, content_type: ContentType) -> Result<(), RequestError> {
+        self.0.set_content_type(content_type)
+    }
+
+    fn set_allow_tearing(&self, allow_tearing: bool) -> Result<(), RequestError> {
+        self.0.set_allow_tearing(allow_tearing)
+    }
+
     fn set_decorations(&self, decorations: bool) {
         self.0.set_decorations(decorations);
     }
@@ -202,6 +218,11 @@ impl CoreWindow for Window {
         self.0.set_window_level(level);
     }
 
+    fn set_owner_window(&self, owner: Option<WindowId>, modal: bool) -> Result<(), RequestError> {
+        let owner = owner.map(|owner| owner.into_raw() as xproto::Window);
+        self.0.set_owner_window(owner, modal)
+    }
+
     fn set_window_icon(&self, window_icon: Option<winit_core::icon::Icon>) {
         let icon = match window_icon.as_ref() {
             Some(icon) => icon.cast_ref::<RgbaIcon>(),
@@ -218,6 +239,10 @@ impl CoreWindow for Window {
         self.0.ime_capabilities()
     }
 
+    fn set_keyboard_layout(&self, index: usize) -> Result<(), RequestError> {
+        self.0.set_keyboard_layout(index)
+    }
+
     fn focus_window(&self) {
         self.0.focus_window();
     }
@@ -242,6 +267,10 @@ impl CoreWindow for Window {
         self.0.set_content_protected(protected);
     }
 
+    fn set_idle_inhibited(&self, inhibited: bool) -> Result<(), RequestError> {
+        self.0.set_idle_inhibited(inhibited)
+    }
+
     fn title(&self) -> String {
         self.0.title()
     }
@@ -258,6 +287,10 @@ impl CoreWindow for Window {
         self.0.set_cursor_grab(mode)
     }
 
+    fn set_keyboard_grab(&self, grab: bool) -> Result<(), RequestError> {
+        self.0.set_keyboard_grab(grab)
+    }
+
     fn set_cursor_visible(&self, visible: bool) {
         self.0.set_cursor_visible(visible);
     }
@@ -266,6 +299,28 @@ impl CoreWindow for Window {
         self.0.drag_window()
     }
 
+    fn start_drag(
+        &self,
+        contents: Vec<ClipboardData>,
+        actions: DragActions,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        self.0.start_drag(contents, actions)
+    }
+
+    fn accept_drag(&self, mime_type: &str, action: DragAction) -> Result<(), RequestError> {
+        self.0.set_drag_accepted(Some((mime_type.to_owned(), action)));
+        Ok(())
+    }
+
+    fn reject_drag(&self) -> Result<(), RequestError> {
+        self.0.set_drag_accepted(None);
+        Ok(())
+    }
+
+    fn request_drag_data(&self, mime_type: &str) -> Result<AsyncRequestSerial, RequestError> {
+        Ok(self.0.request_drag_data(mime_type))
+    }
+
     fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), RequestError> {
         self.0.drag_resize_window(direction)
     }
@@ -328,6 +383,12 @@ impl Drop for Window {
             window.set_fullscreen(None);
         }
 
+        // The server keeps the screensaver suspended for as long as we are connected.
+        let _ = window.set_idle_inhibited(false);
+
+        // Forget about the grab, the server releases it with the window.
+        window.ungrab_popup_input();
+
         if let Ok(c) =
             xconn.xcb_connection().destroy_window(window.id().into_raw() as xproto::Window)
         {
@@ -339,12 +400,16 @@ impl Drop for Window {
 #[derive(Debug)]
 pub struct SharedState {
     pub cursor_pos: Option<(f64, f64)>,
+    /// The point the cursor is held at while grabbed with [`CursorGrabMode::Locked`].
+    pub cursor_lock_pos: Option<(i32, i32)>,
     pub size: Option<(u32, u32)>,
     pub position: Option<(i32, i32)>,
     pub inner_position: Option<(i32, i32)>,
     pub inner_position_rel_parent: Option<(i32, i32)>,
     pub is_resizable: bool,
     pub is_decorated: bool,
+    pub is_transparent: bool,
+    pub enabled_buttons: WindowButtons,
     pub ime_capabilities: Option<ImeCapabilities>,
     pub last_monitor: X11MonitorHandle,
     pub dpi_adjusted: Option<(u32, u32)>,
@@ -362,8 +427,27 @@ pub struct SharedState {
     pub base_size: Option<Size>,
     pub visibility: Visibility,
     pub has_focus: bool,
+    pub content_type: ContentType,
+    pub allow_tearing: bool,
+    /// Whether the keyboard should be grabbed while the window is focused.
+    pub keyboard_grab_requested: bool,
+    pub keyboard_grabbed: bool,
     // Use `Option` to not apply hittest logic when it was never requested.
     pub cursor_hittest: Option<bool>,
+    pub(crate) strut: Option<Strut>,
+    pub(crate) sync_request: Option<SyncRequest>,
+}
+
+/// How long a `_NET_WM_SYNC_REQUEST` waits for its `ConfigureNotify`.
+const SYNC_REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The progress of a `_NET_WM_SYNC_REQUEST` of the window manager.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum SyncRequest {
+    /// Waiting for the `ConfigureNotify` the request was sent for, until the deadline.
+    Requested(Int64, Instant),
+    /// Waiting for the redraw following the configure.
+    Configured(Int64),
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -385,7 +469,10 @@ impl SharedState {
 
             is_resizable: window_attributes.resizable,
             is_decorated: window_attributes.decorations,
+            is_transparent: window_attributes.transparent,
+            enabled_buttons: window_attributes.enabled_buttons,
             cursor_pos: None,
+            cursor_lock_pos: None,
             size: None,
             position: None,
             inner_position: None,
@@ -402,7 +489,13 @@ impl SharedState {
             surface_resize_increments: None,
             base_size: None,
             has_focus: false,
+            content_type: window_attributes.content_type,
+            allow_tearing: window_attributes.allow_tearing,
+            keyboard_grab_requested: false,
+            keyboard_grabbed: false,
             cursor_hittest: None,
+            strut: None,
+            sync_request: None,
         })
     }
 }
@@ -416,6 +509,7 @@ pub struct UnownedWindow {
     xwindow: xproto::Window,            // never changes
     #[allow(dead_code)]
     visual: u32, // never changes
+    has_alpha: bool,                    // never changes
     root: xproto::Window,               // never changes
     #[allow(dead_code)]
     screen_id: i32, // never changes
@@ -428,6 +522,25 @@ pub struct UnownedWindow {
     pub shared_state: Mutex<SharedState>,
     redraw_sender: WakeSender<WindowId>,
     activation_sender: WakeSender<ActivationItem>,
+    drag_sender: WakeSender<DragRequest>,
+    keyboard_grab_sender: WakeSender<(WindowId, bool)>,
+    popup: Option<PopupAttributes>, // never changes
+    /// Whether the popup holds the pointer and keyboard grab.
+    popup_grabbed: AtomicBool,
+    /// Whether Present events are selected for the window.
+    present_selected: AtomicBool,
+    /// Whether the presentation of the next frame should be reported.
+    pub(crate) presentation_requested: AtomicBool,
+    idle_inhibitor: Mutex<Option<IdleInhibitor>>,
+}
+
+/// How the screensaver is inhibited for a window.
+#[derive(Debug)]
+enum IdleInhibitor {
+    /// Through `XScreenSaverSuspend`, which the server counts per client.
+    Suspended,
+    /// Through `org.freedesktop.ScreenSaver`, lifted when dropped.
+    ScreenSaver(#[allow(dead_code)] dbus::ScreenSaverInhibitor),
 }
 macro_rules! leap {
     ($e:expr) => {
@@ -444,12 +557,45 @@ impl UnownedWindow {
         let xconn = &event_loop.xconn;
         let atoms = xconn.atoms();
 
-        let x11_attributes = window_attrs
+        let mut x11_attributes = window_attrs
             .platform
             .take()
             .and_then(|attrs| attrs.cast::<WindowAttributesX11>().ok())
             .unwrap_or_default();
 
+        // Popups are placed by us relative to their parent, without the window manager.
+        let popup_parent = match window_attrs.popup.as_ref() {
+            Some(popup) => {
+                let parent = event_loop.windows.borrow().get(&popup.parent).and_then(Weak::upgrade);
+                let parent = parent.ok_or_else(|| {
+                    NotSupportedError::new("the parent of the popup is not an X11 window")
+                })?;
+                x11_attributes.override_redirect = true;
+                if x11_attributes.x11_window_types == [WindowType::Normal] {
+                    let window_type =
+                        if popup.grab { WindowType::DropdownMenu } else { WindowType::Tooltip };
+                    x11_attributes.x11_window_types = vec![window_type];
+                }
+                Some(parent)
+            },
+            None => None,
+        };
+
+        // Owned windows are dialogs, unless other types were requested.
+        let owner = match window_attrs.owner_window {
+            Some(owner) => {
+                let owner = event_loop.windows.borrow().get(&owner).and_then(Weak::upgrade);
+                let owner = owner.ok_or_else(|| {
+                    NotSupportedError::new("the owner window is not an X11 window")
+                })?;
+                if x11_attributes.x11_window_types == [WindowType::Normal] {
+                    x11_attributes.x11_window_types = vec![WindowType::Dialog];
+                }
+                Some(owner.xwindow)
+            },
+            None => None,
+        };
+
         let screen_id = match x11_attributes.screen_id {
             Some(id) => id,
             None => xconn.default_screen_index() as c_int,
@@ -490,7 +636,10 @@ impl UnownedWindow {
                 })
                 .unwrap_or_else(|| monitors.swap_remove(0))
         };
-        let scale_factor = guessed_monitor.scale_factor();
+        let scale_factor = match popup_parent.as_ref() {
+            Some(parent) => parent.scale_factor(),
+            None => guessed_monitor.scale_factor(),
+        };
 
         info!("Guessed window scale factor: {}", scale_factor);
 
@@ -499,10 +648,10 @@ impl UnownedWindow {
         let min_surface_size: Option<(u32, u32)> =
             window_attrs.min_surface_size.map(|size| size.to_physical::<u32>(scale_factor).into());
 
-        let position =
+        let mut position =
             window_attrs.position.map(|position| position.to_physical::<i32>(scale_factor));
 
-        let dimensions = {
+        let mut dimensions = {
             // x11 only applies constraints when the window is actively resized
             // by the user, so we have to manually apply the initial constraints
             let mut dimensions: (u32, u32) = window_attrs
@@ -523,6 +672,32 @@ impl UnownedWindow {
             dimensions
         };
 
+        if let (Some(popup), Some(parent)) = (window_attrs.popup.as_ref(), popup_parent.as_ref()) {
+            let (parent_x, parent_y) = parent.inner_position_physical();
+            let anchor_position = popup.anchor_position.to_physical::<i32>(scale_factor);
+            let anchor_rect = util::AaRect::new(
+                (parent_x + anchor_position.x, parent_y + anchor_position.y),
+                popup.anchor_size.to_physical::<u32>(scale_factor).into(),
+            );
+
+            // Keep the popup on the monitor the anchor is on.
+            let bounds = leap!(xconn.available_monitors())
+                .into_iter()
+                .map(|monitor| monitor.rect)
+                .max_by_key(|rect| rect.get_overlapping_area(&anchor_rect))
+                .unwrap_or_else(|| {
+                    util::AaRect::new(
+                        (0, 0),
+                        (screen.width_in_pixels.into(), screen.height_in_pixels.into()),
+                    )
+                });
+
+            let (popup_position, popup_dimensions) =
+                util::place_popup(popup, &anchor_rect, dimensions, &bounds);
+            position = Some(popup_position.into());
+            dimensions = popup_dimensions;
+        }
+
         // An iterator over the visuals matching screen id combined with their depths.
         let mut all_visuals = screen
             .allowed_depths
@@ -643,6 +818,7 @@ impl UnownedWindow {
             xconn: Arc::clone(xconn),
             xwindow: xwindow as xproto::Window,
             visual,
+            has_alpha: depth == 32,
             root,
             screen_id,
             sync_counter_id: None,
@@ -653,6 +829,13 @@ impl UnownedWindow {
             shared_state: SharedState::new(guessed_monitor, &window_attrs),
             redraw_sender: event_loop.redraw_sender.clone(),
             activation_sender: event_loop.activation_sender.clone(),
+            drag_sender: event_loop.drag_sender.clone(),
+            keyboard_grab_sender: event_loop.keyboard_grab_sender.clone(),
+            popup: window_attrs.popup,
+            popup_grabbed: AtomicBool::new(false),
+            present_selected: AtomicBool::new(false),
+            presentation_requested: AtomicBool::new(false),
+            idle_inhibitor: Mutex::new(None),
         };
 
         // Title must be set before mapping. Some tiling window managers (i.e. i3) use the window
@@ -661,6 +844,22 @@ impl UnownedWindow {
         leap!(window.set_title_inner(&window_attrs.title)).ignore_error();
         leap!(window.set_decorations_inner(window_attrs.decorations)).ignore_error();
 
+        if window_attrs.enabled_buttons != WindowButtons::all() || !window_attrs.resizable {
+            leap!(window.set_enabled_buttons_inner()).ignore_error();
+        }
+
+        if window_attrs.blur {
+            leap!(window.set_blur_inner(true)).ignore_error();
+        }
+
+        if window_attrs.allow_tearing || window_attrs.content_type != ContentType::None {
+            leap!(window.update_bypass_compositor()).ignore_error();
+        }
+
+        if window.has_alpha && !window_attrs.transparent {
+            leap!(window.update_opaque_region(dimensions));
+        }
+
         if let Some(theme) = window_attrs.preferred_theme {
             leap!(window.set_theme_inner(Some(theme))).ignore_error();
         }
@@ -670,6 +869,29 @@ impl UnownedWindow {
             window.embed_window()?;
         }
 
+        if let Some(transient_for) = popup_parent.as_ref().map(|parent| parent.xwindow).or(owner) {
+            leap!(xconn.change_property(
+                window.xwindow,
+                u32::from(xproto::AtomEnum::WM_TRANSIENT_FOR),
+                u32::from(xproto::AtomEnum::WINDOW),
+                xproto::PropMode::REPLACE,
+                &[transient_for],
+            ))
+            .ignore_error();
+        }
+
+        // The window isn't mapped yet, so its state is set directly.
+        if owner.is_some() && window_attrs.modal {
+            leap!(xconn.change_property(
+                window.xwindow,
+                atoms[_NET_WM_STATE],
+                u32::from(xproto::AtomEnum::ATOM),
+                xproto::PropMode::APPEND,
+                &[atoms[_NET_WM_STATE_MODAL]],
+            ))
+            .ignore_error();
+        }
+
         {
             // Enable drag and drop (TODO: extend API to make this toggleable)
             {
@@ -720,6 +942,11 @@ impl UnownedWindow {
 
             leap!(window.set_window_types(x11_attributes.x11_window_types)).ignore_error();
 
+            if let Some(strut) = x11_attributes.strut {
+                window.shared_state_lock().strut = Some(strut);
+                leap!(window.update_strut());
+            }
+
             // Set size hints.
             let mut min_surface_size =
                 window_attrs.min_surface_size.map(|size| size.to_physical::<u32>(scale_factor));
@@ -826,6 +1053,14 @@ impl UnownedWindow {
                 | xinput::XIEventMask::TOUCH_BEGIN
                 | xinput::XIEventMask::TOUCH_UPDATE
                 | xinput::XIEventMask::TOUCH_END;
+            let mask = if event_loop.xinput_gestures {
+                // x11rb doesn't know the masks of the XInput 2.4 events.
+                let gestures = (ffi::XI_GesturePinchBegin..=ffi::XI_GestureSwipeEnd)
+                    .fold(0u32, |mask, event| mask | (1 << event));
+                mask | xinput::XIEventMask::from(gestures)
+            } else {
+                mask
+            };
             leap!(xconn.select_xinput_events(window.xwindow, ALL_MASTER_DEVICES, mask))
                 .ignore_error();
 
@@ -891,9 +1126,84 @@ impl UnownedWindow {
         // We never want to give the user a broken window, since by then, it's too late to handle.
         let window = leap!(xconn.sync_with_server().map(|_| window));
 
+        // Override-redirect windows are mapped right away, so the grab can be taken now.
+        if window.popup.is_some_and(|popup| popup.grab) && window_attrs.visible {
+            window.grab_popup_input();
+        }
+
         Ok(window)
     }
 
+    /// Grab the pointer and the keyboard for the popup.
+    fn grab_popup_input(&self) {
+        let owner = GrabOwner::Popup(self.xwindow);
+        let conn = self.xconn.xcb_connection();
+        // Clicks on our other windows are delivered to them, the others to the popup.
+        let pointer = conn
+            .grab_pointer(
+                true,
+                self.xwindow,
+                xproto::EventMask::BUTTON_PRESS | xproto::EventMask::BUTTON_RELEASE,
+                xproto::GrabMode::ASYNC,
+                xproto::GrabMode::ASYNC,
+                x11rb::NONE,
+                x11rb::NONE,
+                x11rb::CURRENT_TIME,
+            )
+            .map_err(X11Error::from)
+            .and_then(|c| Ok(c.reply()?.status));
+        if !matches!(pointer, Ok(xproto::GrabStatus::SUCCESS)) {
+            warn!("Failed to grab the pointer for the popup: {pointer:?}");
+            return;
+        }
+        *self.xconn.pointer_grab.lock().unwrap() = Some(owner);
+
+        let keyboard = conn
+            .grab_keyboard(
+                false,
+                self.xwindow,
+                x11rb::CURRENT_TIME,
+                xproto::GrabMode::ASYNC,
+                xproto::GrabMode::ASYNC,
+            )
+            .map_err(X11Error::from)
+            .and_then(|c| Ok(c.reply()?.status));
+        if !matches!(keyboard, Ok(xproto::GrabStatus::SUCCESS)) {
+            warn!("Failed to grab the keyboard for the popup: {keyboard:?}");
+            // Don't keep half of the grab.
+            if let Err(err) = self.xconn.ungrab_pointer_for(owner, x11rb::CURRENT_TIME) {
+                warn!("Failed to ungrab the pointer: {err}");
+            }
+            return;
+        }
+        *self.xconn.keyboard_grab.lock().unwrap() = Some(owner);
+
+        self.popup_grabbed.store(true, Ordering::Relaxed);
+    }
+
+    /// Release the grab of the popup, returning whether it had one.
+    ///
+    /// The grabs taken since, like the pointer grab of a drag, are kept.
+    pub(crate) fn ungrab_popup_input(&self) -> bool {
+        if !self.popup_grabbed.swap(false, Ordering::Relaxed) {
+            return false;
+        }
+
+        let owner = GrabOwner::Popup(self.xwindow);
+        if let Err(err) = self.xconn.ungrab_pointer_for(owner, x11rb::CURRENT_TIME) {
+            warn!("Failed to ungrab the pointer: {err}");
+        }
+        if let Err(err) = self.xconn.ungrab_keyboard_for(owner, x11rb::CURRENT_TIME) {
+            warn!("Failed to ungrab the keyboard: {err}");
+        }
+        true
+    }
+
+    /// The parent of the popup.
+    pub(crate) fn popup_parent(&self) -> Option<WindowId> {
+        self.popup.map(|popup| popup.parent)
+    }
+
     /// Embed this window into a parent window.
     pub(super) fn embed_window(&self) -> Result<(), RequestError> {
         let atoms = self.xconn.atoms();
@@ -957,6 +1267,64 @@ impl UnownedWindow {
         )
     }
 
+    pub(crate) fn set_strut(&self, strut: Option<Strut>) -> Result<(), RequestError> {
+        self.shared_state_lock().strut = strut;
+        self.update_strut().map_err(|err| os_error!(err))?;
+        self.xconn.flush_requests().map_err(|err| os_error!(err))?;
+        Ok(())
+    }
+
+    /// Compute the strut again, for the current position of the window and monitors.
+    pub(crate) fn update_strut(&self) -> Result<(), X11Error> {
+        let atoms = self.xconn.atoms();
+        let conn = self.xconn.xcb_connection();
+
+        let strut = self.shared_state_lock().strut.clone();
+        let monitor = match strut.as_ref().map(|strut| strut.monitor.as_ref()) {
+            None => None,
+            Some(Some(monitor)) => monitor.cast_ref::<X11MonitorHandle>().cloned(),
+            Some(None) => Some(self.shared_state_lock().last_monitor.clone()),
+        };
+        // Look the monitor up again, in case it was reconfigured or disconnected.
+        let monitor = match monitor {
+            Some(monitor) => self
+                .xconn
+                .available_monitors()?
+                .into_iter()
+                .find(|new| new.output == monitor.output),
+            None => None,
+        };
+
+        let (Some(strut), Some(monitor)) = (strut, monitor) else {
+            conn.delete_property(self.xwindow, atoms[_NET_WM_STRUT_PARTIAL])?.ignore_error();
+            conn.delete_property(self.xwindow, atoms[_NET_WM_STRUT])?.ignore_error();
+            return Ok(());
+        };
+
+        let root = conn.get_geometry(self.root)?.reply()?;
+        let root_size = (root.width.into(), root.height.into());
+        let partial = util::strut_partial(strut.edge, strut.thickness, &monitor.rect, root_size);
+        self.xconn
+            .change_property(
+                self.xwindow,
+                atoms[_NET_WM_STRUT_PARTIAL],
+                xproto::Atom::from(xproto::AtomEnum::CARDINAL),
+                xproto::PropMode::REPLACE,
+                &partial,
+            )?
+            .ignore_error();
+        self.xconn
+            .change_property(
+                self.xwindow,
+                atoms[_NET_WM_STRUT],
+                xproto::Atom::from(xproto::AtomEnum::CARDINAL),
+                xproto::PropMode::REPLACE,
+                &partial[..4],
+            )?
+            .ignore_error();
+        Ok(())
+    }
+
     pub fn set_theme_inner(&self, theme: Option<Theme>) -> Result<VoidCookie<'_>, X11Error> {
         let atoms = self.xconn.atoms();
         let hint_atom = atoms[_GTK_THEME_VARIANT];
@@ -1157,6 +1525,68 @@ impl UnownedWindow {
         }
     }
 
+    /// Ask the compositor to unredirect the window for latency sensitive content, which it
+    /// usually only does in fullscreen.
+    fn update_bypass_compositor(&self) -> Result<VoidCookie<'_>, X11Error> {
+        let shared_state = self.shared_state_lock();
+        let bypass = shared_state.allow_tearing
+            || matches!(shared_state.content_type, ContentType::Video | ContentType::Game);
+        drop(shared_state);
+
+        let atoms = self.xconn.atoms();
+        let bypass_atom = atoms[_NET_WM_BYPASS_COMPOSITOR];
+        if bypass {
+            self.xconn.change_property(
+                self.xwindow,
+                bypass_atom,
+                xproto::Atom::from(xproto::AtomEnum::CARDINAL),
+                xproto::PropMode::REPLACE,
+                &[1u32],
+            )
+        } else {
+            // No preference.
+            Ok(self.xconn.xcb_connection().delete_property(self.xwindow, bypass_atom)?)
+        }
+    }
+
+    pub fn set_owner_window(
+        &self,
+        owner: Option<xproto::Window>,
+        modal: bool,
+    ) -> Result<(), RequestError> {
+        let transient_for = u32::from(xproto::AtomEnum::WM_TRANSIENT_FOR);
+        match owner {
+            Some(owner) => leap!(self.xconn.change_property(
+                self.xwindow,
+                transient_for,
+                u32::from(xproto::AtomEnum::WINDOW),
+                xproto::PropMode::REPLACE,
+                &[owner],
+            ))
+            .ignore_error(),
+            None => leap!(self.xconn.xcb_connection().delete_property(self.xwindow, transient_for))
+                .ignore_error(),
+        }
+
+        leap!(self.toggle_atom(_NET_WM_STATE_MODAL, owner.is_some() && modal)).ignore_error();
+        leap!(self.xconn.flush_requests());
+        Ok(())
+    }
+
+    pub fn set_content_type(&self, content_type: ContentType) -> Result<(), RequestError> {
+        self.shared_state_lock().content_type = content_type;
+        leap!(self.update_bypass_compositor()).ignore_error();
+        leap!(self.xconn.flush_requests());
+        Ok(())
+    }
+
+    pub fn set_allow_tearing(&self, allow_tearing: bool) -> Result<(), RequestError> {
+        self.shared_state_lock().allow_tearing = allow_tearing;
+        leap!(self.update_bypass_compositor()).ignore_error();
+        leap!(self.xconn.flush_requests());
+        Ok(())
+    }
+
     // Called by EventProcessor when a VisibilityNotify event is received
     pub(crate) fn visibility_notify(&self) {
         let mut shared_state = self.shared_state_lock();
@@ -1356,11 +1786,71 @@ impl UnownedWindow {
         self.xconn.flush_requests().expect("Failed to set window title");
     }
 
+    /// Mark the whole window as opaque to the compositor, unless it's transparent.
+    ///
+    /// Only windows with an ARGB visual can be transparent, the others are always opaque.
+    pub(crate) fn update_opaque_region(&self, size: (u32, u32)) -> Result<(), X11Error> {
+        if !self.has_alpha {
+            return Ok(());
+        }
+
+        let atoms = self.xconn.atoms();
+        let opaque_region_atom = atoms[_NET_WM_OPAQUE_REGION];
+        if self.shared_state_lock().is_transparent {
+            self.xconn
+                .xcb_connection()
+                .delete_property(self.xwindow, opaque_region_atom)?
+                .ignore_error();
+        } else {
+            self.xconn
+                .change_property(
+                    self.xwindow,
+                    opaque_region_atom,
+                    xproto::Atom::from(xproto::AtomEnum::CARDINAL),
+                    xproto::PropMode::REPLACE,
+                    &[0, 0, size.0, size.1],
+                )?
+                .ignore_error();
+        }
+
+        Ok(())
+    }
+
     #[inline]
-    pub fn set_transparent(&self, _transparent: bool) {}
+    pub fn set_transparent(&self, transparent: bool) {
+        if !self.has_alpha {
+            debug!("Could not change transparency, because the window visual has no alpha channel");
+            return;
+        }
+
+        self.shared_state_lock().is_transparent = transparent;
+        self.update_opaque_region(self.surface_size_physical())
+            .expect("Failed to set the opaque region");
+        self.xconn.flush_requests().expect("Failed to set the opaque region");
+    }
+
+    fn set_blur_inner(&self, blur: bool) -> Result<VoidCookie<'_>, X11Error> {
+        let atoms = self.xconn.atoms();
+        let blur_atom = atoms[_KDE_NET_WM_BLUR_BEHIND_REGION];
+        if blur {
+            // An empty region blurs behind the whole window.
+            self.xconn.change_property(
+                self.xwindow,
+                blur_atom,
+                xproto::Atom::from(xproto::AtomEnum::CARDINAL),
+                xproto::PropMode::REPLACE,
+                &[] as &[u32],
+            )
+        } else {
+            Ok(self.xconn.xcb_connection().delete_property(self.xwindow, blur_atom)?)
+        }
+    }
 
     #[inline]
-    pub fn set_blur(&self, _blur: bool) {}
+    pub fn set_blur(&self, blur: bool) {
+        self.set_blur_inner(blur).expect_then_ignore_error("Failed to set blur state");
+        self.xconn.flush_requests().expect("Failed to set blur state");
+    }
 
     fn set_decorations_inner(&self, decorations: bool) -> Result<VoidCookie<'_>, X11Error> {
         self.shared_state_lock().is_decorated = decorations;
@@ -1384,9 +1874,53 @@ impl UnownedWindow {
         self.shared_state_lock().is_decorated
     }
 
-    fn set_maximizable_inner(&self, maximizable: bool) -> Result<VoidCookie<'_>, X11Error> {
+    fn set_enabled_buttons_inner(&self) -> Result<VoidCookie<'_>, X11Error> {
+        let (buttons, resizable) = {
+            let shared_state_lock = self.shared_state_lock();
+            (shared_state_lock.enabled_buttons, shared_state_lock.is_resizable)
+        };
+        let closable = buttons.contains(WindowButtons::CLOSE);
+        let minimizable = buttons.contains(WindowButtons::MINIMIZE);
+        let maximizable = resizable && buttons.contains(WindowButtons::MAXIMIZE);
+
+        // The window manager maintains `_NET_WM_ALLOWED_ACTIONS` once it manages the window, so
+        // only update the actions of the buttons in what it published.
+        let atoms = self.xconn.atoms();
+        let allowed_actions_atom = atoms[_NET_WM_ALLOWED_ACTIONS];
+        let atom_type = xproto::Atom::from(xproto::AtomEnum::ATOM);
+        let mut allowed_actions = self
+            .xconn
+            .get_property::<xproto::Atom>(self.xwindow, allowed_actions_atom, atom_type)
+            .unwrap_or_default();
+        if !allowed_actions.is_empty() {
+            for (action, enabled) in [
+                (_NET_WM_ACTION_CLOSE, closable),
+                (_NET_WM_ACTION_MINIMIZE, minimizable),
+                (_NET_WM_ACTION_MAXIMIZE_HORZ, maximizable),
+                (_NET_WM_ACTION_MAXIMIZE_VERT, maximizable),
+            ] {
+                let action = atoms[action];
+                allowed_actions.retain(|&allowed| allowed != action);
+                if enabled {
+                    allowed_actions.push(action);
+                }
+            }
+
+            self.xconn
+                .change_property(
+                    self.xwindow,
+                    allowed_actions_atom,
+                    atom_type,
+                    xproto::PropMode::REPLACE,
+                    &allowed_actions,
+                )?
+                .ignore_error();
+        }
+
         let mut hints = self.xconn.get_motif_hints(self.xwindow);
 
+        hints.set_closable(closable);
+        hints.set_minimizable(minimizable);
         hints.set_maximizable(maximizable);
 
         self.xconn.set_motif_hints(self.xwindow, &hints)
@@ -1472,7 +2006,16 @@ impl UnownedWindow {
                 .expect_then_ignore_error("Failed to call `xcb_configure_window`");
             self.xconn.flush_requests().expect("Failed to call XMapRaised");
             shared_state.visibility = Visibility::YesWait;
+            drop(shared_state);
+
+            // Popups are override-redirect, so they're viewable once the server mapped them.
+            if self.popup.is_some_and(|popup| popup.grab) {
+                self.grab_popup_input();
+            }
         } else {
+            // The server releases the grab of an unmapped window.
+            self.ungrab_popup_input();
+
             self.xconn
                 .xcb_connection()
                 .unmap_window(self.xwindow)
@@ -1760,8 +2303,7 @@ impl UnownedWindow {
         };
         self.shared_state_lock().is_resizable = resizable;
 
-        self.set_maximizable_inner(resizable)
-            .expect_then_ignore_error("Failed to call `XSetWMNormalHints`");
+        self.set_enabled_buttons_inner().expect_then_ignore_error("Failed to set enabled buttons");
 
         let scale_factor = self.scale_factor();
         let min_surface_size = min_size.map(|size| cast_size_to_hint(size, scale_factor));
@@ -1779,11 +2321,15 @@ impl UnownedWindow {
     }
 
     #[inline]
-    pub fn set_enabled_buttons(&self, _buttons: WindowButtons) {}
+    pub fn set_enabled_buttons(&self, buttons: WindowButtons) {
+        self.shared_state_lock().enabled_buttons = buttons;
+        self.set_enabled_buttons_inner().expect_then_ignore_error("Failed to set enabled buttons");
+        self.xconn.flush_requests().expect("Failed to set enabled buttons");
+    }
 
     #[inline]
     pub fn enabled_buttons(&self) -> WindowButtons {
-        WindowButtons::all()
+        self.shared_state_lock().enabled_buttons
     }
 
     #[allow(dead_code)]
@@ -1838,11 +2384,6 @@ impl UnownedWindow {
 
     #[inline]
     pub fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), RequestError> {
-        // We don't support the locked cursor yet, so ignore it early on.
-        if mode == CursorGrabMode::Locked {
-            return Err(NotSupportedError::new("locked cursor is not implemented on X11").into());
-        }
-
         let mut grabbed_lock = self.cursor_grabbed_mode.lock().unwrap();
         if mode == *grabbed_lock {
             return Ok(());
@@ -1854,14 +2395,18 @@ impl UnownedWindow {
             .xcb_connection()
             .ungrab_pointer(x11rb::CURRENT_TIME)
             .expect_then_ignore_error("Failed to call `xcb_ungrab_pointer`");
+        *self.xconn.pointer_grab.lock().unwrap() = None;
         *grabbed_lock = CursorGrabMode::None;
+        self.shared_state_lock().cursor_lock_pos = None;
 
         let result = match mode {
             CursorGrabMode::None => self
                 .xconn
                 .flush_requests()
                 .map_err(|err| RequestError::Os(os_error!(X11Error::Xlib(err)))),
-            CursorGrabMode::Confined => {
+            // X11 has no pointer lock, so the locked cursor is confined to the window and warped
+            // back to the lock point whenever it moves. Raw motion keeps flowing through XI2.
+            CursorGrabMode::Confined | CursorGrabMode::Locked => {
                 let result = self
                     .xconn
                     .xcb_connection()
@@ -1908,16 +2453,114 @@ impl UnownedWindow {
                 }
                 .map_err(|err| RequestError::Os(os_error!(err)))
             },
-            CursorGrabMode::Locked => return Ok(()),
         };
 
         if result.is_ok() {
+            if mode != CursorGrabMode::None {
+                *self.xconn.pointer_grab.lock().unwrap() = Some(GrabOwner::Cursor(self.xwindow));
+            }
             *grabbed_lock = mode;
+            if mode == CursorGrabMode::Locked {
+                self.lock_cursor();
+            }
         }
 
         result
     }
 
+    /// Hold the cursor at its current position, or at the center of the window when it's
+    /// outside of it.
+    fn lock_cursor(&self) {
+        let (width, height) = self.surface_size_physical();
+        let mut shared_state_lock = self.shared_state_lock();
+        let position = shared_state_lock
+            .cursor_pos
+            .map(|(x, y)| (x as i32, y as i32))
+            .filter(|&(x, y)| x >= 0 && y >= 0 && x < width as i32 && y < height as i32)
+            .unwrap_or((width as i32 / 2, height as i32 / 2));
+        shared_state_lock.cursor_lock_pos = Some(position);
+        drop(shared_state_lock);
+
+        if let Err(err) = self.set_cursor_position_physical(position.0, position.1) {
+            warn!("failed to move the cursor to the lock position: {err}");
+        }
+    }
+
+    /// Warp the cursor back to the lock point if it moved away from it.
+    ///
+    /// Returns `false` when the cursor isn't locked.
+    pub(crate) fn restore_locked_cursor(&self, cursor_pos: (f64, f64)) -> bool {
+        let Some((x, y)) = self.shared_state_lock().cursor_lock_pos else {
+            return false;
+        };
+
+        if cursor_pos != (x as f64, y as f64) {
+            let _ = self.set_cursor_position_physical(x, y);
+        }
+
+        true
+    }
+
+    pub fn set_keyboard_grab(&self, grab: bool) -> Result<(), RequestError> {
+        self.shared_state_lock().keyboard_grab_requested = grab;
+        self.update_keyboard_grab()
+    }
+
+    /// Grab the keyboard while the window is focused if it was requested, and release it
+    /// otherwise.
+    pub(crate) fn update_keyboard_grab(&self) -> Result<(), RequestError> {
+        let mut shared_state_lock = self.shared_state_lock();
+        let grab = shared_state_lock.keyboard_grab_requested && shared_state_lock.has_focus;
+        if grab == shared_state_lock.keyboard_grabbed {
+            return Ok(());
+        }
+
+        if grab {
+            let result = leap!(
+                leap!(self.xconn.xcb_connection().grab_keyboard(
+                    true,
+                    self.xwindow,
+                    x11rb::CURRENT_TIME,
+                    xproto::GrabMode::ASYNC,
+                    xproto::GrabMode::ASYNC,
+                ))
+                .reply()
+            );
+
+            let err = match result.status {
+                xproto::GrabStatus::SUCCESS => None,
+                xproto::GrabStatus::ALREADY_GRABBED => {
+                    Some("Keyboard could not be grabbed: already grabbed by another client")
+                },
+                xproto::GrabStatus::INVALID_TIME => {
+                    Some("Keyboard could not be grabbed: invalid time")
+                },
+                xproto::GrabStatus::NOT_VIEWABLE => {
+                    Some("Keyboard could not be grabbed: window not viewable")
+                },
+                xproto::GrabStatus::FROZEN => {
+                    Some("Keyboard could not be grabbed: frozen by another client")
+                },
+                _ => Some("Keyboard could not be grabbed: unknown grab status"),
+            };
+            if let Some(err) = err {
+                return Err(os_error!(err).into());
+            }
+            *self.xconn.keyboard_grab.lock().unwrap() = Some(GrabOwner::Keyboard(self.xwindow));
+        } else {
+            leap!(
+                self.xconn
+                    .ungrab_keyboard_for(GrabOwner::Keyboard(self.xwindow), x11rb::CURRENT_TIME)
+            );
+            leap!(self.xconn.flush_requests());
+        }
+
+        shared_state_lock.keyboard_grabbed = grab;
+        self.keyboard_grab_sender.send((self.id(), grab));
+
+        Ok(())
+    }
+
     #[inline]
     pub fn set_cursor_visible(&self, visible: bool) {
         #[allow(clippy::mutex_atomic)]
@@ -2044,7 +2687,9 @@ impl UnownedWindow {
             .map_err(|err| os_error!(X11Error::from(err)))?
             .ignore_error();
         self.xconn.flush_requests().map_err(|err| os_error!(X11Error::Xlib(err)))?;
+        *self.xconn.pointer_grab.lock().unwrap() = None;
         *grabbed_lock = CursorGrabMode::None;
+        self.shared_state_lock().cursor_lock_pos = None;
 
         // we keep the lock until we are done
         self.xconn
@@ -2145,6 +2790,34 @@ impl UnownedWindow {
         self.shared_state_lock().ime_capabilities
     }
 
+    pub fn set_keyboard_layout(&self, index: usize) -> Result<(), RequestError> {
+        let conn = self.xconn.xcb_connection();
+        let num_groups = conn
+            .xkb_get_controls(xkb::ID::USE_CORE_KBD.into())
+            .map_err(|err| os_error!(X11Error::from(err)))?
+            .reply()
+            .map_err(|err| os_error!(X11Error::from(err)))?
+            .num_groups;
+        let group = u8::try_from(index)
+            .ok()
+            .filter(|&group| group < num_groups)
+            .ok_or(RequestError::Ignored)?;
+
+        conn.xkb_latch_lock_state(
+            xkb::ID::USE_CORE_KBD.into(),
+            xproto::ModMask::default(),
+            xproto::ModMask::default(),
+            true,
+            xkb::Group::from(group),
+            xproto::ModMask::default(),
+            false,
+            0,
+        )
+        .map_err(|err| os_error!(X11Error::from(err)))?;
+        self.xconn.flush_requests().map_err(|err| os_error!(X11Error::Xlib(err)))?;
+        Ok(())
+    }
+
     #[inline]
     pub fn focus_window(&self) {
         let atoms = self.xconn.atoms();
@@ -2225,13 +2898,113 @@ impl UnownedWindow {
         Ok(serial)
     }
 
+    #[inline]
+    pub fn start_drag(
+        &self,
+        contents: Vec<ClipboardData>,
+        actions: DragActions,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = AsyncRequestSerial::get();
+        self.drag_sender.send(DragRequest::Start {
+            window_id: self.id(),
+            serial,
+            contents,
+            actions,
+        });
+        Ok(serial)
+    }
+
+    #[inline]
+    pub fn set_drag_accepted(&self, accepted: Option<(String, DragAction)>) {
+        self.drag_sender.send(DragRequest::Accept { window_id: self.id(), accepted });
+    }
+
+    #[inline]
+    pub fn request_drag_data(&self, mime_type: &str) -> AsyncRequestSerial {
+        let serial = AsyncRequestSerial::get();
+        self.drag_sender.send(DragRequest::Data {
+            window_id: self.id(),
+            serial,
+            mime_type: mime_type.to_owned(),
+        });
+        serial
+    }
+
     #[inline]
     pub fn id(&self) -> WindowId {
         WindowId::from_raw(self.xwindow as _)
     }
 
-    pub(super) fn sync_counter_id(&self) -> Option<NonZeroU32> {
-        self.sync_counter_id
+    /// The window manager requested the counter to be set to `value` after the next configure.
+    pub(crate) fn sync_requested(&self, value: Int64) {
+        if self.sync_counter_id.is_some() {
+            let deadline = Instant::now() + SYNC_REQUEST_TIMEOUT;
+            self.shared_state_lock().sync_request = Some(SyncRequest::Requested(value, deadline));
+        }
+    }
+
+    /// The configure was handled, returning whether a sync request waits for the redraw.
+    pub(crate) fn sync_configured(&self) -> bool {
+        let mut shared_state = self.shared_state_lock();
+        match shared_state.sync_request {
+            Some(SyncRequest::Requested(value, _)) => {
+                shared_state.sync_request = Some(SyncRequest::Configured(value));
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// When the sync request gives up on its `ConfigureNotify`.
+    pub(crate) fn sync_deadline(&self) -> Option<Instant> {
+        match self.shared_state_lock().sync_request {
+            Some(SyncRequest::Requested(_, deadline)) => Some(deadline),
+            _ => None,
+        }
+    }
+
+    /// Answer a sync request that wasn't followed by a `ConfigureNotify` in time, since the window
+    /// manager didn't change the window and the current frame is up to date.
+    pub(crate) fn expire_sync_request(&self, now: Instant) {
+        let value = {
+            let mut shared_state = self.shared_state_lock();
+            match shared_state.sync_request {
+                Some(SyncRequest::Requested(value, deadline)) if deadline <= now => {
+                    shared_state.sync_request = None;
+                    value
+                },
+                _ => return,
+            }
+        };
+
+        self.set_sync_counter(value);
+    }
+
+    /// The window was redrawn, so tell the window manager the new frame is ready.
+    pub(crate) fn finish_sync_request(&self) {
+        let value = {
+            let mut shared_state = self.shared_state_lock();
+            match shared_state.sync_request {
+                Some(SyncRequest::Configured(value)) => {
+                    shared_state.sync_request = None;
+                    value
+                },
+                _ => return,
+            }
+        };
+
+        self.set_sync_counter(value);
+    }
+
+    fn set_sync_counter(&self, value: Int64) {
+        let Some(sync_counter_id) = self.sync_counter_id else { return };
+        self.xconn
+            .xcb_connection()
+            .sync_set_counter(sync_counter_id.get(), value)
+            .expect_then_ignore_error("Failed to set XSync counter.");
+        if let Err(err) = self.xconn.flush_requests() {
+            warn!("Failed to set XSync counter: {err}");
+        }
     }
 
     #[inline]
@@ -2241,7 +3014,28 @@ impl UnownedWindow {
 
     #[inline]
     pub fn pre_present_notify(&self) {
-        // TODO timer
+        let conn = self.xconn.xcb_connection();
+        if !matches!(conn.extension_information(present::X11_EXTENSION_NAME), Ok(Some(_))) {
+            return;
+        }
+
+        // Select the events once the first presentation is requested.
+        if !self.present_selected.swap(true, Ordering::Relaxed) {
+            let result = conn.generate_id().map_err(X11Error::from).and_then(|event_id| {
+                conn.present_select_input(
+                    event_id,
+                    self.xwindow,
+                    present::EventMask::COMPLETE_NOTIFY,
+                )?;
+                Ok(self.xconn.flush_requests()?)
+            });
+            if let Err(err) = result {
+                warn!("Failed to select Present events: {err}");
+                return;
+            }
+        }
+
+        self.presentation_requested.store(true, Ordering::Relaxed);
     }
 
     #[inline]
@@ -2272,6 +3066,58 @@ impl UnownedWindow {
 
     pub fn set_content_protected(&self, _protected: bool) {}
 
+    pub fn set_idle_inhibited(&self, inhibited: bool) -> Result<(), RequestError> {
+        let mut idle_inhibitor = self.idle_inhibitor.lock().unwrap();
+        if inhibited == idle_inhibitor.is_some() {
+            return Ok(());
+        }
+
+        if !inhibited {
+            // Dropping the D-Bus inhibitor lifts it.
+            if let Some(IdleInhibitor::Suspended) = idle_inhibitor.take() {
+                leap!(self.xconn.xcb_connection().screensaver_suspend(0)).ignore_error();
+                leap!(self.xconn.flush_requests());
+            }
+            return Ok(());
+        }
+
+        if self.screensaver_suspend_supported() {
+            leap!(self.xconn.xcb_connection().screensaver_suspend(1)).ignore_error();
+            leap!(self.xconn.flush_requests());
+            *idle_inhibitor = Some(IdleInhibitor::Suspended);
+            return Ok(());
+        }
+
+        debug!("MIT-SCREEN-SAVER is unavailable, inhibiting the screensaver over D-Bus");
+        let application = env::args_os()
+            .next()
+            .as_ref()
+            .and_then(|path| Path::new(path).file_name())
+            .and_then(|bin_name| bin_name.to_str())
+            .map(|bin_name| bin_name.to_owned())
+            .unwrap_or_else(|| String::from("winit"));
+        // This is best effort, the worker only logs the failures of the service.
+        let inhibitor = self.xconn.screensaver().inhibit(&application, "Window is active");
+        *idle_inhibitor = Some(IdleInhibitor::ScreenSaver(inhibitor));
+
+        Ok(())
+    }
+
+    /// Whether the server implements `XScreenSaverSuspend`, added in version 1.1.
+    fn screensaver_suspend_supported(&self) -> bool {
+        let conn = self.xconn.xcb_connection();
+        if !matches!(conn.extension_information(screensaver::X11_EXTENSION_NAME), Ok(Some(_))) {
+            return false;
+        }
+
+        conn.screensaver_query_version(1, 1)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|version| {
+                (version.server_major_version, version.server_minor_version) >= (1, 1)
+            })
+    }
+
     #[inline]
     pub fn has_focus(&self) -> bool {
         self.shared_state_lock().has_focus
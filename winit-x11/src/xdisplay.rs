@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::ffi::c_int;
 use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard};
+use std::sync::{Arc, Mutex, OnceLock, RwLock, RwLockReadGuard};
 use std::{fmt, ptr};
 
 use rwh_06::HasDisplayHandle;
@@ -17,6 +17,7 @@ use x11rb::xcb_ffi::XCBConnection;
 use super::atoms::Atoms;
 use super::ffi;
 use super::monitor::MonitorHandle;
+use crate::dbus;
 use crate::event_loop::X11Error;
 
 /// A connection to an X server.
@@ -63,6 +64,31 @@ pub struct XConnection {
 
     pub latest_error: Mutex<Option<XError>>,
     pub cursor_cache: Mutex<HashMap<Option<CursorIcon>, xproto::Cursor>>,
+
+    /// What the pointer grab of this client is for.
+    pub pointer_grab: Mutex<Option<GrabOwner>>,
+
+    /// What the keyboard grab of this client is for.
+    pub keyboard_grab: Mutex<Option<GrabOwner>>,
+
+    /// The `org.freedesktop.ScreenSaver` client, started on the first use.
+    screensaver: OnceLock<dbus::ScreenSaver>,
+}
+
+/// What a grab is held for.
+///
+/// A client holds at most one pointer and one keyboard grab, taking a grab replaces the previous
+/// one. The owner is tracked so a grab is only released by what took it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrabOwner {
+    /// The grab of a popup.
+    Popup(xproto::Window),
+    /// The pointer grab following an outgoing drag.
+    Drag(xproto::Window),
+    /// The pointer grab confining the cursor.
+    Cursor(xproto::Window),
+    /// The grab requested with `Window::set_keyboard_grab`.
+    Keyboard(xproto::Window),
 }
 
 impl HasDisplayHandle for XConnection {
@@ -156,6 +182,9 @@ impl XConnection {
             monitor_handles: Mutex::new(None),
             database: RwLock::new(database),
             cursor_cache: Default::default(),
+            pointer_grab: Mutex::new(None),
+            keyboard_grab: Mutex::new(None),
+            screensaver: OnceLock::new(),
             randr_version: (randr_version.major_version, randr_version.minor_version),
             render_formats: formats,
             xsettings_screen,
@@ -266,6 +295,39 @@ impl XConnection {
         }
     }
 
+    /// The `org.freedesktop.ScreenSaver` client on the session bus.
+    pub fn screensaver(&self) -> &dbus::ScreenSaver {
+        self.screensaver.get_or_init(|| dbus::ScreenSaver::new(dbus::session))
+    }
+
+    /// Release the pointer grab if it's held by `owner`.
+    pub fn ungrab_pointer_for(
+        &self,
+        owner: GrabOwner,
+        time: xproto::Timestamp,
+    ) -> Result<(), X11Error> {
+        let mut grab = self.pointer_grab.lock().unwrap();
+        if *grab == Some(owner) {
+            self.xcb_connection().ungrab_pointer(time)?.ignore_error();
+            *grab = None;
+        }
+        Ok(())
+    }
+
+    /// Release the keyboard grab if it's held by `owner`.
+    pub fn ungrab_keyboard_for(
+        &self,
+        owner: GrabOwner,
+        time: xproto::Timestamp,
+    ) -> Result<(), X11Error> {
+        let mut grab = self.keyboard_grab.lock().unwrap();
+        if *grab == Some(owner) {
+            self.xcb_connection().ungrab_keyboard(time)?.ignore_error();
+            *grab = None;
+        }
+        Ok(())
+    }
+
     /// Get the atom for Xsettings.
     #[inline]
     pub fn xsettings_screen(&self) -> Option<xproto::Atom> {
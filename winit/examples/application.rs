@@ -31,7 +31,7 @@ use winit::event::{DeviceEvent, DeviceId, MouseButton, MouseScrollDelta, WindowE
 use winit::event_loop::{ActiveEventLoop, EventLoop};
 use winit::icon::{Icon, RgbaIcon};
 use winit::keyboard::{Key, ModifiersState};
-use winit::monitor::Fullscreen;
+use winit::monitor::{Fullscreen, MonitorEvent};
 #[cfg(macos_platform)]
 use winit::platform::macos::{OptionAsAlt, WindowAttributesMacOS, WindowExtMacOS};
 #[cfg(any(x11_platform, wayland_platform))]
@@ -242,6 +242,15 @@ impl Application {
                     error!("Error creating new window: {err}");
                 }
             },
+            Action::CreateModalDialog => match self.create_window(event_loop, None) {
+                Ok(dialog_id) => {
+                    let dialog = self.windows[&dialog_id].window.as_ref();
+                    if let Err(err) = dialog.set_owner_window(Some(window_id), true) {
+                        error!("Error making the window a modal dialog: {err}");
+                    }
+                },
+                Err(err) => error!("Error creating new window: {err}"),
+            },
             Action::ToggleResizeIncrements => window.toggle_resize_increments(),
             Action::ToggleCursorVisibility => window.toggle_cursor_visibility(),
             Action::ToggleResizable => window.toggle_resizable(),
@@ -283,6 +292,7 @@ impl Application {
                 }
             },
             Action::CycleCursorGrab => window.cycle_cursor_grab(),
+            Action::ToggleKeyboardGrab => window.toggle_keyboard_grab(),
             Action::DragWindow => window.drag_window(),
             Action::DragResizeWindow => window.drag_resize_window(),
             Action::ShowWindowMenu => window.show_menu(),
@@ -467,14 +477,28 @@ impl ApplicationHandler for Application {
             WindowEvent::Occluded(occluded) => {
                 window.set_occluded(occluded);
             },
+            WindowEvent::KeyboardGrabbed(grabbed) => {
+                if grabbed {
+                    info!("Window={window_id:?} grabbed the keyboard");
+                } else {
+                    info!("Window={window_id:?} released the keyboard");
+                }
+            },
             WindowEvent::CloseRequested => {
                 info!("Closing Window={window_id:?}");
                 self.windows.remove(&window_id);
             },
+            WindowEvent::PopupDismissed => {
+                info!("Popup Window={window_id:?} dismissed");
+                self.windows.remove(&window_id);
+            },
             WindowEvent::ModifiersChanged(modifiers) => {
                 window.modifiers = modifiers.state();
                 info!("Modifiers changed to {:?}", window.modifiers);
             },
+            WindowEvent::KeyboardLayoutChanged(layouts) => {
+                info!("Keyboard layouts changed to {layouts:?}");
+            },
             WindowEvent::MouseWheel { delta, .. } => match delta {
                 MouseScrollDelta::LineDelta(x, y) => {
                     info!("Mouse wheel Line Delta: ({x},{y})");
@@ -561,7 +585,10 @@ impl ApplicationHandler for Application {
             | WindowEvent::DragEntered { .. }
             | WindowEvent::DragMoved { .. }
             | WindowEvent::DragDropped { .. }
+            | WindowEvent::DragData { .. }
+            | WindowEvent::DragSourceFinished { .. }
             | WindowEvent::Destroyed
+            | WindowEvent::FramePresented(_)
             | WindowEvent::Ime(_)
             | WindowEvent::Moved(_) => (),
         }
@@ -576,6 +603,11 @@ impl ApplicationHandler for Application {
         info!("Device {device_id:?} event: {event:?}");
     }
 
+    fn monitor_event(&mut self, event_loop: &dyn ActiveEventLoop, event: MonitorEvent) {
+        info!("Monitor event: {event:?}");
+        self.dump_monitors(event_loop);
+    }
+
     fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
         info!("Ready to create surfaces");
         self.dump_monitors(event_loop);
@@ -643,6 +675,8 @@ struct WindowState {
     occluded: bool,
     /// Current cursor grab mode.
     cursor_grab: CursorGrabMode,
+    /// Whether the keyboard grab is requested.
+    keyboard_grab: bool,
     /// The amount of zoom into window.
     zoom: f64,
     /// The amount of rotation of the window.
@@ -679,6 +713,7 @@ impl WindowState {
             option_as_alt: window.option_as_alt(),
             custom_idx: app.custom_cursors.as_ref().map(Vec::len).unwrap_or(1) - 1,
             cursor_grab: CursorGrabMode::None,
+            keyboard_grab: false,
             named_idx,
             #[cfg(not(android_platform))]
             surface,
@@ -776,6 +811,14 @@ impl WindowState {
         }
     }
 
+    fn toggle_keyboard_grab(&mut self) {
+        self.keyboard_grab = !self.keyboard_grab;
+        info!("Changing keyboard grab to {}", self.keyboard_grab);
+        if let Err(err) = self.window.set_keyboard_grab(self.keyboard_grab) {
+            error!("Error setting keyboard grab: {err}");
+        }
+    }
+
     #[cfg(macos_platform)]
     fn cycle_option_as_alt(&mut self) {
         self.option_as_alt = match self.option_as_alt {
@@ -1027,6 +1070,7 @@ enum Action {
     CloseWindow,
     ToggleCursorVisibility,
     CreateNewWindow,
+    CreateModalDialog,
     ToggleResizeIncrements,
     ToggleDecorations,
     ToggleResizable,
@@ -1044,6 +1088,7 @@ enum Action {
     #[cfg(web_platform)]
     AnimationCustomCursor,
     CycleCursorGrab,
+    ToggleKeyboardGrab,
     PrintHelp,
     DragWindow,
     DragResizeWindow,
@@ -1067,6 +1112,7 @@ impl Action {
             Action::CloseWindow => "Close window",
             Action::ToggleCursorVisibility => "Hide cursor",
             Action::CreateNewWindow => "Create new window",
+            Action::CreateModalDialog => "Create a modal dialog of the window",
             Action::ToggleDecorations => "Toggle decorations",
             Action::ToggleResizable => "Toggle window resizable state",
             Action::ToggleFullscreen => "Toggle fullscreen",
@@ -1084,6 +1130,7 @@ impl Action {
             #[cfg(web_platform)]
             Action::AnimationCustomCursor => "Custom cursor from an animation",
             Action::CycleCursorGrab => "Cycle through cursor grab mode",
+            Action::ToggleKeyboardGrab => "Toggle keyboard grab",
             Action::PrintHelp => "Print help",
             Action::DragWindow => "Start window drag",
             Action::DragResizeWindow => "Start window drag-resize",
@@ -1291,6 +1338,7 @@ const KEY_BINDINGS: &[Binding<&'static str>] = &[
     Binding::new("F", ModifiersState::ALT, Action::ToggleSimpleFullscreen),
     Binding::new("D", ModifiersState::CONTROL, Action::ToggleDecorations),
     Binding::new("L", ModifiersState::CONTROL, Action::CycleCursorGrab),
+    Binding::new("G", ModifiersState::CONTROL, Action::ToggleKeyboardGrab),
     Binding::new("P", ModifiersState::CONTROL, Action::ToggleResizeIncrements),
     Binding::new("R", ModifiersState::CONTROL, Action::ToggleResizable),
     Binding::new("R", ModifiersState::ALT, Action::RequestResize),
@@ -1301,6 +1349,7 @@ const KEY_BINDINGS: &[Binding<&'static str>] = &[
     Binding::new("M", ModifiersState::ALT, Action::Minimize),
     // N.
     Binding::new("N", ModifiersState::CONTROL, Action::CreateNewWindow),
+    Binding::new("N", ModifiersState::ALT, Action::CreateModalDialog),
     // C.
     Binding::new("C", ModifiersState::CONTROL, Action::NextCursor),
     Binding::new("C", ModifiersState::ALT, Action::NextCustomCursor),
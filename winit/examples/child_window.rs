@@ -1,4 +1,4 @@
-#[cfg(any(x11_platform, macos_platform, windows_platform))]
+#[cfg(any(x11_platform, wayland_platform, macos_platform, windows_platform))]
 #[allow(deprecated)]
 fn main() -> Result<(), impl std::error::Error> {
     use std::collections::HashMap;
@@ -120,10 +120,10 @@ fn main() -> Result<(), impl std::error::Error> {
     event_loop.run_app(Application::default())
 }
 
-#[cfg(not(any(x11_platform, macos_platform, windows_platform)))]
+#[cfg(not(any(x11_platform, wayland_platform, macos_platform, windows_platform)))]
 fn main() {
     panic!(
-        "This example is supported only on x11, macOS, and Windows, with the `rwh_06` feature \
-         enabled."
+        "This example is supported only on X11, Wayland, macOS, and Windows, with the `rwh_06` \
+         feature enabled."
     );
 }
@@ -1,6 +1,8 @@
 use std::error::Error;
 
 use winit::application::ApplicationHandler;
+use winit::clipboard::TEXT_MIME_TYPE;
+use winit::drag::DragAction;
 use winit::event::WindowEvent;
 use winit::event_loop::{ActiveEventLoop, EventLoop};
 use winit::window::{Window, WindowAttributes, WindowId};
@@ -23,18 +25,20 @@ fn main() -> Result<(), Box<dyn Error>> {
 #[derive(Debug)]
 struct Application {
     window: Option<Box<dyn Window>>,
+    /// Whether the text being dragged was accepted.
+    text_accepted: bool,
 }
 
 impl Application {
     fn new() -> Self {
-        Self { window: None }
+        Self { window: None, text_accepted: false }
     }
 }
 
 impl ApplicationHandler for Application {
     fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
         let window_attributes =
-            WindowAttributes::default().with_title("Drag and drop files on me!");
+            WindowAttributes::default().with_title("Drag and drop files or text on me!");
         self.window = Some(event_loop.create_window(window_attributes).unwrap());
     }
 
@@ -45,10 +49,30 @@ impl ApplicationHandler for Application {
         event: WindowEvent,
     ) {
         match event {
-            WindowEvent::DragLeft { .. }
-            | WindowEvent::DragEntered { .. }
-            | WindowEvent::DragMoved { .. }
-            | WindowEvent::DragDropped { .. } => {
+            WindowEvent::DragEntered { ref paths, ref mime_types, .. } => {
+                println!("{event:?}");
+
+                // Files are accepted by default, accept text as well.
+                let window = self.window.as_ref().unwrap();
+                self.text_accepted = paths.is_empty()
+                    && mime_types.iter().any(|mime_type| mime_type == TEXT_MIME_TYPE)
+                    && window.accept_drag(TEXT_MIME_TYPE, DragAction::Copy).is_ok();
+            },
+            WindowEvent::DragDropped { .. } => {
+                println!("{event:?}");
+
+                if self.text_accepted {
+                    let window = self.window.as_ref().unwrap();
+                    if let Err(err) = window.request_drag_data(TEXT_MIME_TYPE) {
+                        println!("Failed to request the dropped text: {err}");
+                    }
+                }
+            },
+            WindowEvent::DragData { result, .. } => match result {
+                Ok(data) => println!("Dropped text: {:?}", data.to_text()),
+                Err(err) => println!("Failed to read the dropped text: {err}"),
+            },
+            WindowEvent::DragLeft { .. } | WindowEvent::DragMoved { .. } => {
                 println!("{event:?}");
             },
             WindowEvent::RedrawRequested => {
@@ -1,10 +1,14 @@
 #![cfg(feature = "serde")]
 
 use serde::{Deserialize, Serialize};
+use winit::clipboard::ClipboardEvent;
 use winit::cursor::CursorIcon;
 use winit::dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize};
-use winit::event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase};
+use winit::event::{
+    DeviceEvent, ElementState, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
+};
 use winit::keyboard::{Key, KeyCode, KeyLocation, ModifiersState, NamedKey, PhysicalKey};
+use winit::record::{Trace, WindowRequest};
 
 #[allow(dead_code)]
 fn needs_serde<S: Serialize + Deserialize<'static>>() {}
@@ -26,6 +30,15 @@ fn events_serde() {
     needs_serde::<PhysicalKey>();
     needs_serde::<KeyLocation>();
     needs_serde::<ModifiersState>();
+    needs_serde::<WindowEvent>();
+    needs_serde::<DeviceEvent>();
+    needs_serde::<ClipboardEvent>();
+}
+
+#[test]
+fn record_serde() {
+    needs_serde::<Trace>();
+    needs_serde::<WindowRequest>();
 }
 
 #[test]